@@ -18,6 +18,12 @@
 //!
 //! [xkcd]: https://xkcd.com/927/
 //!
+//! OLPC's specification also normalizes strings to Unicode Normalization Form C (NFC), which
+//! `CanonicalFormatter::new` does. The TUF reference implementation (securesystemslib) and
+//! go-securesystemslib instead write strings exactly as given, escaping only backslashes and
+//! double quotes; use `CanonicalFormatter::without_normalization` to produce the same bytes they
+//! sign.
+//!
 //! ```rust
 //! use olpc_cjson::CanonicalFormatter;
 //! use serde::Serialize;
@@ -45,9 +51,19 @@ use unicode_normalization::UnicodeNormalization;
 /// See the [crate-level documentation](../index.html) for more detail.
 ///
 /// [`Formatter`]: ../serde_json/ser/trait.Formatter.html
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct CanonicalFormatter {
     object_stack: Vec<Object>,
+    normalize: bool,
+}
+
+impl Default for CanonicalFormatter {
+    fn default() -> Self {
+        Self {
+            object_stack: Vec::new(),
+            normalize: true,
+        }
+    }
 }
 
 /// Internal struct to keep track of an object in progress of being built.
@@ -78,11 +94,21 @@ struct Object {
 }
 
 impl CanonicalFormatter {
-    /// Create a new `CanonicalFormatter` object.
+    /// Create a new `CanonicalFormatter` object, which normalizes strings as NFC.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Create a new `CanonicalFormatter` object that writes strings as given, without normalizing
+    /// them, as securesystemslib and go-securesystemslib do. Strings that are already NFC are
+    /// written the same way by either formatter.
+    pub fn without_normalization() -> Self {
+        Self {
+            normalize: false,
+            ..Self::default()
+        }
+    }
+
     /// Convenience method to return the appropriate writer given the current context.
     ///
     /// If we are currently writing an object (that is, if `!self.object_stack.is_empty()`), we
@@ -173,13 +199,16 @@ impl Formatter for CanonicalFormatter {
     wrapper!(begin_string);
     wrapper!(end_string);
 
-    // Strings are normalized as Normalization Form C (NFC). `str::nfc` is provided by the
-    // `UnicodeNormalization` trait and returns an iterator of `char`s.
+    // Unless disabled, strings are normalized as Normalization Form C (NFC). `str::nfc` is provided
+    // by the `UnicodeNormalization` trait and returns an iterator of `char`s.
     fn write_string_fragment<W: Write + ?Sized>(
         &mut self,
         writer: &mut W,
         fragment: &str,
     ) -> Result<()> {
+        if !self.normalize {
+            return self.writer(writer).write_all(fragment.as_bytes());
+        }
         fragment.nfc().try_for_each(|ch| {
             self.writer(writer)
                 .write_all(ch.encode_utf8(&mut [0; 4]).as_bytes())
@@ -283,7 +312,11 @@ impl Formatter for CanonicalFormatter {
         writer: &mut W,
         fragment: &str,
     ) -> Result<()> {
-        let mut ser = Serializer::with_formatter(self.writer(writer), Self::new());
+        let formatter = Self {
+            normalize: self.normalize,
+            ..Self::default()
+        };
+        let mut ser = Serializer::with_formatter(self.writer(writer), formatter);
         serde_json::from_str::<serde_json::Value>(fragment)?.serialize(&mut ser)?;
         Ok(())
     }
@@ -308,6 +341,21 @@ mod tests {
         };
     }
 
+    /// Like `encode!`, without normalizing strings.
+    macro_rules! encode_unnormalized {
+        ($($tt:tt)+) => {
+            (|v: serde_json::Value| -> Result<Vec<u8>> {
+                let mut buf = Vec::new();
+                let mut ser = Serializer::with_formatter(
+                    &mut buf,
+                    CanonicalFormatter::without_normalization(),
+                );
+                v.serialize(&mut ser)?;
+                Ok(buf)
+            })(serde_json::json!($($tt)+))
+        };
+    }
+
     /// These smoke tests come from securesystemslib, the library used by the TUF reference
     /// implementation.
     ///
@@ -397,6 +445,26 @@ mod tests {
         Ok(())
     }
 
+    /// Without normalization, non-ASCII strings are written exactly as given. securesystemslib's
+    /// `encode_canonical` and go-securesystemslib do the same, escaping only backslashes and double
+    /// quotes. Keys sort by code point, which is the order of their UTF-8 bytes.
+    ///
+    /// tough/tests/data/canonical-interop checks a larger document against the output of
+    /// `encode_canonical`.
+    #[test]
+    fn unicode_strings() -> Result<()> {
+        let expected = b"{\"a\":\"\xf0\x9f\x98\x80\\\"\",\"caf\xc3\xa9\":\"e\xcc\x81\",\"z\":\"\x01\xe2\x98\x83\\\\\"}";
+        let value = || serde_json::json!({"caf\u{e9}": "e\u{301}", "z": "\x01\u{2603}\\", "a": "\u{1f600}\""});
+        assert_eq!(encode_unnormalized!(value())?, expected.to_vec());
+
+        // The decomposed "e\u{301}" is composed as "\u{e9}" only when normalizing
+        assert_eq!(encode_unnormalized!("e\u{301}")?, b"\"e\xcc\x81\"");
+        assert_eq!(encode!("e\u{301}")?, b"\"\xc3\xa9\"");
+        assert_eq!(encode_unnormalized!("\u{e9}")?, encode!("\u{e9}")?);
+
+        Ok(())
+    }
+
     /// A more involved test than any of the above for olpc-cjson's core competency: ordering
     /// things.
     #[test]
@@ -29,8 +29,8 @@ use snafu::{ensure, OptionExt, ResultExt};
 use std::collections::HashMap;
 use std::fmt;
 use tough::key_source::KeySource;
-use tough::schema::decoded::{Decoded, RsaPem};
-use tough::schema::key::{Key, RsaKey, RsaScheme};
+use tough::schema::decoded::{Decoded, EcdsaPem, RsaPem};
+use tough::schema::key::{EcdsaKey, EcdsaScheme, Key, RsaKey, RsaScheme};
 use tough::sign::Sign;
 
 /// Represents a Signing Algorithms for AWS KMS.
@@ -39,14 +39,15 @@ use tough::sign::Sign;
 pub enum KmsSigningAlgorithm {
     /// Signing Algorithm `RSASSA_PSS_SHA_256`
     RsassaPssSha256,
+    /// Signing Algorithm `ECDSA_SHA_256`, for `ECC_NIST_P256` keys
+    EcdsaSha256,
 }
 
 impl KmsSigningAlgorithm {
     fn value(self) -> String {
-        // Currently we are supporting only single algorithm, but code stub is added to support
-        // multiple algorithms in future.
         String::from(match self {
             KmsSigningAlgorithm::RsassaPssSha256 => "RSASSA_PSS_SHA_256",
+            KmsSigningAlgorithm::EcdsaSha256 => "ECDSA_SHA_256",
         })
     }
 }
@@ -59,7 +60,9 @@ pub struct KmsKeySource {
     pub key_id: String,
     /// KmsClient Object to query AWS KMS
     pub client: Option<KmsClient>,
-    /// Signing Algorithm to be used for the message digest, only `KmsSigningAlgorithm::RsassaPssSha256` is supported at present.
+    /// Signing Algorithm to be used for the message digest. It must be one the key supports:
+    /// `KmsSigningAlgorithm::RsassaPssSha256` for RSA keys, or `KmsSigningAlgorithm::EcdsaSha256`
+    /// for `ECC_NIST_P256` keys.
     pub signing_algorithm: KmsSigningAlgorithm,
 }
 
@@ -110,19 +113,33 @@ impl KeySource for KmsKeySource {
                 .contains(&self.signing_algorithm.value()),
             error::ValidSignAlgorithm
         );
-        Ok(Box::new(KmsRsaKey {
-            profile: self.profile.clone(),
-            client: Some(kms_client.clone()),
-            key_id: self.key_id.clone(),
-            public_key: key.parse().context(error::PublicKeyParse)?,
-            signing_algorithm: self.signing_algorithm,
-            modulus_size_bytes: parse_modulus_length_bytes(
-                response
-                    .customer_master_key_spec
-                    .as_ref()
-                    .context(error::MissingCustomerMasterKeySpec)?,
-            )?,
-        }))
+        let spec = response
+            .customer_master_key_spec
+            .as_ref()
+            .context(error::MissingCustomerMasterKeySpec)?;
+        Ok(match self.signing_algorithm {
+            KmsSigningAlgorithm::RsassaPssSha256 => Box::new(KmsRsaKey {
+                profile: self.profile.clone(),
+                client: Some(kms_client),
+                key_id: self.key_id.clone(),
+                public_key: key.parse().context(error::PublicKeyParse)?,
+                signing_algorithm: self.signing_algorithm,
+                modulus_size_bytes: parse_modulus_length_bytes(spec)?,
+            }),
+            KmsSigningAlgorithm::EcdsaSha256 => {
+                // only the P-256 curve has a TUF signature scheme
+                ensure!(
+                    spec == "ECC_NIST_P256",
+                    error::BadCustomerMasterKeySpec { spec }
+                );
+                Box::new(KmsEcdsaKey {
+                    profile: self.profile.clone(),
+                    client: Some(kms_client),
+                    key_id: self.key_id.clone(),
+                    public_key: key.parse().context(error::PublicKeyParse)?,
+                })
+            }
+        })
     }
 
     fn write(
@@ -178,42 +195,105 @@ impl Sign for KmsRsaKey {
         msg: &[u8],
         _rng: &dyn SecureRandom,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        let kms_client = match self.client.to_owned() {
-            Some(value) => value,
-            None => client::build_client_kms(self.profile.as_deref())?,
-        };
-        let sign_fut = kms_client.sign(SignRequest {
-            key_id: self.key_id.clone(),
-            message: digest(&SHA256, msg).as_ref().to_vec().into(),
-            message_type: Some(String::from("DIGEST")),
-            signing_algorithm: self.signing_algorithm.value(),
-            ..rusoto_kms::SignRequest::default()
-        });
-        let response = tokio::runtime::Runtime::new()
-            .context(error::RuntimeCreation)?
-            .block_on(sign_fut)
-            .context(error::KmsSignMessage {
-                profile: self.profile.clone(),
-                key_id: self.key_id.clone(),
-            })?;
-        let signature = response
-            .signature
-            .context(error::SignatureNotFound)?
-            .to_vec();
+        let signature = kms_sign(
+            self.client.as_ref(),
+            self.profile.as_deref(),
+            &self.key_id,
+            self.signing_algorithm,
+            msg,
+        )?;
 
         // sometimes KMS produces a signature that is shorter than the modulus. in those cases,
         // we have observed that openssl and KMS will both validate the signature, but ring will
         // not. if we pad the beginning of the signature with zeros to make the signature exactly
         // the same length as the modulus, then ring will verify the signature.
-        let signature = match &self.signing_algorithm {
-            KmsSigningAlgorithm::RsassaPssSha256 => {
-                pad_signature(signature, self.modulus_size_bytes)?
-            }
-        };
-        Ok(signature)
+        Ok(pad_signature(signature, self.modulus_size_bytes)?)
+    }
+}
+
+/// Implements the Sign trait for KMS ecdsa Key
+pub struct KmsEcdsaKey {
+    /// Key Id of Customer Managed Key in KMS used to sign the message
+    key_id: String,
+    /// Aws account profile
+    profile: Option<String>,
+    /// KmsClient Object to query AWS KMS
+    client: Option<KmsClient>,
+    /// Public Key corresponding to Customer Managed Key
+    public_key: Decoded<EcdsaPem>,
+}
+
+impl fmt::Debug for KmsEcdsaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KmsEcdsaKey")
+            .field("key_id", &self.key_id)
+            .field("public_key", &self.public_key)
+            .finish()
     }
 }
 
+impl Sign for KmsEcdsaKey {
+    fn tuf_key(&self) -> Key {
+        // Create a Key struct for the public key
+        Key::Ecdsa {
+            keyval: EcdsaKey {
+                public: self.public_key.to_owned(),
+                _extra: HashMap::new(),
+            },
+            scheme: EcdsaScheme::EcdsaSha2Nistp256,
+            _extra: HashMap::new(),
+        }
+    }
+
+    fn sign(
+        &self,
+        msg: &[u8],
+        _rng: &dyn SecureRandom,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        // KMS returns ECDSA signatures DER-encoded, which is the form TUF expects
+        Ok(kms_sign(
+            self.client.as_ref(),
+            self.profile.as_deref(),
+            &self.key_id,
+            KmsSigningAlgorithm::EcdsaSha256,
+            msg,
+        )?)
+    }
+}
+
+/// Asks AWS KMS to sign the SHA-256 digest of `msg` with the key `key_id`, and returns the
+/// signature.
+fn kms_sign(
+    client: Option<&KmsClient>,
+    profile: Option<&str>,
+    key_id: &str,
+    signing_algorithm: KmsSigningAlgorithm,
+    msg: &[u8],
+) -> error::Result<Vec<u8>> {
+    let kms_client = match client {
+        Some(value) => value.clone(),
+        None => client::build_client_kms(profile)?,
+    };
+    let sign_fut = kms_client.sign(SignRequest {
+        key_id: key_id.to_owned(),
+        message: digest(&SHA256, msg).as_ref().to_vec().into(),
+        message_type: Some(String::from("DIGEST")),
+        signing_algorithm: signing_algorithm.value(),
+        ..rusoto_kms::SignRequest::default()
+    });
+    let response = tokio::runtime::Runtime::new()
+        .context(error::RuntimeCreation)?
+        .block_on(sign_fut)
+        .context(error::KmsSignMessage {
+            profile: profile.map(str::to_owned),
+            key_id,
+        })?;
+    Ok(response
+        .signature
+        .context(error::SignatureNotFound)?
+        .to_vec())
+}
+
 /// Parses the `CustomerMasterKeySpec` string returned by KMS, e.g. `RSA_3072` and returns the size
 /// of the modulus in bytes. For example `RSA_3072` has a modulus of 3072 bits, so the function will
 /// return 384 == (3072 / 8). If the parsed number is not divisible by 8, an error is returned.
@@ -5,6 +5,7 @@ use rusoto_kms::KmsClient;
 extern crate rusoto_mock;
 use self::rusoto_mock::*;
 use ring::rand::SystemRandom;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1};
 use rusoto_core::signature::SignedRequest;
 use rusoto_core::{HttpDispatchError, Region};
 use serde::{Deserialize, Deserializer};
@@ -14,7 +15,7 @@ use tough::key_source::KeySource;
 use tough::schema::decoded::{Decoded, RsaPem};
 use tough::schema::key::Key;
 use tough_kms::KmsKeySource;
-use tough_kms::KmsSigningAlgorithm::RsassaPssSha256;
+use tough_kms::KmsSigningAlgorithm::{EcdsaSha256, RsassaPssSha256};
 
 /// Deserialize base64 to `bytes::Bytes`
 fn de_bytes<'de, D>(deserializer: D) -> Result<bytes::Bytes, D::Error>
@@ -155,6 +156,91 @@ fn check_sign_success() {
     assert_eq!(signature, expected_signature);
 }
 
+#[test]
+// Ensure an ECDSA key's public key is returned on calling tuf_key, and that the signature KMS
+// returns verifies against it
+fn check_ecdsa_sign_success() {
+    let resp_public_key = "response_public_key_ecdsa.json";
+    let resp_signature = "response_signature_ecdsa.json";
+    let file = File::open(test_utils::test_data().join("expected_public_key_ecdsa.json")).unwrap();
+    let expected_key: Key = serde_json::from_reader(BufReader::new(file)).unwrap();
+    let mock = MultipleMockRequestDispatcher::new(vec![
+        MockRequestDispatcher::with_status(200)
+            .with_request_checker(|request: &SignedRequest| {
+                assert!(request
+                    .headers
+                    .get("x-amz-target")
+                    .unwrap()
+                    .contains(&Vec::from("TrentService.GetPublicKey")));
+            })
+            .with_body(
+                MockResponseReader::read_response(
+                    test_utils::test_data().to_str().unwrap(),
+                    resp_public_key,
+                )
+                .as_ref(),
+            ),
+        MockRequestDispatcher::with_status(200)
+            .with_request_checker(|request: &SignedRequest| {
+                assert!(request
+                    .headers
+                    .get("x-amz-target")
+                    .unwrap()
+                    .contains(&Vec::from("TrentService.Sign")));
+            })
+            .with_body(
+                MockResponseReader::read_response(
+                    test_utils::test_data().to_str().unwrap(),
+                    resp_signature,
+                )
+                .as_ref(),
+            ),
+    ]);
+    let mock_client = KmsClient::new_with(mock, MockCredentialsProvider, Region::UsEast1);
+    let kms_key = KmsKeySource {
+        profile: None,
+        key_id: String::from("alias/some_alias"),
+        client: Some(mock_client),
+        signing_algorithm: EcdsaSha256,
+    };
+    let rng = SystemRandom::new();
+    let kms_sign = kms_key.as_sign().unwrap();
+    let key = kms_sign.tuf_key();
+    assert_eq!(key, expected_key);
+    let public = match key {
+        Key::Ecdsa { keyval, .. } => keyval.public,
+        key => panic!("expected an ECDSA key, got {:?}", key),
+    };
+
+    let msg = "Some message to sign".as_bytes();
+    let signature = kms_sign.sign(msg, &rng).unwrap();
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, &public)
+        .verify(msg, &signature)
+        .unwrap();
+}
+
+#[test]
+// Ensure call to as_sign fails when an ECDSA signing algorithm is given for an RSA key
+fn check_ecdsa_algo_rsa_key() {
+    let input = "response_public_key.json";
+    let mock = MockRequestDispatcher::default().with_body(
+        MockResponseReader::read_response(test_utils::test_data().to_str().unwrap(), input)
+            .as_ref(),
+    );
+    let mock_client = KmsClient::new_with(mock, MockCredentialsProvider, Region::UsEast1);
+    let kms_key = KmsKeySource {
+        profile: None,
+        key_id: String::from("alias/some_alias"),
+        client: Some(mock_client),
+        signing_algorithm: EcdsaSha256,
+    };
+    let err = kms_key.as_sign().err().unwrap();
+    assert_eq!(
+        String::from("Please provide valid signing algorithm"),
+        err.to_string()
+    );
+}
+
 #[test]
 // Ensure call to tuf_key fails when public key is not available
 fn check_public_key_failure() {
@@ -8,26 +8,34 @@ use rusoto_ssm::SsmClient;
 use snafu::ResultExt;
 use std::str::FromStr;
 
-/// Builds an SSM client for a given profile name.
-pub(crate) fn build_client(profile: Option<&str>) -> Result<SsmClient> {
+/// Builds an SSM client for a given profile name. If `region` is given, it's used instead of the
+/// profile's region.
+pub(crate) fn build_client(profile: Option<&str>, region: Option<&str>) -> Result<SsmClient> {
+    let region = region
+        .map(|region| Region::from_str(region).context(error::RusotoRegion { region }))
+        .transpose()?;
     Ok(if let Some(profile) = profile {
         let mut provider = ProfileProvider::new().context(error::RusotoCreds)?;
         provider.set_profile(profile);
-        let region = provider
-            .region_from_profile()
-            .context(error::RusotoRegionFromProfile { profile })?;
-
-        SsmClient::new_with(
-            HttpClient::new().context(error::RusotoTls)?,
-            provider,
-            match region {
+        let region = match region {
+            Some(region) => region,
+            None => match provider
+                .region_from_profile()
+                .context(error::RusotoRegionFromProfile { profile })?
+            {
                 Some(region) => {
                     Region::from_str(&region).context(error::RusotoRegion { region })?
                 }
                 None => Region::default(),
             },
+        };
+
+        SsmClient::new_with(
+            HttpClient::new().context(error::RusotoTls)?,
+            provider,
+            region,
         )
     } else {
-        SsmClient::new(Region::default())
+        SsmClient::new(region.unwrap_or_default())
     })
 }
@@ -10,9 +10,13 @@ use tough::key_source::KeySource;
 use tough::sign::{parse_keypair, Sign};
 
 /// Implements the KeySource trait for keys that live in AWS SSM.
+///
+/// The SSM client uses `region` if it's given, and otherwise the region of `profile`, falling back
+/// to the region from the environment.
 #[derive(Debug)]
 pub struct SsmKeySource {
     pub profile: Option<String>,
+    pub region: Option<String>,
     pub parameter_name: String,
     pub key_id: Option<String>,
 }
@@ -23,7 +27,7 @@ impl KeySource for SsmKeySource {
         &self,
     ) -> std::result::Result<Box<dyn Sign>, Box<dyn std::error::Error + Send + Sync + 'static>>
     {
-        let ssm_client = client::build_client(self.profile.as_deref())?;
+        let ssm_client = client::build_client(self.profile.as_deref(), self.region.as_deref())?;
         let fut = ssm_client.get_parameter(rusoto_ssm::GetParameterRequest {
             name: self.parameter_name.to_owned(),
             with_decryption: Some(true),
@@ -57,7 +61,7 @@ impl KeySource for SsmKeySource {
         value: &str,
         key_id_hex: &str,
     ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-        let ssm_client = client::build_client(self.profile.as_deref())?;
+        let ssm_client = client::build_client(self.profile.as_deref(), self.region.as_deref())?;
         let fut = ssm_client.put_parameter(rusoto_ssm::PutParameterRequest {
             name: self.parameter_name.to_owned(),
             description: Some(key_id_hex.to_owned()),
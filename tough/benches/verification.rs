@@ -0,0 +1,146 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Baselines for the hot paths of loading a repository: signature verification, canonical JSON
+//! serialization and target hashing.
+//!
+//! Run with `cargo bench -p tough`.
+
+use chrono::{Duration, Utc};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::num::NonZeroU64;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use tough::editor::signed::PathExists;
+use tough::editor::RepositoryEditor;
+use tough::key_source::LocalKeySource;
+use tough::schema::decoded::Decoded;
+use tough::schema::{Hashes, Role, Root, Signed, Target, Targets};
+use tough::RepositoryLoader;
+use url::Url;
+
+/// Number of targets in the generated `Targets` role.
+const TARGETS_COUNT: usize = 10_000;
+
+/// Size of the generated target.
+const TARGET_SIZE: usize = 64 * 1024 * 1024;
+
+fn test_data() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+}
+
+fn verify_root(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify root");
+    for (name, path) in &[
+        ("rsa", test_data().join("simple-rsa").join("root.json")),
+        (
+            "ed25519",
+            test_data()
+                .join("tuf-reference-impl")
+                .join("metadata")
+                .join("root.json"),
+        ),
+    ] {
+        let root: Signed<Root> = serde_json::from_reader(File::open(path).unwrap()).unwrap();
+        group.bench_function(*name, |b| {
+            b.iter(|| root.signed.verify_role(&root).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn canonical_targets(c: &mut Criterion) {
+    let mut targets = Targets::new(
+        "1.0.0".to_owned(),
+        NonZeroU64::new(1).unwrap(),
+        Utc::now() + Duration::days(7),
+    );
+    for i in 0..TARGETS_COUNT {
+        targets.targets.insert(
+            format!("target-{}.txt", i),
+            Target {
+                length: i as u64,
+                hashes: Hashes {
+                    sha256: Decoded::from(vec![(i % 256) as u8; 32]),
+                    _extra: HashMap::new(),
+                },
+                custom: HashMap::new(),
+                _extra: HashMap::new(),
+            },
+        );
+    }
+
+    let mut group = c.benchmark_group("canonical json");
+    group.throughput(Throughput::Elements(TARGETS_COUNT as u64));
+    group.bench_function("targets", |b| b.iter(|| targets.canonical_form().unwrap()));
+    group.finish();
+}
+
+fn read_target(c: &mut Criterion) {
+    // Create a repository containing a single large target.
+    let dir = TempDir::new().unwrap();
+    let indir = dir.path().join("in");
+    let metadata_dir = dir.path().join("metadata");
+    let targets_dir = dir.path().join("targets");
+    std::fs::create_dir(&indir).unwrap();
+    let target_path = indir.join("large.bin");
+    let mut file = File::create(&target_path).unwrap();
+    let chunk = vec![0x5a_u8; 1024 * 1024];
+    for _ in 0..TARGET_SIZE / chunk.len() {
+        file.write_all(&chunk).unwrap();
+    }
+    drop(file);
+
+    let root = test_data().join("simple-rsa").join("root.json");
+    let expires = Utc::now() + Duration::days(7);
+    let one = NonZeroU64::new(1).unwrap();
+    let mut editor = RepositoryEditor::new(&root).unwrap();
+    editor
+        .targets_version(one)
+        .unwrap()
+        .targets_expires(expires)
+        .unwrap()
+        .snapshot_version(one)
+        .snapshot_expires(expires)
+        .timestamp_version(one)
+        .timestamp_expires(expires)
+        .add_target_path(&target_path)
+        .unwrap();
+    let signed = editor
+        .sign(&[Box::new(LocalKeySource {
+            path: test_data().join("snakeoil.pem"),
+        })])
+        .unwrap();
+    signed.write(&metadata_dir).unwrap();
+    signed
+        .link_targets(&indir, &targets_dir, PathExists::Skip)
+        .unwrap();
+
+    let repo = RepositoryLoader::new(
+        File::open(&root).unwrap(),
+        Url::from_directory_path(&metadata_dir).unwrap(),
+        Url::from_directory_path(&targets_dir).unwrap(),
+    )
+    .load()
+    .unwrap();
+
+    let mut group = c.benchmark_group("read target");
+    group.throughput(Throughput::Bytes(TARGET_SIZE as u64));
+    group.sample_size(10);
+    group.bench_function("sha256", |b| {
+        b.iter_batched(
+            || repo.read_target("large.bin").unwrap().unwrap(),
+            |mut reader| std::io::copy(&mut reader, &mut std::io::sink()).unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, verify_root, canonical_targets, read_target);
+criterion_main!(benches);
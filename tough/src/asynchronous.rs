@@ -0,0 +1,110 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides async wrappers around the client for use from a tokio runtime.
+//!
+//! Loading a repository and reading its targets block on file and network I/O. Calling
+//! [`RepositoryLoader::load`] or reading from [`Repository::read_target`] on an async worker
+//! thread stalls every other task scheduled on it. [`RepositoryLoader::load_async`] and
+//! [`Repository::read_target_stream`] instead run that work on tokio's blocking thread pool, so
+//! they can be awaited directly.
+//!
+//! Verification is unchanged. As with [`Repository::read_target`], the target is only known to be
+//! valid once the returned [`TargetStream`] has ended without yielding an error.
+//!
+//! The `async` feature enables this module. It requires a tokio runtime.
+
+use crate::error::{self, Result};
+use crate::{Repository, RepositoryLoader};
+use bytes::Bytes;
+use futures_core::Stream;
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// The size of each chunk of target data yielded by a [`TargetStream`].
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of chunks a [`TargetStream`] reads ahead of its consumer.
+const CHANNEL_CAPACITY: usize = 4;
+
+impl<R: Read + Send + 'static> RepositoryLoader<R> {
+    /// Loads and verifies TUF repository metadata, as [`RepositoryLoader::load`] does, on tokio's
+    /// blocking thread pool.
+    pub async fn load_async(self) -> Result<Repository> {
+        blocking(move || self.load()).await?
+    }
+}
+
+impl Repository {
+    /// Fetches a target from the repository, as [`Repository::read_target`] does, and returns a
+    /// stream of its contents. The target is fetched and read on tokio's blocking thread pool, which
+    /// shares the repository through the `Arc` rather than copying it.
+    ///
+    /// If the requested target is not listed in the repository metadata, `Ok(None)` is returned.
+    ///
+    /// If the stream yields an error, such as when the target doesn't match its hash, the target
+    /// must not be used, and the stream ends.
+    pub async fn read_target_stream(self: Arc<Self>, name: &str) -> Result<Option<TargetStream>> {
+        let name = name.to_owned();
+        let reader = match blocking(move || self.read_target(&name)).await?? {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        tokio::task::spawn_blocking(move || send_chunks(reader, &tx));
+        Ok(Some(TargetStream { rx }))
+    }
+}
+
+/// A stream of the verified contents of a target, returned by
+/// [`Repository::read_target_stream`].
+#[derive(Debug)]
+pub struct TargetStream {
+    rx: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+impl Stream for TargetStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Runs `f` on tokio's blocking thread pool, resuming its panic, if any, in the caller. Fails if
+/// the task is cancelled, such as when the runtime is shutting down.
+async fn blocking<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => Ok(value),
+        Err(err) if err.is_cancelled() => error::BlockingTaskCancelled.fail(),
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Reads `reader` to the end, sending each chunk to `tx`. Stops after the first error, or once the
+/// receiving [`TargetStream`] is dropped.
+fn send_chunks(mut reader: impl Read, tx: &mpsc::Sender<io::Result<Bytes>>) {
+    loop {
+        let mut buf = vec![0; CHUNK_SIZE];
+        let chunk = match reader.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                buf.truncate(n);
+                Ok(Bytes::from(buf))
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => Err(err),
+        };
+        let failed = chunk.is_err();
+        if tx.blocking_send(chunk).is_err() || failed {
+            return;
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides a [`Transport`] that serves repository metadata from a single JSON bundle.
+//!
+//! Some clients cannot easily make a separate request for each metadata file. For these clients,
+//! all of a repository's metadata can be shipped as one JSON object whose members are named by
+//! role:
+//!
+//! ```json
+//! { "root": {...}, "timestamp": {...}, "snapshot": {...}, "targets": {...} }
+//! ```
+//!
+//! Delegated targets roles are included as members named after the role. Intermediate root
+//! versions, needed when the client's trusted root is more than one version behind, are included
+//! as members named `{version}.root`.
+//!
+//! A [`MetadataBundle`] is used as the transport of a [`RepositoryLoader`], which runs the usual
+//! verification chain over the bundle's members. Any metadata base URL may be given to the loader;
+//! only the file name is used to pick a member.
+//!
+//! The snapshot and timestamp roles record the hashes of the metadata files they describe, so each
+//! member must be embedded verbatim. The bytes served for a member are exactly those in the bundle
+//! from the start of its value up to (not including) the `,` or `}` that follows it. A member can
+//! therefore keep the trailing newline of the file it was copied from.
+//!
+//! [`RepositoryLoader`]: crate::RepositoryLoader
+
+use crate::error::{self, Result};
+use crate::schema::{Root, Signed};
+use crate::transport::{Transport, TransportError, TransportErrorKind};
+use serde_json::value::RawValue;
+use snafu::ResultExt;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::ops::Range;
+use std::sync::Arc;
+use url::Url;
+
+/// Repository metadata loaded from a single JSON bundle. See the [module documentation] for the
+/// bundle format.
+///
+/// [module documentation]: crate::bundle
+#[derive(Debug, Clone)]
+pub struct MetadataBundle {
+    text: Arc<str>,
+    members: HashMap<String, Range<usize>>,
+    root_version: Option<u64>,
+}
+
+impl MetadataBundle {
+    /// Parses a bundle from `reader`.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .context(error::BundleRead)?;
+        Self::from_string(text)
+    }
+
+    /// Parses a bundle from a string.
+    pub fn from_string(text: String) -> Result<Self> {
+        let raw_members: HashMap<String, &RawValue> =
+            serde_json::from_str(&text).context(error::BundleParse)?;
+
+        let mut members = HashMap::new();
+        for (name, value) in raw_members {
+            let start = value.get().as_ptr() as usize - text.as_ptr() as usize;
+            let mut end = start + value.get().len();
+            // Whitespace after the value is part of the embedded file, e.g. a trailing newline.
+            let rest = &text[end..];
+            end += rest.len() - rest.trim_start().len();
+            members.insert(name, start..end);
+        }
+
+        let root_version = match members.get("root") {
+            Some(range) => {
+                let root: Signed<Root> =
+                    serde_json::from_str(&text[range.clone()]).context(error::BundleParse)?;
+                Some(root.signed.version.get())
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            text: text.into(),
+            members,
+            root_version,
+        })
+    }
+
+    /// Returns the names of the members in this bundle.
+    pub fn members(&self) -> impl Iterator<Item = &str> + '_ {
+        self.members.keys().map(String::as_str)
+    }
+
+    /// Returns the bytes of the member named `name`, exactly as they appear in the bundle.
+    pub fn member(&self, name: &str) -> Option<&[u8]> {
+        self.members
+            .get(name)
+            .map(|range| &self.text.as_bytes()[range.clone()])
+    }
+
+    /// Finds the member that should be served for a metadata file name with its `.json`
+    /// extension removed, e.g. `timestamp`, `42.snapshot` or `3.root`.
+    fn member_for_stem(&self, stem: &str) -> Option<&[u8]> {
+        if let Some(member) = self.member(stem) {
+            return Some(member);
+        }
+
+        let mut parts = stem.splitn(2, '.');
+        let version = parts.next()?.parse::<u64>().ok()?;
+        let role = parts.next()?;
+        if role == "root" && self.root_version != Some(version) {
+            // Root is always requested by version. Only serve the bundle's root for the version
+            // it actually is, so that the loader stops looking for newer roots after it.
+            return None;
+        }
+        self.member(role)
+    }
+}
+
+impl Transport for MetadataBundle {
+    fn fetch(&self, url: Url) -> std::result::Result<Box<dyn Read + Send>, TransportError> {
+        let member = url
+            .path_segments()
+            .and_then(Iterator::last)
+            .and_then(|filename| filename.strip_suffix(".json"))
+            .and_then(|stem| self.member_for_stem(stem));
+        match member {
+            Some(member) => Ok(Box::new(Cursor::new(member.to_vec()))),
+            None => Err(TransportError::new(TransportErrorKind::FileNotFound, url)),
+        }
+    }
+}
@@ -1,11 +1,16 @@
 use crate::error::{self, Result};
-use crate::fetch::{fetch_max_size, fetch_sha256};
+use crate::fetch::{fetch_digest, fetch_max_size};
+use crate::hash::{self, HashAlgorithm};
+use crate::io::{CachingReader, DigestAdapter, MaxSizeAdapter};
 use crate::schema::{RoleType, Target};
 use crate::Repository;
+use log::{debug, warn};
+use ring::digest::SHA256;
 use snafu::{OptionExt, ResultExt};
 use std::fs::OpenOptions;
 use std::io::{Read, Write};
 use std::path::Path;
+use url::Url;
 
 impl Repository {
     /// Cache an entire or partial repository to disk, including all required metadata.
@@ -164,16 +169,19 @@ impl Repository {
     /// Saves a signed target to the specified `outdir`. Retains the digest-prepended filename if
     /// consistent snapshots are used.
     fn cache_target<P: AsRef<Path>>(&self, outdir: P, name: &str) -> Result<()> {
-        let t = self
-            .targets
-            .signed
-            .find_target(name)
-            .context(error::CacheTargetMissing {
-                target_name: name.to_owned(),
-            })?;
-        let (sha, filename) = self.target_digest_and_filename(&t, name);
-        let mut reader = self.fetch_target(t, &sha, filename.as_str())?;
+        let (role, t) =
+            self.targets
+                .signed
+                .resolve_target(name)
+                .context(error::CacheTargetMissing {
+                    target_name: name.to_owned(),
+                })?;
+        let filename = self.target_filename(t, name)?;
+        let mut reader = self.fetch_target(role, t, &filename)?;
         let path = outdir.as_ref().join(filename);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context(error::DirCreate { path: parent })?;
+        }
         let mut f = OpenOptions::new()
             .write(true)
             .create(true)
@@ -183,7 +191,9 @@ impl Repository {
         Ok(())
     }
 
-    /// Gets the max size of the snapshot.json file as specified by the timestamp file.
+    /// Gets the max size of the snapshot.json file as specified by the timestamp file. If the
+    /// repository was pinned to an older snapshot, the timestamp doesn't describe it, so
+    /// `max_targets_size` is used as for the initial load.
     fn max_snapshot_size(&self) -> Result<u64> {
         let snapshot_meta =
             self.timestamp()
@@ -194,43 +204,125 @@ impl Repository {
                     file: "snapshot.json",
                     role: RoleType::Timestamp,
                 })?;
+        if snapshot_meta.version != self.snapshot.signed.version {
+            return Ok(self.limits.max_targets_size);
+        }
         Ok(snapshot_meta.length)
     }
 
-    /// Prepends the target digest to the name if using consistent snapshots. Returns both the
-    /// digest and the filename.
-    pub(crate) fn target_digest_and_filename(
-        &self,
-        target: &Target,
-        name: &str,
-    ) -> (Vec<u8>, String) {
-        let sha256 = &target.hashes.sha256.clone().into_vec();
-        if self.consistent_snapshot {
-            (sha256.clone(), format!("{}.{}", hex::encode(sha256), name))
+    /// Prepends the target digest to the name if using consistent snapshots, or uses the target's
+    /// storage path if it has one under the key set by
+    /// [`RepositoryLoader::storage_path_custom_key`].
+    ///
+    /// [`RepositoryLoader::storage_path_custom_key`]: crate::RepositoryLoader::storage_path_custom_key
+    pub(crate) fn target_filename(&self, target: &Target, name: &str) -> Result<String> {
+        let storage_path = match &self.storage_path_custom_key {
+            Some(key) => target
+                .storage_path(key)
+                .context(error::TargetStoragePath { target: name })?,
+            None => None,
+        };
+        Ok(if let Some(storage_path) = storage_path {
+            storage_path.to_owned()
+        } else if self.consistent_snapshot {
+            target.consistent_name(name)
         } else {
-            (sha256.clone(), name.to_owned())
-        }
+            name.to_owned()
+        })
     }
 
-    /// Fetches the signed target using `Transport`. Aborts with error if the fetched target is
-    /// larger than its signed size.
+    /// Returns the strongest hash algorithm the repository recognizes that the target lists a
+    /// digest for, along with the digest.
+    pub(crate) fn target_digest(&self, target: &Target) -> (&dyn HashAlgorithm, Vec<u8>) {
+        hash::select(&self.hash_algorithms, &target.hashes)
+    }
+
+    /// Fetches the signed target using `Transport`, from the targets base URL of `role`, the
+    /// delegated role that lists it (if any). Aborts with error if the fetched target is larger
+    /// than its signed size.
     pub(crate) fn fetch_target(
         &self,
+        role: Option<&str>,
         target: &Target,
-        digest: &[u8],
         filename: &str,
     ) -> Result<impl Read> {
-        fetch_sha256(
+        let (algorithm, digest) = self.target_digest(target);
+        fetch_digest(
             self.transport.as_ref(),
-            self.targets_base_url
-                .join(&filename)
-                .context(error::JoinUrl {
-                    path: filename,
-                    url: self.targets_base_url.to_owned(),
-                })?,
+            self.target_url(role, target, filename)?,
             target.length,
             "targets.json",
-            digest,
+            algorithm,
+            &digest,
         )
     }
+
+    /// Reads the signed target from the datastore's target cache, if it's there and matches its
+    /// digest, or else fetches it, adding it to the cache once it's been read and verified.
+    pub(crate) fn read_cached_target(
+        &self,
+        role: Option<&str>,
+        target: &Target,
+        name: &str,
+        filename: &str,
+    ) -> Result<Box<dyn Read + Send>> {
+        let url = self.target_url(role, target, filename)?;
+        let (algorithm, digest) = self.target_digest(target);
+        let cache_file = format!(
+            "target-cache/{}.{}",
+            hex::encode(&digest),
+            hex::encode(ring::digest::digest(&SHA256, name.as_bytes()))
+        );
+        let verified = |reader: Box<dyn Read + Send>| {
+            DigestAdapter::new(
+                Box::new(MaxSizeAdapter::new(reader, "targets.json", target.length)),
+                algorithm,
+                &digest,
+                url.clone(),
+            )
+        };
+        if let Some(reader) = self.datastore.reader(&cache_file)? {
+            if std::io::copy(&mut verified(Box::new(reader)), &mut std::io::sink()).is_ok() {
+                if let Some(reader) = self.datastore.reader(&cache_file)? {
+                    debug!("reading target '{}' from the target cache", name);
+                    return Ok(Box::new(verified(Box::new(reader))));
+                }
+            } else {
+                warn!("cached target '{}' doesn't match its digest", name);
+                self.datastore.remove(&cache_file)?;
+            }
+        }
+        Ok(Box::new(CachingReader::new(
+            self.fetch_target(role, target, filename)?,
+            self.datastore.pending(&cache_file)?,
+        )))
+    }
+
+    /// Returns the URL of the target at `filename` under the targets base URL of `role`, the
+    /// delegated role that lists it (if any), unless the target's `custom` object gives an absolute
+    /// URL under the key set by [`RepositoryLoader::target_url_custom_key`].
+    ///
+    /// [`RepositoryLoader::target_url_custom_key`]: crate::RepositoryLoader::target_url_custom_key
+    fn target_url(&self, role: Option<&str>, target: &Target, filename: &str) -> Result<Url> {
+        if let Some(key) = &self.target_url_custom_key {
+            if let Some(value) = target.custom.get(key) {
+                return value.as_str().and_then(|url| Url::parse(url).ok()).context(
+                    error::TargetCustomUrl {
+                        key,
+                        value: value.to_string(),
+                    },
+                );
+            }
+        }
+        let targets_base_url = role
+            .and_then(|role| self.delegated_targets_base_urls.get(role))
+            .unwrap_or(&self.targets_base_url);
+        // `\` is an ordinary character in a target name, but URL parsing treats it as `/`
+        targets_base_url
+            .join(&filename.replace('\\', "%5C"))
+            .context(error::JoinUrl {
+                path: filename,
+                url: targets_base_url.to_owned(),
+            })
+    }
 }
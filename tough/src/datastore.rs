@@ -2,36 +2,58 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::error::{self, Result};
-use log::debug;
+use log::{debug, warn};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
-use snafu::ResultExt;
-use std::fs::{self, File};
-use std::io::{ErrorKind, Read};
+use snafu::{ensure, ResultExt};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
-use tempfile::TempDir;
+use tempfile::{NamedTempFile, TempDir};
+
+/// The default mode of files created in the datastore.
+pub(crate) const DEFAULT_FILE_MODE: u32 = 0o644;
 
 #[derive(Debug, Clone)]
-pub(crate) struct Datastore(Arc<RwLock<DatastorePath>>);
+pub(crate) struct Datastore {
+    path: Arc<RwLock<DatastorePath>>,
+    file_mode: u32,
+}
 
 impl Datastore {
-    pub(crate) fn new(path: Option<PathBuf>) -> Result<Self> {
+    /// Creates a datastore in `path`, or in a temporary directory if `path` is `None`. Files are
+    /// created with `file_mode` on Unix, which may not be world-writable: anyone able to modify the
+    /// trusted metadata could defeat rollback protection.
+    pub(crate) fn new(path: Option<PathBuf>, file_mode: u32) -> Result<Self> {
+        ensure!(
+            file_mode & 0o002 == 0,
+            error::DatastoreFileMode {
+                mode: format!("{file_mode:o}")
+            }
+        );
         // using pattern matching instead of mapping because TempDir::new() can error
-        Ok(Self(Arc::new(RwLock::new(match path {
+        let path = match path {
             None => DatastorePath::TempDir(TempDir::new().context(error::DatastoreInit)?),
             Some(p) => DatastorePath::Path(p),
-        }))))
+        };
+        Ok(Self {
+            path: Arc::new(RwLock::new(path)),
+            file_mode,
+        })
     }
 
     // Because we are not actually changing the underlying data in the lock, we can ignore when a
     // lock is poisoned.
 
     fn read(&self) -> RwLockReadGuard<'_, DatastorePath> {
-        self.0.read().unwrap_or_else(PoisonError::into_inner)
+        self.path.read().unwrap_or_else(PoisonError::into_inner)
     }
 
     fn write(&self) -> RwLockWriteGuard<'_, DatastorePath> {
-        self.0.write().unwrap_or_else(PoisonError::into_inner)
+        self.path.write().unwrap_or_else(PoisonError::into_inner)
     }
 
     pub(crate) fn reader(&self, file: &str) -> Result<Option<impl Read>> {
@@ -45,18 +67,60 @@ impl Datastore {
         }
     }
 
+    /// Reads `file` as JSON. What the datastore holds was verified when it was stored, but it's only
+    /// a cache that the loader checks newly fetched metadata against, never a source of trust. So a
+    /// file that can't be read or parsed, such as one left truncated by a crash, is treated as
+    /// absent: it's removed with a warning, and replaced once the metadata it cached has been
+    /// fetched and verified again.
+    pub(crate) fn cached<T: DeserializeOwned>(&self, file: &str) -> Result<Option<T>> {
+        let reader = match self.reader(file)? {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+        match serde_json::from_reader(reader) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => {
+                warn!("ignoring corrupt '{}' in the datastore: {}", file, err);
+                self.remove(file)?;
+                Ok(None)
+            }
+        }
+    }
+
     pub(crate) fn create<T: Serialize>(&self, file: &str, value: &T) -> Result<()> {
         let path = self.write().path().join(file);
-        serde_json::to_writer_pretty(
-            File::create(&path).context(error::DatastoreCreate { path: &path })?,
-            value,
-        )
-        .context(error::DatastoreSerialize {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(self.file_mode);
+        let writer = options
+            .open(&path)
+            .context(error::DatastoreCreate { path: &path })?;
+        // The mode given when opening only applies to new files, and is subject to the umask.
+        #[cfg(unix)]
+        writer
+            .set_permissions(fs::Permissions::from_mode(self.file_mode))
+            .context(error::DatastoreCreate { path: &path })?;
+        serde_json::to_writer_pretty(writer, value).context(error::DatastoreSerialize {
             what: format!("{} in datastore", file),
             path,
         })
     }
 
+    /// Starts writing `file`, which may be in a subdirectory of the datastore. The file is only
+    /// created once the returned [`PendingFile`] is persisted.
+    pub(crate) fn pending(&self, file: &str) -> Result<PendingFile> {
+        let path = self.write().path().join(file);
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(dir).context(error::DatastoreCreate { path: dir })?;
+        let temp = NamedTempFile::new_in(dir).context(error::DatastoreCreate { path: &path })?;
+        #[cfg(unix)]
+        temp.as_file()
+            .set_permissions(fs::Permissions::from_mode(self.file_mode))
+            .context(error::DatastoreCreate { path: &path })?;
+        Ok(PendingFile { temp, path })
+    }
+
     pub(crate) fn remove(&self, file: &str) -> Result<()> {
         let path = self.write().path().join(file);
         debug!("removing '{}'", path.display());
@@ -70,6 +134,31 @@ impl Datastore {
     }
 }
 
+/// A file being written to the datastore, which is written to a temporary file and only moved into
+/// place by [`PendingFile::persist`]. It's removed if dropped before then.
+#[derive(Debug)]
+pub(crate) struct PendingFile {
+    temp: NamedTempFile,
+    path: PathBuf,
+}
+
+impl PendingFile {
+    pub(crate) fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.temp
+            .write_all(buf)
+            .context(error::DatastoreCreate { path: &self.path })
+    }
+
+    pub(crate) fn persist(self) -> Result<()> {
+        let path = self.path;
+        self.temp
+            .persist(&path)
+            .map_err(|e| e.error)
+            .context(error::DatastoreCreate { path })?;
+        Ok(())
+    }
+}
+
 /// Because `TempDir` is an RAII object, we need to hold on to it. This private enum allows us to
 /// hold either a `TempDir` or a `PathBuf` depending on whether or not the user wants to manage the
 /// directory.
@@ -0,0 +1,71 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides readers that decompress targets after they are verified.
+//!
+//! Some repositories store compressed artifacts as targets. The hashes and length recorded in
+//! targets metadata are those of the target as stored, so they describe the *compressed* bytes.
+//! [`Repository::read_target`] always verifies the bytes exactly as they are fetched; the functions
+//! in this module are a convenience for callers that want the decompressed contents, and wrap the
+//! verifying reader rather than replacing it.
+//!
+//! As with [`Repository::read_target`], the target is only known to be valid once the returned
+//! reader has been read to the end without error. Each reader here reads the remainder of the
+//! compressed stream once decompression finishes, so that verification completes before the end
+//! of the decompressed data is reported, even if the compressed stream has trailing bytes.
+//!
+//! The `gzip` and `zstd` features enable the corresponding functions.
+//!
+//! [`Repository::read_target`]: crate::Repository::read_target
+
+use std::io::{self, Read};
+
+/// A decompressing reader that can give access to the compressed stream it reads from.
+trait Decoder: Read {
+    fn compressed(&mut self) -> &mut dyn Read;
+}
+
+#[cfg(feature = "gzip")]
+impl<R: Read> Decoder for flate2::read::GzDecoder<R> {
+    fn compressed(&mut self) -> &mut dyn Read {
+        self.get_mut()
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: io::BufRead> Decoder for zstd::stream::read::Decoder<'static, R> {
+    fn compressed(&mut self) -> &mut dyn Read {
+        self.get_mut()
+    }
+}
+
+/// Reads from a `Decoder` until it is exhausted, and then drains its compressed stream.
+struct ReadToEnd<D>(D);
+
+impl<D: Decoder> Read for ReadToEnd<D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.0.read(buf)?;
+        if size == 0 && !buf.is_empty() {
+            io::copy(self.0.compressed(), &mut io::sink())?;
+        }
+        Ok(size)
+    }
+}
+
+/// Decompresses a gzip-compressed target read from `verified`, such as the reader returned by
+/// [`Repository::read_target`](crate::Repository::read_target).
+#[cfg(feature = "gzip")]
+pub fn gzip<R: Read>(verified: R) -> impl Read {
+    ReadToEnd(flate2::read::GzDecoder::new(verified))
+}
+
+/// Decompresses a zstd-compressed target read from `verified`, such as the reader returned by
+/// [`Repository::read_target`](crate::Repository::read_target).
+#[cfg(feature = "zstd")]
+pub fn zstd<R: Read>(verified: R) -> crate::error::Result<impl Read> {
+    use snafu::ResultExt;
+
+    Ok(ReadToEnd(
+        zstd::stream::read::Decoder::new(verified).context(crate::error::Decompress)?,
+    ))
+}
@@ -4,23 +4,49 @@
 use crate::error::{self, Result};
 use crate::key_source::KeySource;
 use crate::schema::decoded::{Decoded, Hex};
+use crate::schema::key::Key;
 use crate::schema::{Delegations, KeyHolder, RoleId, RoleKeys, Root, Signed, Targets};
 use crate::sign::Sign;
+use log::warn;
 use snafu::{ensure, OptionExt, ResultExt};
 use std::collections::HashMap;
 
 /// A map of key ID (from root.json or the Delegations field of any Targets) to its corresponding signing key
-pub(crate) type KeyList = HashMap<Decoded<Hex>, Box<dyn Sign>>;
+pub(crate) type KeyList<'a> = HashMap<Decoded<Hex>, &'a dyn Sign>;
+
+/// Gets the signing key of each of `keys`. Sources such as remote key services may make a request
+/// for each key, so they're resolved once and the signing keys used for every role signed.
+pub(crate) fn resolve_keys(keys: &[Box<dyn KeySource>]) -> Result<Vec<Box<dyn Sign>>> {
+    keys.iter()
+        .map(|source| source.as_sign().context(error::KeyPairFromKeySource))
+        .collect()
+}
 
 impl KeyHolder {
     /// Creates a key list for the provided keys
-    pub(crate) fn get_keys(&self, keys: &[Box<dyn KeySource>]) -> Result<KeyList> {
+    pub(crate) fn get_keys<'a>(&self, keys: &'a [Box<dyn Sign>]) -> Result<KeyList<'a>> {
         match self {
             Self::Delegations(delegations) => get_targets_keys(delegations, keys),
             Self::Root(root) => get_root_keys(root, keys),
         }
     }
 
+    /// Returns the ID that `key_pair` is listed under, if it's listed.
+    fn key_id(&self, key_pair: &dyn Sign) -> Option<Decoded<Hex>> {
+        match self {
+            Self::Delegations(delegations) => delegations.key_id(key_pair),
+            Self::Root(root) => root.key_id(key_pair),
+        }
+    }
+
+    /// Returns the key listed under `key_id`
+    pub(crate) fn key(&self, key_id: &Decoded<Hex>) -> Option<&Key> {
+        match self {
+            Self::Delegations(delegations) => delegations.keys.get(key_id),
+            Self::Root(root) => root.keys.get(key_id),
+        }
+    }
+
     /// Returns role keys for the provided role id
     pub(crate) fn role_keys(&self, name: RoleId) -> Result<RoleKeys> {
         match self {
@@ -71,41 +97,80 @@ impl KeyHolder {
     }
 }
 
-/// Gets the corresponding keys from Root (root.json) for the given `KeySource`s.
+/// Checks that each of `keys` is authorized to sign for at least one of `roles`, each given with
+/// the metadata that lists its keys. A key that isn't would be skipped, so it's warned about, or
+/// is an error if `strict` is set.
+pub(crate) fn check_signing_keys(
+    roles: &[(&KeyHolder, RoleId)],
+    keys: &[Box<dyn Sign>],
+    strict: bool,
+) -> Result<()> {
+    for key_pair in keys {
+        let authorized = roles.iter().any(|(key_holder, role)| {
+            match (
+                key_holder.key_id(key_pair.as_ref()),
+                key_holder.role_keys(role.clone()),
+            ) {
+                (Some(key_id), Ok(role_keys)) => role_keys.keyids.contains(&key_id),
+                _ => false,
+            }
+        });
+        if authorized {
+            continue;
+        }
+        let key_id = hex::encode(
+            key_pair
+                .tuf_key()
+                .key_id()
+                .context(error::JsonSerialization {})?,
+        );
+        let roles = roles
+            .iter()
+            .map(|(_, role)| match role {
+                RoleId::StandardRole(role) => role.to_string(),
+                RoleId::DelegatedRole(name) => name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        ensure!(!strict, error::UnauthorizedSigningKey { key_id, roles });
+        warn!(
+            "key {} is not authorized to sign for {} and will not be used",
+            key_id, roles
+        );
+    }
+    Ok(())
+}
+
+/// Gets the corresponding keys from Root (root.json) for the given signing keys.
 /// This is a convenience function that wraps `Root.key_id()` for multiple
-/// `KeySource`s.
-pub(crate) fn get_root_keys(root: &Root, keys: &[Box<dyn KeySource>]) -> Result<KeyList> {
+/// keys.
+pub(crate) fn get_root_keys<'a>(root: &Root, keys: &'a [Box<dyn Sign>]) -> Result<KeyList<'a>> {
     let mut root_keys = KeyList::new();
 
-    for source in keys {
-        // Get a keypair from the given source
-        let key_pair = source.as_sign().context(error::KeyPairFromKeySource)?;
-
+    for key_pair in keys {
         // If the keypair matches any of the keys in the root.json,
         // add its ID and corresponding keypair the map to be returned
         if let Some(key_id) = root.key_id(key_pair.as_ref()) {
-            root_keys.insert(key_id, key_pair);
+            root_keys.insert(key_id, key_pair.as_ref());
         }
     }
     ensure!(!root_keys.is_empty(), error::KeysNotFoundInRoot);
     Ok(root_keys)
 }
 
-/// Gets the corresponding keys from delegations for the given `KeySource`s.
+/// Gets the corresponding keys from delegations for the given signing keys.
 /// This is a convenience function that wraps `Delegations.key_id()` for multiple
-/// `KeySource`s.
-pub(crate) fn get_targets_keys(
+/// keys.
+pub(crate) fn get_targets_keys<'a>(
     delegations: &Delegations,
-    keys: &[Box<dyn KeySource>],
-) -> Result<KeyList> {
+    keys: &'a [Box<dyn Sign>],
+) -> Result<KeyList<'a>> {
     let mut delegations_keys = KeyList::new();
-    for source in keys {
-        // Get a keypair from the given source
-        let key_pair = source.as_sign().context(error::KeyPairFromKeySource)?;
+    for key_pair in keys {
         // If the keypair matches any of the keys in the delegations metadata,
         // add its ID and corresponding keypair the map to be returned
         if let Some(key_id) = delegations.key_id(key_pair.as_ref()) {
-            delegations_keys.insert(key_id, key_pair);
+            delegations_keys.insert(key_id, key_pair.as_ref());
         }
     }
     Ok(delegations_keys)
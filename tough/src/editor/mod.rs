@@ -9,6 +9,7 @@ pub mod signed;
 pub mod targets;
 mod test;
 
+use crate::editor::keys::{check_signing_keys, resolve_keys};
 use crate::editor::signed::{SignedDelegatedTargets, SignedRepository, SignedRole};
 use crate::editor::targets::TargetsEditor;
 use crate::error::{self, Result};
@@ -17,9 +18,10 @@ use crate::key_source::KeySource;
 use crate::schema::decoded::{Decoded, Hex};
 use crate::schema::key::Key;
 use crate::schema::{
-    Hashes, KeyHolder, PathSet, Role, RoleType, Root, Signed, Snapshot, SnapshotMeta, Target,
-    Targets, Timestamp, TimestampMeta,
+    DelegatedTargets, Hashes, KeyHolder, PathSet, Role, RoleId, RoleType, Root, Signed, Snapshot,
+    SnapshotMeta, SpecVersion, Target, Targets, Timestamp,
 };
+use crate::target_name_from_path;
 use crate::transport::Transport;
 use crate::Limits;
 use crate::Repository;
@@ -34,7 +36,22 @@ use std::num::NonZeroU64;
 use std::path::Path;
 use url::Url;
 
-const SPEC_VERSION: &str = "1.0.0";
+/// The TUF specification version written to new metadata. Existing metadata that the editor loads
+/// is accepted if this version [supports](SpecVersion::supports) its `spec_version`.
+pub const SPEC_VERSION: &str = "1.0.0";
+
+/// Returns whether the editor can load metadata with the given `spec_version`. Tools that write
+/// their own spec version into metadata should check it here, so that they never produce metadata
+/// the editor would refuse to load.
+pub fn spec_version_supported(spec_version: &str) -> bool {
+    match (
+        SPEC_VERSION.parse::<SpecVersion>(),
+        spec_version.parse::<SpecVersion>(),
+    ) {
+        (Ok(supported), Ok(given)) => supported.supports(given),
+        _ => false,
+    }
+}
 
 /// `RepositoryEditor` contains the various bits of data needed to construct
 /// or edit a TUF repository.
@@ -162,8 +179,29 @@ impl RepositoryEditor {
     pub fn sign(mut self, keys: &[Box<dyn KeySource>]) -> Result<SignedRepository> {
         let rng = SystemRandom::new();
         let root = KeyHolder::Root(self.signed_root.signed.signed.clone());
+        let keys = resolve_keys(keys)?;
+        // Each key only needs to sign one of the roles signed here, so the keys are checked
+        // against all of them at once
+        let targets_role = match &self.targets_editor {
+            Some(targets_editor) => Some(targets_editor.signing_role(&keys)?),
+            None => None,
+        };
+        let mut roles = vec![
+            (&root, RoleId::StandardRole(RoleType::Snapshot)),
+            (&root, RoleId::StandardRole(RoleType::Timestamp)),
+        ];
+        if let Some((key_holder, role_id)) = &targets_role {
+            roles.push((key_holder, role_id.clone()));
+        }
+        check_signing_keys(&roles, &keys, false)?;
+
         // Sign the targets editor if able to with the provided keys
-        self.sign_targets_editor(keys)?;
+        if let (Some(targets_editor), Some((key_holder, _))) =
+            (self.targets_editor.take(), &targets_role)
+        {
+            let signed = targets_editor.create_signed_with(key_holder, &keys)?;
+            self.set_signed_targets(signed)?;
+        }
         let targets = self.signed_targets.clone().context(error::NoTargets)?;
         let delegated_targets = targets.signed.signed_delegated_targets();
         let signed_targets = SignedRole::from_signed(targets)?;
@@ -183,15 +221,29 @@ impl RepositoryEditor {
             Some(SignedDelegatedTargets {
                 roles,
                 consistent_snapshot: self.signed_root.signed.signed.consistent_snapshot,
+                storage_path_custom_key: None,
             })
         };
 
-        let signed_snapshot = self
-            .build_snapshot(&signed_targets, &signed_delegated_targets)
-            .and_then(|snapshot| SignedRole::new(snapshot, &root, keys, &rng))?;
+        let snapshot = self.build_snapshot(&signed_targets, &signed_delegated_targets)?;
+        // Fail rather than write metadata that clients would reject as inconsistent
+        let mut written = vec![(
+            "targets.json".to_owned(),
+            signed_targets.signed.signed.version,
+        )];
+        if let Some(signed_delegated_targets) = &signed_delegated_targets {
+            for role in &signed_delegated_targets.roles {
+                written.push((
+                    format!("{}.json", role.signed.signed.name),
+                    role.signed.signed.version(),
+                ));
+            }
+        }
+        check_snapshot_versions(&snapshot, self.signed_root.signed.signed.version, &written)?;
+        let signed_snapshot = SignedRole::sign(snapshot, &root, &keys, &rng)?;
         let signed_timestamp = self
             .build_timestamp(&signed_snapshot)
-            .and_then(|timestamp| SignedRole::new(timestamp, &root, keys, &rng))?;
+            .and_then(|timestamp| SignedRole::sign(timestamp, &root, &keys, &rng))?;
 
         Ok(SignedRepository {
             root: self.signed_root,
@@ -199,13 +251,14 @@ impl RepositoryEditor {
             snapshot: signed_snapshot,
             timestamp: signed_timestamp,
             delegated_targets: signed_delegated_targets,
+            storage_path_custom_key: None,
         })
     }
 
     /// Add an existing `Targets` struct to the repository.
     pub fn targets(&mut self, targets: Signed<Targets>) -> Result<&mut Self> {
         ensure!(
-            targets.signed.spec_version == SPEC_VERSION,
+            spec_version_supported(&targets.signed.spec_version),
             error::SpecVersion {
                 given: targets.signed.spec_version,
                 supported: SPEC_VERSION
@@ -226,7 +279,7 @@ impl RepositoryEditor {
     /// is preserved
     pub fn snapshot(&mut self, snapshot: Snapshot) -> Result<&mut Self> {
         ensure!(
-            snapshot.spec_version == SPEC_VERSION,
+            spec_version_supported(&snapshot.spec_version),
             error::SpecVersion {
                 given: snapshot.spec_version,
                 supported: SPEC_VERSION
@@ -240,7 +293,7 @@ impl RepositoryEditor {
     /// is preserved
     pub fn timestamp(&mut self, timestamp: Timestamp) -> Result<&mut Self> {
         ensure!(
-            timestamp.spec_version == SPEC_VERSION,
+            spec_version_supported(&timestamp.spec_version),
             error::SpecVersion {
                 given: timestamp.spec_version,
                 supported: SPEC_VERSION
@@ -309,13 +362,13 @@ impl RepositoryEditor {
         let target =
             Target::from_path(target_path).context(error::TargetFromPath { path: target_path })?;
 
-        // Get the file name as a string
-        let target_name = target_path
-            .file_name()
-            .context(error::NoFileName { path: target_path })?
-            .to_str()
-            .context(error::PathUtf8 { path: target_path })?
-            .to_owned();
+        // Get the file name as a target name
+        let target_name = target_name_from_path(Path::new(
+            target_path
+                .file_name()
+                .context(error::NoFileName { path: target_path })?,
+        ))
+        .context(error::PathUtf8 { path: target_path })?;
 
         Ok((target_name, target))
     }
@@ -411,24 +464,30 @@ impl RepositoryEditor {
     /// Sets `targets_editor` to None
     /// Must be called before `change_delegated_targets()`
     pub fn sign_targets_editor(&mut self, keys: &[Box<dyn KeySource>]) -> Result<&mut Self> {
-        if let Some(targets_editor) = self.targets_editor.as_mut() {
-            let (name, targets) = targets_editor.create_signed(keys)?.targets();
-            if name == "targets" {
-                self.signed_targets = Some(targets);
-            } else {
-                self.signed_targets
-                    .as_mut()
-                    .context(error::NoTargets)?
-                    .signed
-                    .delegated_role_mut(&name)
-                    .context(error::DelegateMissing { name })?
-                    .targets = Some(targets);
-            }
+        if let Some(targets_editor) = self.targets_editor.take() {
+            let signed = targets_editor.create_signed(keys)?;
+            self.set_signed_targets(signed)?;
         }
-        self.targets_editor = None;
         Ok(self)
     }
 
+    /// Stores the signed targets of the `targets_editor`, whether top level or delegated.
+    fn set_signed_targets(&mut self, signed: Signed<DelegatedTargets>) -> Result<()> {
+        let (name, targets) = signed.targets();
+        if name == "targets" {
+            self.signed_targets = Some(targets);
+        } else {
+            self.signed_targets
+                .as_mut()
+                .context(error::NoTargets)?
+                .signed
+                .delegated_role_mut(&name)
+                .context(error::DelegateMissing { name })?
+                .targets = Some(targets);
+        }
+        Ok(())
+    }
+
     /// Changes the targets refered to in `targets_editor` to role
     /// All `Targets` related calls will now be called on the `Targets` role named `role`
     /// Throws error if the `targets_editor` was not cleared using `sign_targets_editor()`
@@ -501,10 +560,7 @@ impl RepositoryEditor {
             "max targets limit",
         )?);
         // Load incoming role metadata as Signed<Targets>
-        let mut role: Signed<crate::schema::Targets> =
-            serde_json::from_reader(reader).context(error::ParseMetadata {
-                role: RoleType::Targets,
-            })?;
+        let mut role: Signed<crate::schema::Targets> = crate::parse_metadata(reader)?;
         //verify role with the parent delegation
         let (parent, current_targets) = if name == "targets" {
             (
@@ -562,10 +618,7 @@ impl RepositoryEditor {
                 "max targets limit",
             )?);
             // Load new role metadata as Signed<Targets>
-            let new_role: Signed<crate::schema::Targets> = serde_json::from_reader(reader)
-                .context(error::ParseMetadata {
-                    role: RoleType::Targets,
-                })?;
+            let new_role: Signed<crate::schema::Targets> = crate::parse_metadata(reader)?;
             // verify the role
             key_holder.verify_role(&new_role, &name)?;
             // add the new role
@@ -681,35 +734,45 @@ impl RepositoryEditor {
         let expires = self.timestamp_expires.context(error::Missing {
             field: "timestamp expiration",
         })?;
-        let _extra = self.timestamp_extra.clone().unwrap_or_else(HashMap::new);
-        let mut timestamp = Timestamp::new(SPEC_VERSION.to_string(), version, expires);
-
-        // Timestamp stores metadata about snapshot
-        let snapshot_meta = Self::timestamp_meta(signed_snapshot);
-        timestamp
-            .meta
-            .insert("snapshot.json".to_owned(), snapshot_meta);
-        timestamp._extra = _extra;
+        let mut timestamp = Timestamp::from_snapshot(
+            &signed_snapshot.signed().signed,
+            *signed_snapshot.length(),
+            Hashes {
+                sha256: signed_snapshot.sha256().to_vec().into(),
+                _extra: HashMap::new(),
+            },
+            version,
+            expires,
+        );
+        timestamp._extra = self.timestamp_extra.clone().unwrap_or_else(HashMap::new);
 
         Ok(timestamp)
     }
+}
 
-    /// Build a `TimestampMeta` struct from a given `SignedRole<R>`. This metadata
-    /// includes the sha256 and length of the signed role.
-    fn timestamp_meta<R>(role: &SignedRole<R>) -> TimestampMeta
-    where
-        R: Role,
-    {
-        TimestampMeta {
-            hashes: Hashes {
-                sha256: role.sha256.to_vec().into(),
-                _extra: HashMap::new(),
-            },
-            length: role.length,
-            version: role.signed.signed.version(),
-            _extra: HashMap::new(),
-        }
+/// Checks that `snapshot` lists each metadata file in `written` at the version it's being written
+/// with. Snapshot needn't list `root.json`, but if it does, it must be at `root_version`.
+pub(crate) fn check_snapshot_versions(
+    snapshot: &Snapshot,
+    root_version: NonZeroU64,
+    written: &[(String, NonZeroU64)],
+) -> Result<()> {
+    let root = snapshot
+        .meta
+        .get("root.json")
+        .map(|_| ("root.json".to_owned(), root_version));
+    for (file, version) in written.iter().chain(root.as_ref()) {
+        let listed = snapshot.meta.get(file).map(|meta| meta.version);
+        ensure!(
+            listed == Some(*version),
+            error::SnapshotVersionMismatch {
+                file,
+                listed: listed.map(NonZeroU64::get),
+                version: version.get(),
+            }
+        );
     }
+    Ok(())
 }
 
 fn parse_url(url: &str) -> Result<Url> {
@@ -6,21 +6,26 @@
 //! Provides the `SignedDelegatedTargets` object which represents the output of `TargetsEditor` after
 //! signing, ready to be written to disk.
 
+use crate::editor::keys::{check_signing_keys, resolve_keys};
 use crate::error::{self, Result};
 use crate::io::DigestAdapter;
 use crate::key_source::KeySource;
+use crate::schema::decoded::{Decoded, Hex};
 use crate::schema::{
     DelegatedTargets, KeyHolder, Role, RoleType, Root, Signature, Signed, Snapshot, Target,
     Targets, Timestamp,
 };
+use crate::sign::Sign;
+use chrono::{DateTime, Utc};
 use olpc_cjson::CanonicalFormatter;
 use ring::digest::{digest, SHA256, SHA256_OUTPUT_LEN};
 use ring::rand::SecureRandom;
 use serde::{Deserialize, Serialize};
 use serde_plain::forward_from_str_to_serde;
 use snafu::{ensure, OptionExt, ResultExt};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::num::NonZeroU64;
 
 #[cfg(not(target_os = "windows"))]
 use std::os::unix::fs::symlink;
@@ -50,12 +55,39 @@ impl<T> SignedRole<T>
 where
     T: Role + Serialize,
 {
-    /// Creates a new `SignedRole`
+    /// Creates a new `SignedRole`, signed with each of `keys` that's authorized to sign for the
+    /// role. Any other key is skipped with a warning.
     pub fn new(
         role: T,
         key_holder: &KeyHolder,
         keys: &[Box<dyn KeySource>],
         rng: &dyn SecureRandom,
+    ) -> Result<Self> {
+        let keys = resolve_keys(keys)?;
+        check_signing_keys(&[(key_holder, role.role_id())], &keys, false)?;
+        Self::sign(role, key_holder, &keys, rng)
+    }
+
+    /// Creates a new `SignedRole`, as [`SignedRole::new`] does, but fails if any of `keys` isn't
+    /// authorized to sign for the role.
+    pub fn new_strict(
+        role: T,
+        key_holder: &KeyHolder,
+        keys: &[Box<dyn KeySource>],
+        rng: &dyn SecureRandom,
+    ) -> Result<Self> {
+        let keys = resolve_keys(keys)?;
+        check_signing_keys(&[(key_holder, role.role_id())], &keys, true)?;
+        Self::sign(role, key_holder, &keys, rng)
+    }
+
+    /// Signs `role` with each of `keys` that's authorized to sign for it. Callers resolve the keys
+    /// with `resolve_keys` and check them with `check_signing_keys` first.
+    pub(crate) fn sign(
+        role: T,
+        key_holder: &KeyHolder,
+        keys: &[Box<dyn Sign>],
+        rng: &dyn SecureRandom,
     ) -> Result<Self> {
         let root_keys = key_holder.get_keys(keys)?;
 
@@ -75,19 +107,29 @@ where
         };
 
         let mut data = Vec::new();
-        let mut ser = serde_json::Serializer::with_formatter(&mut data, CanonicalFormatter::new());
+        let mut ser = serde_json::Serializer::with_formatter(
+            &mut data,
+            CanonicalFormatter::without_normalization(),
+        );
         role.signed
             .serialize(&mut ser)
             .context(error::SerializeRole {
                 role: T::TYPE.to_string(),
             })?;
         for (signing_key_id, signing_key) in valid_keys {
-            let sig = signing_key.sign(&data, rng).context(error::SignMessage)?;
+            // Sign under the scheme the key is listed with, which may differ from the key pair's
+            // default scheme
+            let sig = match key_holder.key(signing_key_id) {
+                Some(key) => signing_key.sign_as(key, &data, rng),
+                None => signing_key.sign(&data, rng),
+            }
+            .context(error::SignMessage)?;
 
             // Add the signatures to the `Signed` struct for this role
             role.signatures.push(Signature {
                 keyid: signing_key_id.clone(),
                 sig: sig.into(),
+                created: None,
             });
         }
 
@@ -101,8 +143,12 @@ where
     }
 
     /// Creates a `SignedRole<Role>` from a `Signed<Role>`.
-    /// This is used to create signed roles for any signed metadata
-    pub(crate) fn from_signed(role: Signed<T>) -> Result<SignedRole<T>> {
+    /// This is used to create signed roles for any signed metadata. Signatures are normalized, so
+    /// they're written consistently whichever tool made them.
+    pub(crate) fn from_signed(mut role: Signed<T>) -> Result<SignedRole<T>> {
+        for signature in &mut role.signatures {
+            signature.normalize();
+        }
         // Serialize the role, and calculate its length and
         // sha256.
         let mut buffer = serde_json::to_vec_pretty(&role).context(error::SerializeSignedRole {
@@ -162,6 +208,42 @@ where
         std::fs::write(&path, &self.buffer).context(error::FileWrite { path })
     }
 
+    /// Write the current role's buffer, compressed with gzip, to the given directory. The file is
+    /// named by [`compressed_metadata_name`](crate::compressed_metadata_name) from the role's
+    /// usual file name, such as `42.targets.json.gz`.
+    #[cfg(feature = "gzip")]
+    pub fn write_compressed<P>(&self, outdir: P, consistent_snapshot: bool) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        use std::io::Write;
+
+        let outdir = outdir.as_ref();
+        std::fs::create_dir_all(outdir).context(error::DirCreate { path: outdir })?;
+
+        let filename =
+            crate::compressed_metadata_name(&self.signed.signed.filename(consistent_snapshot));
+
+        let path = outdir.join(filename);
+        let file = fs::File::create(&path).context(error::FileWrite { path: &path })?;
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        encoder
+            .write_all(&self.buffer)
+            .and_then(|()| encoder.finish())
+            .context(error::FileWrite { path })?;
+        Ok(())
+    }
+
+    /// Records `created` as the signing time of each signature that doesn't already have one. The
+    /// signing time is informational and is not covered by any signature, so this doesn't affect
+    /// verification of the role.
+    pub fn record_signing_time(mut self, created: DateTime<Utc>) -> Result<Self> {
+        for signature in &mut self.signed.signatures {
+            signature.created.get_or_insert(created);
+        }
+        SignedRole::from_signed(self.signed)
+    }
+
     /// Append the old signatures for root role
     pub fn add_old_signatures(mut self, old_signatures: Vec<Signature>) -> Result<Self> {
         for old_signature in old_signatures {
@@ -173,10 +255,7 @@ where
                 .find(|new_sig| new_sig.keyid == old_signature.keyid)
                 == None
             {
-                self.signed.signatures.push(Signature {
-                    keyid: old_signature.keyid,
-                    sig: old_signature.sig,
-                });
+                self.signed.signatures.push(old_signature);
             }
         }
         SignedRole::from_signed(self.signed)
@@ -227,9 +306,21 @@ pub struct SignedRepository {
     pub(crate) snapshot: SignedRole<Snapshot>,
     pub(crate) timestamp: SignedRole<Timestamp>,
     pub(crate) delegated_targets: Option<SignedDelegatedTargets>,
+    pub(crate) storage_path_custom_key: Option<String>,
 }
 
 impl SignedRepository {
+    /// Sets the key of the targets' `custom` field that gives where a target is stored, relative
+    /// to the targets directory, when copying, linking, or checking targets. Targets are stored
+    /// under their names unless this is set, and clients must be loaded with the same key, via
+    /// [`RepositoryLoader::storage_path_custom_key`], to fetch them.
+    ///
+    /// [`RepositoryLoader::storage_path_custom_key`]: crate::RepositoryLoader::storage_path_custom_key
+    pub fn storage_path_custom_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.storage_path_custom_key = Some(key.into());
+        self
+    }
+
     /// Writes the metadata to the given directory. If consistent snapshots
     /// are used, the appropriate files are prefixed with their version.
     pub fn write<P>(&self, outdir: P) -> Result<()>
@@ -247,6 +338,127 @@ impl SignedRepository {
         Ok(())
     }
 
+    /// Returns an index of the metadata files of this repository, as written by
+    /// [`SignedRepository::write_versions`].
+    pub fn versions(&self) -> VersionsIndex {
+        let consistent_snapshot = self.root.signed.signed.consistent_snapshot;
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            "root".to_owned(),
+            IndexedRole::new(&self.root, consistent_snapshot),
+        );
+        roles.insert(
+            "targets".to_owned(),
+            IndexedRole::new(&self.targets, consistent_snapshot),
+        );
+        roles.insert(
+            "snapshot".to_owned(),
+            IndexedRole::new(&self.snapshot, consistent_snapshot),
+        );
+        roles.insert(
+            "timestamp".to_owned(),
+            IndexedRole::new(&self.timestamp, consistent_snapshot),
+        );
+        if let Some(delegated_targets) = &self.delegated_targets {
+            for role in &delegated_targets.roles {
+                roles.insert(
+                    role.signed.signed.name.clone(),
+                    IndexedRole::new(role, consistent_snapshot),
+                );
+            }
+        }
+        VersionsIndex { roles }
+    }
+
+    /// Writes an index of the metadata files of this repository to [`VERSIONS_INDEX`] in the given
+    /// directory, which should be the directory the metadata is written to.
+    pub fn write_versions<P>(&self, outdir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let outdir = outdir.as_ref();
+        std::fs::create_dir_all(outdir).context(error::DirCreate { path: outdir })?;
+        let mut buf =
+            serde_json::to_vec_pretty(&self.versions()).context(error::SerializeVersionsIndex)?;
+        buf.push(b'\n');
+        let path = outdir.join(VERSIONS_INDEX);
+        std::fs::write(&path, buf).context(error::FileWrite { path })
+    }
+
+    /// Returns the metadata of this repository as a single JSON bundle, in the format read by
+    /// [`MetadataBundle`](crate::bundle::MetadataBundle). Each role is a member named after the
+    /// role, and its value is the role's buffer, byte for byte, so that its signatures and the
+    /// hashes recorded by snapshot and timestamp still match.
+    pub fn bundle(&self) -> Vec<u8> {
+        let mut members = vec![
+            ("root", self.root.buffer()),
+            ("timestamp", self.timestamp.buffer()),
+            ("snapshot", self.snapshot.buffer()),
+            ("targets", self.targets.buffer()),
+        ];
+        if let Some(delegated_targets) = &self.delegated_targets {
+            for role in &delegated_targets.roles {
+                members.push((&role.signed.signed.name, role.buffer()));
+            }
+        }
+
+        let mut bundle = b"{".to_vec();
+        for (i, (name, buffer)) in members.into_iter().enumerate() {
+            if i > 0 {
+                bundle.push(b',');
+            }
+            bundle.push(b'\n');
+            bundle.extend_from_slice(serde_json::Value::from(name).to_string().as_bytes());
+            bundle.extend_from_slice(b": ");
+            bundle.extend_from_slice(buffer);
+        }
+        bundle.extend_from_slice(b"}\n");
+        bundle
+    }
+
+    /// Writes the metadata of this repository to `path` as a single JSON bundle; see
+    /// [`SignedRepository::bundle`].
+    pub fn write_bundle<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context(error::DirCreate { path: parent })?;
+        }
+        std::fs::write(path, self.bundle()).context(error::FileWrite { path })
+    }
+
+    /// Writes the metadata to the given directory as [`SignedRepository::write`] does, along with
+    /// gzip-compressed copies of the targets metadata, for clients that load the repository with
+    /// [`RepositoryLoader::compressed_metadata`](crate::RepositoryLoader::compressed_metadata).
+    #[cfg(feature = "gzip")]
+    pub fn write_compressed<P>(&self, outdir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        self.write(&outdir)?;
+        let consistent_snapshot = self.root.signed.signed.consistent_snapshot;
+        self.targets
+            .write_compressed(&outdir, consistent_snapshot)?;
+        if let Some(delegated_targets) = &self.delegated_targets {
+            for targets in &delegated_targets.roles {
+                targets.write_compressed(&outdir, consistent_snapshot)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every target listed in the metadata, including delegated targets, has a file
+    /// at the path it would be fetched from under `targets_dir`, with a matching hash. Use this
+    /// before publishing to avoid writing metadata for an incomplete repository.
+    pub fn check_targets<P>(&self, targets_dir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        TargetsWalker::check_targets(self, targets_dir.as_ref())
+    }
+
     /// Crawls a given directory and symlinks any targets found to the given
     /// "out" directory. If consistent snapshots are used, the target files
     /// are prefixed with their `sha256`.
@@ -394,6 +606,50 @@ impl TargetsWalker for SignedRepository {
     fn consistent_snapshot(&self) -> bool {
         self.root.signed.signed.consistent_snapshot
     }
+
+    fn storage_path_custom_key(&self) -> Option<&str> {
+        self.storage_path_custom_key.as_deref()
+    }
+}
+
+/// The name of the file [`SignedRepository::write_versions`] writes.
+pub const VERSIONS_INDEX: &str = "versions.json";
+
+/// An index of the metadata files of a [`SignedRepository`], written alongside them so that
+/// mirrors and clients can find the current files of a repository that uses consistent snapshots
+/// without guessing version numbers.
+///
+/// The index isn't signed. It's only a convenience for discovery, and the metadata it points to
+/// must still be verified.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct VersionsIndex {
+    /// The metadata file of each role, keyed by role name, such as `snapshot` or the name of a
+    /// delegated role.
+    pub roles: BTreeMap<String, IndexedRole>,
+}
+
+/// A metadata file listed in a [`VersionsIndex`].
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct IndexedRole {
+    /// The version of the role.
+    pub version: NonZeroU64,
+    /// The name of the file, including the version prefix if consistent snapshots are used.
+    pub filename: String,
+    /// The length of the file in bytes.
+    pub length: u64,
+    /// The SHA-256 digest of the file.
+    pub sha256: Decoded<Hex>,
+}
+
+impl IndexedRole {
+    fn new<T: Role>(role: &SignedRole<T>, consistent_snapshot: bool) -> Self {
+        Self {
+            version: role.signed.signed.version(),
+            filename: role.signed.signed.filename(consistent_snapshot),
+            length: role.length,
+            sha256: role.sha256.to_vec().into(),
+        }
+    }
 }
 
 /// A set of signed targets role metadata.
@@ -401,9 +657,17 @@ impl TargetsWalker for SignedRepository {
 pub struct SignedDelegatedTargets {
     pub(crate) roles: Vec<SignedRole<DelegatedTargets>>,
     pub(crate) consistent_snapshot: bool,
+    pub(crate) storage_path_custom_key: Option<String>,
 }
 
 impl SignedDelegatedTargets {
+    /// Sets the key of the targets' `custom` field that gives where a target is stored, as
+    /// [`SignedRepository::storage_path_custom_key`] does.
+    pub fn storage_path_custom_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.storage_path_custom_key = Some(key.into());
+        self
+    }
+
     /// Writes the metadata to the given directory. If consistent snapshots
     /// are used, the appropriate files are prefixed with their version.
     pub fn write<P>(&self, outdir: P, consistent_snapshot: bool) -> Result<()>
@@ -416,11 +680,35 @@ impl SignedDelegatedTargets {
         Ok(())
     }
 
+    /// Writes the metadata to the given directory as [`SignedDelegatedTargets::write`] does, along
+    /// with a gzip-compressed copy of each role's metadata.
+    #[cfg(feature = "gzip")]
+    pub fn write_compressed<P>(&self, outdir: P, consistent_snapshot: bool) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        for targets in &self.roles {
+            targets.write(&outdir, consistent_snapshot)?;
+            targets.write_compressed(&outdir, consistent_snapshot)?;
+        }
+        Ok(())
+    }
+
     /// Returns all `SignedRole<DelegatedTargets>>` contained by this `SignedDelegatedTargets`
     pub fn roles(self) -> Vec<SignedRole<DelegatedTargets>> {
         self.roles
     }
 
+    /// Checks that every target listed by these roles has a file at the path it would be fetched
+    /// from under `targets_dir`, with a matching hash. Use this before publishing to avoid writing
+    /// metadata for an incomplete repository.
+    pub fn check_targets<P>(&self, targets_dir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        TargetsWalker::check_targets(self, targets_dir.as_ref())
+    }
+
     /// Crawls a given directory and symlinks any targets found to the given
     /// "out" directory. If consistent snapshots are used, the target files
     /// are prefixed with their `sha256`.
@@ -572,6 +860,10 @@ impl TargetsWalker for SignedDelegatedTargets {
     fn consistent_snapshot(&self) -> bool {
         self.consistent_snapshot
     }
+
+    fn storage_path_custom_key(&self) -> Option<&str> {
+        self.storage_path_custom_key.as_deref()
+    }
 }
 
 /// `TargetsWalker` is used to unify the logic related to copying and linking targets.
@@ -583,6 +875,18 @@ trait TargetsWalker {
     fn targets(&self) -> HashMap<String, &Target>;
     /// Determines whether or not consistent snapshot filenames should be used
     fn consistent_snapshot(&self) -> bool;
+    /// The key of the `custom` field that gives a target's storage path, if storage paths are used
+    fn storage_path_custom_key(&self) -> Option<&str>;
+
+    /// Returns the storage path of `target`, named `name`, if storage paths are used and it has one
+    fn storage_path<'a>(&self, target: &'a Target, name: &str) -> Result<Option<&'a str>> {
+        match self.storage_path_custom_key() {
+            Some(key) => target
+                .storage_path(key)
+                .context(error::TargetStoragePath { target: name }),
+            None => Ok(None),
+        }
+    }
 
     /// Walks a given directory and calls the provided function with every file found.
     /// The function is given the file path, the output directory where the user expects
@@ -629,6 +933,39 @@ trait TargetsWalker {
         Ok(())
     }
 
+    /// Checks that every target has a file at the path it would be fetched from under
+    /// `targets_dir`, and that the file's hash matches the metadata.
+    fn check_targets(&self, targets_dir: &Path) -> Result<()> {
+        let targets = self.targets();
+        let mut names = targets.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let target = targets[name];
+            let storage_path = self.storage_path(target, name)?;
+            let path = if let Some(storage_path) = storage_path {
+                targets_dir.join(storage_path)
+            } else if self.consistent_snapshot() {
+                targets_dir.join(target.consistent_name(name))
+            } else {
+                targets_dir.join(name)
+            };
+            ensure!(
+                path.is_file(),
+                error::TargetFileMissing { target: name, path }
+            );
+            let found = Target::from_path(&path).context(error::TargetFromPath { path: &path })?;
+            ensure!(
+                found.hashes.sha256 == target.hashes.sha256,
+                error::HashMismatch {
+                    context: path.display().to_string(),
+                    calculated: hex::encode(&found.hashes.sha256),
+                    expected: hex::encode(&target.hashes.sha256),
+                }
+            );
+        }
+        Ok(())
+    }
+
     /// Determines the output path of a target based on consistent snapshot rules. Returns Err if
     /// the target already exists in the repo with a different hash, or if the target is not known
     /// to the repo.  (We're dealing with a signed repo, so it's too late to add targets.)
@@ -674,12 +1011,13 @@ trait TargetsWalker {
             }
         );
 
-        let dest = if self.consistent_snapshot() {
-            outdir.join(format!(
-                "{}.{}",
-                hex::encode(&target_from_path.hashes.sha256),
-                file_name
-            ))
+        // A target may name its storage location explicitly, in which case its name is not used
+        // to derive the output path.
+        let storage_path = self.storage_path(repo_target, file_name)?;
+        let dest = if let Some(storage_path) = storage_path {
+            outdir.join(storage_path)
+        } else if self.consistent_snapshot() {
+            outdir.join(repo_target.consistent_name(file_name))
         } else {
             outdir.join(&file_name)
         };
@@ -687,13 +1025,17 @@ trait TargetsWalker {
         // Return the target path, using the `TargetPath` enum that represents the type of file
         // that already exists at that path (if any)
         if !dest.exists() {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).context(error::DirCreate { path: parent })?;
+            }
             return Ok(TargetPath::New { path: dest });
         }
 
         // If we're using consistent snapshots, filenames include the checksum, so we know they're
-        // unique; if we're not, then there could be a target from another repo with the same name
-        // but different checksum.  We can't assume such conflicts are OK, so we fail.
-        if !self.consistent_snapshot() {
+        // unique; if we're not, or the target has an explicit storage path, then there could be a
+        // target from another repo at the same path but with a different checksum.  We can't
+        // assume such conflicts are OK, so we fail.
+        if !self.consistent_snapshot() || storage_path.is_some() {
             // Use DigestAdapter to get a streaming checksum of the file without needing to hold
             // its contents.
             let f = fs::File::open(&dest).context(error::FileOpen { path: &dest })?;
@@ -4,16 +4,20 @@
 
 //! Provides a `TargetsEditor` object for building and editing targets roles.
 
+use crate::editor::keys::{check_signing_keys, resolve_keys};
 use crate::editor::signed::{SignedDelegatedTargets, SignedRole};
+use crate::editor::SPEC_VERSION;
 use crate::error::{self, Result};
 use crate::fetch::fetch_max_size;
 use crate::key_source::KeySource;
 use crate::schema::decoded::{Decoded, Hex};
 use crate::schema::key::Key;
 use crate::schema::{
-    DelegatedRole, DelegatedTargets, Delegations, KeyHolder, PathSet, RoleType, Signed, Target,
-    Targets,
+    DelegatedRole, DelegatedTargets, Delegations, KeyHolder, PathSet, RoleId, RoleType, Signed,
+    Target, Targets,
 };
+use crate::sign::Sign;
+use crate::target_name_from_path;
 use crate::transport::Transport;
 use crate::Limits;
 use crate::Repository;
@@ -27,8 +31,6 @@ use std::num::NonZeroU64;
 use std::path::Path;
 use url::Url;
 
-const SPEC_VERSION: &str = "1.0.0";
-
 /// If you are not working with a repository that utilizes delegated targets, use the `RepositoryEditor`.
 ///
 /// `TargetsEditor` contains the various bits of data needed to construct
@@ -198,13 +200,13 @@ impl TargetsEditor {
         let target =
             Target::from_path(target_path).context(error::TargetFromPath { path: target_path })?;
 
-        // Get the file name as a string
-        let target_name = target_path
-            .file_name()
-            .context(error::NoFileName { path: target_path })?
-            .to_str()
-            .context(error::PathUtf8 { path: target_path })?
-            .to_owned();
+        // Get the file name as a target name
+        let target_name = target_name_from_path(Path::new(
+            target_path
+                .file_name()
+                .context(error::NoFileName { path: target_path })?,
+        ))
+        .context(error::PathUtf8 { path: target_path })?;
 
         self.add_target(&target_name, target);
         Ok(self)
@@ -310,6 +312,20 @@ impl TargetsEditor {
         Ok(self)
     }
 
+    /// Sets the paths delegated to `role`, which must be delegated by this role. Targets that `role`
+    /// already lists outside of `paths` will no longer be trusted by clients.
+    pub fn set_paths(&mut self, role: &str, paths: PathSet) -> Result<&mut Self> {
+        let delegations = self.delegations.as_mut().context(error::NoDelegations)?;
+        let delegated_role = delegations
+            .roles
+            .iter_mut()
+            .chain(self.new_roles.iter_mut().flatten())
+            .find(|delegated_role| delegated_role.name == role)
+            .context(error::DelegateNotFound { name: role })?;
+        delegated_role.paths = paths;
+        Ok(self)
+    }
+
     /// Adds a `DelegatedRole` to `new_roles`
     /// To use `delegate_role()` a new `Targets` should be created using `TargetsEditor::new()`
     /// followed by `create_signed()` to provide a `Signed<DelegatedTargets>` for the new role.
@@ -334,6 +350,7 @@ impl TargetsEditor {
                     signed: targets.signed.targets,
                     signatures: targets.signatures,
                 }),
+                _extra: HashMap::new(),
             });
         Ok(self)
     }
@@ -392,10 +409,7 @@ impl TargetsEditor {
             "max targets limit",
         )?);
         // Load incoming role metadata as Signed<Targets>
-        let role: Signed<crate::schema::Targets> =
-            serde_json::from_reader(reader).context(error::ParseMetadata {
-                role: RoleType::Targets,
-            })?;
+        let role: Signed<crate::schema::Targets> = crate::parse_metadata(reader)?;
 
         // Create `Signed<DelegatedTargets>` for the role
         let delegated_targets = Signed {
@@ -461,17 +475,14 @@ impl TargetsEditor {
     }
 
     /// Creates a `KeyHolder` to sign the `Targets` role with the signing keys provided
-    fn create_key_holder(&self, keys: &[Box<dyn KeySource>]) -> Result<KeyHolder> {
+    fn create_key_holder(&self, keys: &[Box<dyn Sign>]) -> Result<KeyHolder> {
         // There isn't a KeyHolder, so create one based on the provided keys
         let mut delegations = Delegations::new();
         // First create the tuf key pairs and keyids
         let mut keyids = Vec::new();
         let mut key_pairs = HashMap::new();
-        for source in keys {
-            let key_pair = source
-                .as_sign()
-                .context(error::KeyPairFromKeySource)?
-                .tuf_key();
+        for key_pair in keys {
+            let key_pair = key_pair.tuf_key();
             key_pairs.insert(
                 key_pair
                     .key_id()
@@ -496,6 +507,7 @@ impl TargetsEditor {
             terminating: false,
             keyids,
             targets: None,
+            _extra: HashMap::new(),
         });
         Ok(KeyHolder::Delegations(delegations))
     }
@@ -506,16 +518,39 @@ impl TargetsEditor {
     /// `create_signed()` guarantees that only 1 `Signed<DelegatedTargets>` is created and that it is the one representing
     /// the current targets. `create_signed()` should be used whenever the result of `TargetsEditor` is not being written.
     pub fn create_signed(&self, keys: &[Box<dyn KeySource>]) -> Result<Signed<DelegatedTargets>> {
-        let rng = SystemRandom::new();
+        let keys = resolve_keys(keys)?;
+        let (key_holder, role_id) = self.signing_role(&keys)?;
+        check_signing_keys(&[(&key_holder, role_id)], &keys, false)?;
+        self.create_signed_with(&key_holder, &keys)
+    }
+
+    /// Returns the metadata that lists the keys of the role being edited, and the role's ID.
+    pub(crate) fn signing_role(&self, keys: &[Box<dyn Sign>]) -> Result<(KeyHolder, RoleId)> {
         let key_holder = if let Some(key_holder) = self.key_holder.as_ref() {
             key_holder.clone()
         } else {
             self.create_key_holder(keys)?
         };
+        let role_id = if self.name == "targets" {
+            RoleId::StandardRole(RoleType::Targets)
+        } else {
+            RoleId::DelegatedRole(self.name.clone())
+        };
+        Ok((key_holder, role_id))
+    }
+
+    /// Creates a `Signed<DelegatedTargets>` for only this role, as `create_signed()` does, with
+    /// keys the caller has already checked.
+    pub(crate) fn create_signed_with(
+        &self,
+        key_holder: &KeyHolder,
+        keys: &[Box<dyn Sign>],
+    ) -> Result<Signed<DelegatedTargets>> {
+        let rng = SystemRandom::new();
         // create a signed role for the targets being edited
         let targets = self
             .build_targets()
-            .and_then(|targets| SignedRole::new(targets, &key_holder, keys, &rng))?;
+            .and_then(|targets| SignedRole::sign(targets, key_holder, keys, &rng))?;
         Ok(targets.signed)
     }
 
@@ -525,16 +560,14 @@ impl TargetsEditor {
     pub fn sign(&self, keys: &[Box<dyn KeySource>]) -> Result<SignedDelegatedTargets> {
         let rng = SystemRandom::new();
         let mut roles = Vec::new();
-        let key_holder = if let Some(key_holder) = self.key_holder.as_ref() {
-            key_holder.clone()
-        } else {
-            self.create_key_holder(keys)?
-        };
+        let keys = resolve_keys(keys)?;
+        let (key_holder, role_id) = self.signing_role(&keys)?;
+        check_signing_keys(&[(&key_holder, role_id)], &keys, false)?;
 
         // create a signed role for the targets we are editing
         let signed_targets = self
             .build_targets()
-            .and_then(|targets| SignedRole::new(targets, &key_holder, keys, &rng))?;
+            .and_then(|targets| SignedRole::sign(targets, &key_holder, &keys, &rng))?;
         roles.push(signed_targets);
         // create signed roles for any role metadata we added to this targets
         if let Some(new_roles) = &self.new_roles {
@@ -551,6 +584,7 @@ impl TargetsEditor {
         Ok(SignedDelegatedTargets {
             roles,
             consistent_snapshot: false,
+            storage_path_custom_key: None,
         })
     }
 }
@@ -162,4 +162,176 @@ mod tests {
         assert!(editor.snapshot_expires.is_none());
         assert!(editor.timestamp_expires.is_none());
     }
+
+    // Make sure the spec version the editor writes is one it accepts when loading
+    #[test]
+    fn spec_version_supported() {
+        use crate::editor::{spec_version_supported, SPEC_VERSION};
+
+        assert!(spec_version_supported(SPEC_VERSION));
+        assert!(!spec_version_supported("2.0.0"));
+        assert!(spec_version_supported("1.0"));
+        assert!(!spec_version_supported("1.1.0"));
+        assert!(!spec_version_supported("x.y"));
+    }
+
+    // Make sure metadata whose snapshot disagrees with the roles written alongside it is rejected
+    #[test]
+    fn snapshot_version_desync() {
+        use crate::editor::check_snapshot_versions;
+        use crate::error::Error;
+
+        let snapshot: Signed<Snapshot> = serde_json::from_str(include_str!(
+            "../../tests/data/tuf-reference-impl/metadata/snapshot.json"
+        ))
+        .unwrap();
+        let mut snapshot = snapshot.signed;
+        let version = |v| NonZeroU64::new(v).unwrap();
+        let targets = |v| vec![("targets.json".to_owned(), version(v))];
+        let listed = snapshot.meta["targets.json"].version.get();
+        check_snapshot_versions(&snapshot, version(1), &targets(listed)).unwrap();
+
+        let err = check_snapshot_versions(&snapshot, version(1), &targets(listed + 1)).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SnapshotVersionMismatch { ref file, listed: Some(l), version: v, .. }
+                if file == "targets.json" && l == listed && v == listed + 1
+        ));
+
+        // A role missing from snapshot is a mismatch too
+        let mut written = targets(listed);
+        written.push(("role1.json".to_owned(), version(1)));
+        snapshot.meta.remove("role1.json");
+        assert!(matches!(
+            check_snapshot_versions(&snapshot, version(1), &written).unwrap_err(),
+            Error::SnapshotVersionMismatch { listed: None, .. }
+        ));
+
+        // Root is only checked if snapshot lists it
+        let mut root_meta = snapshot.meta["targets.json"].clone();
+        root_meta.version = version(2);
+        snapshot.meta.insert("root.json".to_owned(), root_meta);
+        check_snapshot_versions(&snapshot, version(2), &targets(listed)).unwrap();
+        let err = check_snapshot_versions(&snapshot, version(1), &targets(listed)).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SnapshotVersionMismatch { ref file, .. } if file == "root.json"
+        ));
+    }
+
+    // Make sure signing keys that aren't authorized for the role are skipped, or rejected if
+    // strict
+    #[test]
+    fn unauthorized_signing_key() {
+        use crate::editor::signed::SignedRole;
+        use crate::error::Error;
+        use crate::key_source::KeySource;
+        use crate::schema::{KeyHolder, Root};
+
+        let root: Signed<Root> =
+            serde_json::from_slice(&std::fs::read(root_path()).unwrap()).unwrap();
+        let key_holder = KeyHolder::Root(root.signed.clone());
+        let keys: Vec<Box<dyn KeySource>> = vec![
+            Box::new(LocalKeySource { path: key_path() }),
+            Box::new(LocalKeySource {
+                path: key_path().with_file_name("snakeoil_2.pem"),
+            }),
+        ];
+        let rng = ring::rand::SystemRandom::new();
+
+        let signed = SignedRole::new(root.signed.clone(), &key_holder, &keys, &rng).unwrap();
+        assert_eq!(signed.signed().signatures.len(), 1);
+
+        let err = SignedRole::new_strict(root.signed, &key_holder, &keys, &rng).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UnauthorizedSigningKey { ref roles, .. } if roles == "root"
+        ));
+    }
+
+    // Make sure the timestamp describes the snapshot as it's written: its version, and the length
+    // and digest of its bytes
+    #[test]
+    fn timestamp_matches_snapshot() {
+        let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+        let mut editor = RepositoryEditor::new(root_path()).unwrap();
+        editor
+            .targets_version(NonZeroU64::new(1).unwrap())
+            .unwrap()
+            .targets_expires(expires)
+            .unwrap()
+            .snapshot_version(NonZeroU64::new(5).unwrap())
+            .snapshot_expires(expires)
+            .timestamp_version(NonZeroU64::new(9).unwrap())
+            .timestamp_expires(expires);
+        let signed = editor
+            .sign(&[Box::new(LocalKeySource { path: key_path() })])
+            .unwrap();
+
+        let timestamp = &signed.timestamp.signed().signed;
+        assert_eq!(timestamp.version.get(), 9);
+        let meta = timestamp.snapshot_meta().unwrap();
+        assert_eq!(meta.version.get(), 5);
+        assert_eq!(meta.length, signed.snapshot.buffer().len() as u64);
+        assert_eq!(
+            meta.hashes.sha256.as_ref(),
+            ring::digest::digest(&ring::digest::SHA256, signed.snapshot.buffer()).as_ref()
+        );
+    }
+
+    // Make sure each key source is only asked for its signing key once while signing every role,
+    // since remote key sources may make a request each time
+    #[test]
+    fn key_source_resolved_once() {
+        use crate::key_source::KeySource;
+        use crate::sign::Sign;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        #[derive(Debug)]
+        struct CountingKeySource {
+            source: LocalKeySource,
+            count: Arc<AtomicUsize>,
+        }
+
+        impl KeySource for CountingKeySource {
+            fn as_sign(
+                &self,
+            ) -> std::result::Result<
+                Box<dyn Sign>,
+                Box<dyn std::error::Error + Send + Sync + 'static>,
+            > {
+                self.count.fetch_add(1, Ordering::SeqCst);
+                self.source.as_sign()
+            }
+
+            fn write(
+                &self,
+                value: &str,
+                key_id_hex: &str,
+            ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+            {
+                self.source.write(value, key_id_hex)
+            }
+        }
+
+        let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+        let mut editor = RepositoryEditor::new(root_path()).unwrap();
+        editor
+            .targets_version(NonZeroU64::new(1).unwrap())
+            .unwrap()
+            .targets_expires(expires)
+            .unwrap()
+            .snapshot_version(NonZeroU64::new(1).unwrap())
+            .snapshot_expires(expires)
+            .timestamp_version(NonZeroU64::new(1).unwrap())
+            .timestamp_expires(expires);
+        let count = Arc::new(AtomicUsize::new(0));
+        let keys: Vec<Box<dyn KeySource>> = vec![Box::new(CountingKeySource {
+            source: LocalKeySource { path: key_path() },
+            count: Arc::clone(&count),
+        })];
+        editor.sign(&keys).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
 }
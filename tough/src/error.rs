@@ -29,6 +29,36 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// A task on tokio's blocking thread pool was cancelled before it finished, such as when the
+    /// runtime is shutting down.
+    #[snafu(display("Blocking task was cancelled before it finished"))]
+    BlockingTaskCancelled { backtrace: Backtrace },
+
+    #[snafu(display("Failed to parse metadata bundle: {}", source))]
+    BundleParse {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to read metadata bundle: {}", source))]
+    BundleRead {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    /// A downloaded metadata file has the same version as a previously downloaded metadata file,
+    /// but different content.
+    #[snafu(display(
+        "Found different {} metadata with the same version {} as previously fetched",
+        role,
+        version
+    ))]
+    ContentChangedWithoutVersionBump {
+        role: RoleType,
+        version: u64,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Failed to create temp directory for the repository datastore: {}",
         source
@@ -38,6 +68,10 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// The file mode requested for the datastore would make its files world-writable.
+    #[snafu(display("Datastore file mode {} must not be world-writable", mode))]
+    DatastoreFileMode { mode: String, backtrace: Backtrace },
+
     /// The library failed to create a file in the datastore.
     #[snafu(display("Failed to create file at datastore path {}: {}", path.display(), source))]
     DatastoreCreate {
@@ -71,6 +105,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// A decompressor could not be created for a target.
+    #[snafu(display("Failed to start decompressing target: {}", source))]
+    Decompress {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to create directory '{}': {}", path.display(), source))]
     DirCreate {
         path: PathBuf,
@@ -78,6 +119,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// A fetched metadata file was empty.
+    #[snafu(display("{} metadata is empty", role))]
+    EmptyMetadata {
+        role: RoleType,
+        backtrace: Backtrace,
+    },
+
     /// A metadata file has expired.
     #[snafu(display("{} metadata is expired", role))]
     ExpiredMetadata {
@@ -190,11 +238,27 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// A metadata file lists another metadata file with a length of zero, which can never be valid.
+    #[snafu(display("Meta for {:?} in {} metadata has a length of zero", file, role))]
+    MetaLengthZero {
+        file: &'static str,
+        role: RoleType,
+        backtrace: Backtrace,
+    },
+
     /// A required reference to a metadata file is missing from a metadata file.
     #[snafu(display("Meta for {:?} missing from {} metadata", file, role))]
     MetaMissing {
-        file: &'static str,
+        file: String,
+        role: RoleType,
+        backtrace: Backtrace,
+    },
+
+    /// A metadata file could not be read.
+    #[snafu(display("Failed to read {} metadata: {}", role, source))]
+    MetadataRead {
         role: RoleType,
+        source: io::Error,
         backtrace: Backtrace,
     },
 
@@ -276,6 +340,18 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// The root fetched to bootstrap trust doesn't match the fingerprint it was expected to have.
+    #[snafu(display(
+        "Root fingerprint mismatch: expected {}, calculated {}",
+        expected,
+        calculated
+    ))]
+    RootFingerprintMismatch {
+        expected: String,
+        calculated: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to serialize role '{}' for signing: {}", role, source))]
     SerializeRole {
         role: String,
@@ -290,6 +366,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to serialize versions index: {}", source))]
+    SerializeVersionsIndex {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to sign message"))]
     Sign {
         source: ring::error::Unspecified,
@@ -302,6 +384,20 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// The snapshot being written doesn't list a role at the version written alongside it.
+    #[snafu(display(
+        "Snapshot lists {} at {}, but version {} is being written",
+        file,
+        listed.map_or_else(|| "no version".to_owned(), |v| format!("version {}", v)),
+        version
+    ))]
+    SnapshotVersionMismatch {
+        file: String,
+        listed: Option<u64>,
+        version: u64,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Unable to find signing keys for role '{}'", role))]
     SigningKeysNotFound { role: String },
 
@@ -327,6 +423,14 @@ pub enum Error {
         latest_known_time: DateTime<Utc>,
     },
 
+    /// A target listed in the metadata has no file at the path it would be fetched from.
+    #[snafu(display("Target '{}' is missing: '{}' does not exist", target, path.display()))]
+    TargetFileMissing {
+        target: String,
+        path: PathBuf,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Refusing to replace {} with requested {} for target {}", found, expected, path.display()))]
     TargetFileTypeMismatch {
         expected: String,
@@ -386,6 +490,44 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// A metadata file's `_type` does not match the role it was loaded as, e.g. a mirror served
+    /// `snapshot.json` where `targets.json` was expected.
+    #[snafu(display(
+        "Expected metadata with _type {} but found {}",
+        expected,
+        found.as_deref().unwrap_or("no _type")
+    ))]
+    WrongMetadataType {
+        expected: &'static str,
+        found: Option<String>,
+        backtrace: Backtrace,
+    },
+
+    /// A specific snapshot version was requested from a repository that does not use consistent
+    /// snapshots, so older snapshot versions cannot be fetched.
+    #[snafu(display(
+        "Cannot load snapshot version {}: the repository does not use consistent snapshots",
+        version
+    ))]
+    SnapshotVersionRequiresConsistentSnapshot { version: u64, backtrace: Backtrace },
+
+    /// A target's `custom` value under the key set by
+    /// [`RepositoryLoader::target_url_custom_key`][crate::RepositoryLoader::target_url_custom_key]
+    /// isn't an absolute URL.
+    #[snafu(display("Target's custom '{}' is not an absolute URL: {}", key, value))]
+    TargetCustomUrl {
+        key: String,
+        value: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid storage path for target '{}': {}", target, source))]
+    TargetStoragePath {
+        target: String,
+        source: crate::schema::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Error reading data from '{}': {}", url, source))]
     CacheFileRead {
         url: Url,
@@ -414,13 +556,34 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
-    #[snafu(display("The target '{}' was not found", target_name))]
+    #[snafu(display("The target '{}' was not found: {}", target_name, source))]
     CacheTargetMissing {
         target_name: String,
-        source: crate::schema::Error,
+        source: TargetResolutionError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Error saving target file to '{}': {}", path.display(), source))]
+    SaveTarget {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Error moving saved target file to '{}': {}", path.display(), source))]
+    SaveTargetPersist {
+        path: PathBuf,
+        source: tempfile::PersistError,
         backtrace: Backtrace,
     },
 
+    #[snafu(display("The target '{}' is not listed in the repository", name))]
+    SaveTargetMissing { name: String, backtrace: Backtrace },
+
+    /// A target's name can't be used as a path under the directory it's being saved to.
+    #[snafu(display("The target name '{}' is not a safe relative path", name))]
+    SaveTargetName { name: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to walk directory tree '{}': {}", directory.display(), source))]
     WalkDir {
         directory: PathBuf,
@@ -455,10 +618,35 @@ pub enum Error {
     #[snafu(display("Delegated roles are not consistent for {}", name))]
     DelegatedRolesNotConsistent { name: String },
 
+    /// A delegated role is delegated to, directly or indirectly, by itself.
+    #[snafu(display("Delegation cycle: {} -> {}", chain, name))]
+    DelegationCycle { name: String, chain: String },
+
+    /// A delegated role is further from the top-level targets role than the loader allows.
+    #[snafu(display(
+        "Delegated role {} is more than {} delegations deep: {}",
+        name,
+        max_depth,
+        chain
+    ))]
+    DelegationDepth {
+        name: String,
+        max_depth: u64,
+        chain: String,
+    },
+
     /// Target doesn't have proper permissions from parent delegations
     #[snafu(display("Invalid file permissions"))]
     InvalidPath { source: crate::schema::Error },
 
+    /// A delegated role lists a key that the role delegating to it doesn't declare.
+    #[snafu(display(
+        "Delegated role {} lists key {}, which isn't declared by its delegator",
+        role,
+        keyid
+    ))]
+    MissingDelegationKey { role: String, keyid: String },
+
     #[snafu(display("Role missing from snapshot meta: {}", name))]
     RoleNotInMeta { name: String },
 
@@ -527,6 +715,9 @@ pub enum Error {
         threshold: u64,
     },
 
+    #[snafu(display("Key {} is not authorized to sign for {}", key_id, roles))]
+    UnauthorizedSigningKey { key_id: String, roles: String },
+
     #[snafu(display("The targets editor was not cleared"))]
     TargetsEditorSome,
 }
@@ -537,3 +728,68 @@ impl From<Error> for std::io::Error {
         Self::new(std::io::ErrorKind::Other, err)
     }
 }
+
+/// Why a target couldn't be resolved, as returned by [`Repository::resolve_target`].
+///
+/// Targets are resolved as the TUF specification describes: the top-level targets role is
+/// consulted first, then each delegated role whose paths match the target, in order and depth
+/// first, until the target is found or a terminating delegation ends the search.
+///
+/// [`Repository::resolve_target`]: crate::Repository::resolve_target
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
+pub enum TargetResolutionError {
+    /// The target isn't listed by any role.
+    #[snafu(display("Target '{}' is not listed in any targets metadata", target))]
+    NotListed {
+        /// The name of the target.
+        target: String,
+    },
+
+    /// The target is listed by `listed_by`, but `role`, a terminating delegation for a path that
+    /// matches the target, was consulted first and doesn't list it.
+    #[snafu(display(
+        "Target '{}' is listed by role '{}', but terminating delegation '{}' was consulted first",
+        target,
+        listed_by,
+        role
+    ))]
+    ExcludedByTerminating {
+        /// The name of the target.
+        target: String,
+        /// The terminating delegated role that ended the search.
+        role: String,
+        /// The delegated role that lists the target.
+        listed_by: String,
+    },
+
+    /// The target is listed by `role`, but isn't within the paths delegated to `role`, or to one
+    /// of the roles that delegate to it.
+    #[snafu(display(
+        "Target '{}' is listed by role '{}', but is outside the paths delegated to it",
+        target,
+        role
+    ))]
+    OutsideDelegatedPaths {
+        /// The name of the target.
+        target: String,
+        /// The delegated role that lists the target.
+        role: String,
+    },
+
+    /// The target's paths are delegated to `role`, whose metadata couldn't be loaded. Why is
+    /// listed by [`Repository::unreachable_delegations`].
+    ///
+    /// [`Repository::unreachable_delegations`]: crate::Repository::unreachable_delegations
+    #[snafu(display(
+        "Target '{}' is delegated to role '{}', whose metadata couldn't be loaded",
+        target,
+        role
+    ))]
+    Unreachable {
+        /// The name of the target.
+        target: String,
+        /// The delegated role whose metadata couldn't be loaded.
+        role: String,
+    },
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::error::{self, Result};
+use crate::hash::{HashAlgorithm, Sha256};
 use crate::io::{DigestAdapter, MaxSizeAdapter};
+use crate::trace;
 use crate::transport::Transport;
 use snafu::ResultExt;
 use std::io::Read;
@@ -14,10 +16,11 @@ pub(crate) fn fetch_max_size(
     max_size: u64,
     specifier: &'static str,
 ) -> Result<impl Read + Send> {
+    let reader = transport
+        .fetch(url.clone())
+        .context(error::Transport { url: url.clone() })?;
     Ok(MaxSizeAdapter::new(
-        transport
-            .fetch(url.clone())
-            .context(error::Transport { url })?,
+        trace::fetched(reader, &url, max_size, specifier, None),
         specifier,
         max_size,
     ))
@@ -30,15 +33,62 @@ pub(crate) fn fetch_sha256(
     specifier: &'static str,
     sha256: &[u8],
 ) -> Result<impl Read + Send> {
-    Ok(DigestAdapter::sha256(
+    fetch_digest(transport, url, size, specifier, &Sha256, sha256)
+}
+
+/// Fetches from `url` up to `size` bytes, which must match `digest` calculated with `algorithm`.
+pub(crate) fn fetch_digest(
+    transport: &dyn Transport,
+    url: Url,
+    size: u64,
+    specifier: &'static str,
+    algorithm: &dyn HashAlgorithm,
+    digest: &[u8],
+) -> Result<impl Read + Send> {
+    let reader = transport
+        .fetch(url.clone())
+        .context(error::Transport { url: url.clone() })?;
+    // The trace only calculates sha256 digests
+    let sha256 = Some(digest).filter(|_| algorithm.name() == Sha256.name());
+    Ok(DigestAdapter::new(
         Box::new(MaxSizeAdapter::new(
-            transport
-                .fetch(url.clone())
-                .context(error::Transport { url: url.clone() })?,
+            trace::fetched(reader, &url, size, specifier, sha256),
             specifier,
             size,
         )),
-        sha256,
+        algorithm,
+        digest,
         url,
     ))
 }
+
+/// Fetches gzip-compressed metadata from `url`. The size limit and hash apply to the decompressed
+/// bytes, so compressed metadata is checked against the same values in snapshot.json as the
+/// uncompressed file would be.
+#[cfg(feature = "gzip")]
+pub(crate) fn fetch_gzip(
+    transport: &dyn Transport,
+    url: Url,
+    max_size: u64,
+    specifier: &'static str,
+    sha256: Option<&[u8]>,
+) -> Result<Box<dyn Read + Send>> {
+    let compressed = transport
+        .fetch(url.clone())
+        .context(error::Transport { url: url.clone() })?;
+    let decompressed = MaxSizeAdapter::new(
+        trace::fetched(
+            Box::new(flate2::read::GzDecoder::new(compressed)),
+            &url,
+            max_size,
+            specifier,
+            sha256,
+        ),
+        specifier,
+        max_size,
+    );
+    Ok(match sha256 {
+        Some(sha256) => Box::new(DigestAdapter::sha256(Box::new(decompressed), sha256, url)),
+        None => Box::new(decompressed),
+    })
+}
@@ -0,0 +1,114 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides the hash algorithms that targets can be verified with.
+//!
+//! A target may list digests for more than one algorithm in its `hashes`. When reading a target, a
+//! [`Repository`] verifies it with the strongest algorithm it recognizes among those listed. The
+//! built-in [`Sha256`] and [`Sha512`] are always recognized; others can be added with
+//! [`RepositoryLoader::hash_algorithm`].
+//!
+//! [`Repository`]: crate::Repository
+//! [`RepositoryLoader::hash_algorithm`]: crate::RepositoryLoader::hash_algorithm
+
+use crate::schema::Hashes;
+use dyn_clone::DynClone;
+use ring::digest::{Context, SHA256, SHA512};
+use std::fmt::Debug;
+
+/// A hash algorithm that targets can be verified with.
+///
+/// Inclusion of the `DynClone` trait means that you will need to implement `Clone` when
+/// implementing a `HashAlgorithm`.
+pub trait HashAlgorithm: Debug + DynClone + Send + Sync {
+    /// The name of the algorithm, as a key in a target's `hashes`. For example, "sha256".
+    fn name(&self) -> &str;
+
+    /// The strength of the algorithm against collisions, in bits. Where a target lists digests for
+    /// more than one recognized algorithm, the strongest is used.
+    fn strength(&self) -> u32;
+
+    /// Starts calculating a digest.
+    fn start(&self) -> Box<dyn HashContext>;
+}
+
+// Implements `Clone` for `HashAlgorithm` trait objects (i.e. on `Box::<dyn Clone>`).
+dyn_clone::clone_trait_object!(HashAlgorithm);
+
+/// A digest being calculated by a [`HashAlgorithm`].
+pub trait HashContext: Send {
+    /// Adds `data` to the digest.
+    fn update(&mut self, data: &[u8]);
+
+    /// Finishes the digest, returning its bytes.
+    fn finish(self: Box<Self>) -> Vec<u8>;
+}
+
+impl HashContext for Context {
+    fn update(&mut self, data: &[u8]) {
+        Context::update(self, data);
+    }
+
+    fn finish(self: Box<Self>) -> Vec<u8> {
+        Context::finish(*self).as_ref().to_vec()
+    }
+}
+
+/// The SHA-256 hash algorithm, which every target lists a digest for.
+#[derive(Debug, Clone, Copy)]
+pub struct Sha256;
+
+impl HashAlgorithm for Sha256 {
+    fn name(&self) -> &str {
+        "sha256"
+    }
+
+    fn strength(&self) -> u32 {
+        128
+    }
+
+    fn start(&self) -> Box<dyn HashContext> {
+        Box::new(Context::new(&SHA256))
+    }
+}
+
+/// The SHA-512 hash algorithm.
+#[derive(Debug, Clone, Copy)]
+pub struct Sha512;
+
+impl HashAlgorithm for Sha512 {
+    fn name(&self) -> &str {
+        "sha512"
+    }
+
+    fn strength(&self) -> u32 {
+        256
+    }
+
+    fn start(&self) -> Box<dyn HashContext> {
+        Box::new(Context::new(&SHA512))
+    }
+}
+
+/// Returns the built-in hash algorithms.
+pub(crate) fn built_in() -> Vec<Box<dyn HashAlgorithm>> {
+    vec![Box::new(Sha256), Box::new(Sha512)]
+}
+
+/// Selects the strongest of `algorithms` that `hashes` lists a digest for, returning it along with
+/// the digest. Where algorithms are equally strong, the last is selected, so that one added to a
+/// repository takes priority over a built-in one. If none is listed, SHA-256 is selected.
+pub(crate) fn select<'a>(
+    algorithms: &'a [Box<dyn HashAlgorithm>],
+    hashes: &Hashes,
+) -> (&'a dyn HashAlgorithm, Vec<u8>) {
+    algorithms
+        .iter()
+        .filter_map(|algorithm| {
+            hashes
+                .get(algorithm.name())
+                .map(|digest| (algorithm.as_ref(), digest))
+        })
+        .max_by_key(|(algorithm, _)| algorithm.strength())
+        .unwrap_or_else(|| (&Sha256, hashes.sha256.to_vec()))
+}
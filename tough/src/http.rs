@@ -9,6 +9,7 @@ use snafu::ResultExt;
 use snafu::Snafu;
 use std::cmp::Ordering;
 use std::io::Read;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 use url::Url;
 
@@ -94,7 +95,10 @@ impl HttpTransportBuilder {
 
     /// Construct an [`HttpTransport`] transport from this builder's settings.
     pub fn build(self) -> HttpTransport {
-        HttpTransport { settings: self }
+        HttpTransport {
+            settings: self,
+            client: Arc::default(),
+        }
     }
 }
 
@@ -105,9 +109,31 @@ impl HttpTransportBuilder {
 /// - 403: Forbidden. (Some services return this code when a file does not exist.)
 /// - 404: Not Found.
 /// - 410: Gone.
-#[derive(Clone, Copy, Debug, Default)]
+///
+/// Every fetch made by an `HttpTransport`, or by its clones, shares one HTTP client, so that
+/// connections to the repository are pooled and reused.
+#[derive(Clone, Debug, Default)]
 pub struct HttpTransport {
     settings: HttpTransportBuilder,
+    /// The shared client, which is created by the first fetch.
+    client: Arc<Mutex<Option<Client>>>,
+}
+
+impl HttpTransport {
+    /// Returns the client shared by this transport's fetches, creating it if this is the first.
+    fn client(&self) -> Result<Client, HttpError> {
+        let mut client = self.client.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(client) = &*client {
+            return Ok(client.clone());
+        }
+        let new_client = ClientBuilder::new()
+            .timeout(self.settings.timeout)
+            .connect_timeout(self.settings.connect_timeout)
+            .build()
+            .context(HttpClient)?;
+        *client = Some(new_client.clone());
+        Ok(new_client)
+    }
 }
 
 /// Implement the `tough` `Transport` trait for `HttpRetryTransport`
@@ -117,7 +143,8 @@ impl Transport for HttpTransport {
     fn fetch(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError> {
         let mut r = RetryState::new(self.settings.initial_backoff);
         Ok(Box::new(
-            fetch_with_retries(&mut r, &self.settings, &url)
+            self.client()
+                .and_then(|client| fetch_with_retries(&mut r, &self.settings, client, &url))
                 .map_err(|e| TransportError::from((url, e)))?,
         ))
     }
@@ -128,6 +155,7 @@ impl Transport for HttpTransport {
 pub struct RetryRead {
     retry_state: RetryState,
     settings: HttpTransportBuilder,
+    client: Client,
     response: Response,
     url: Url,
 }
@@ -156,9 +184,13 @@ impl Read for RetryRead {
             self.err_if_no_range_support(retry_err)?;
             // wait, then retry the request (with a range header).
             std::thread::sleep(self.retry_state.wait);
-            let new_retry_read =
-                fetch_with_retries(&mut self.retry_state, &self.settings, &self.url)
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            let new_retry_read = fetch_with_retries(
+                &mut self.retry_state,
+                &self.settings,
+                self.client.clone(),
+                &self.url,
+            )
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
             // the new fetch succeeded so we need to replace our read object with the new one.
             self.response = new_retry_read.response;
         }
@@ -236,19 +268,15 @@ impl RetryState {
     }
 }
 
-/// Sends a `GET` request to the `url`. Retries the request as necessary per the `ClientSettings`.
+/// Sends a `GET` request to the `url` with `client`. Retries the request as necessary per the
+/// `ClientSettings`.
 fn fetch_with_retries(
     r: &mut RetryState,
     cs: &HttpTransportBuilder,
+    client: Client,
     url: &Url,
 ) -> Result<RetryRead, HttpError> {
     trace!("beginning fetch for '{}'", url);
-    // create a reqwest client
-    let client = ClientBuilder::new()
-        .timeout(cs.timeout)
-        .connect_timeout(cs.connect_timeout)
-        .build()
-        .context(HttpClient)?;
 
     // retry loop
     loop {
@@ -264,6 +292,7 @@ fn fetch_with_retries(
                 return Ok(RetryRead {
                     retry_state: *r,
                     settings: *cs,
+                    client,
                     response,
                     url: url.clone(),
                 });
@@ -1,8 +1,10 @@
 // Copyright 2019 Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use crate::datastore::PendingFile;
 use crate::error;
-use ring::digest::{Context, SHA256};
+use crate::hash::{HashAlgorithm, HashContext, Sha256};
+use log::warn;
 use std::io::{self, Read};
 use url::Url;
 
@@ -10,45 +12,55 @@ pub(crate) struct DigestAdapter {
     url: Url,
     reader: Box<dyn Read + Send>,
     hash: Vec<u8>,
-    digest: Option<Context>,
+    digest: Option<Box<dyn HashContext>>,
+    /// The digest of the complete stream, once the end of file has been reached.
+    calculated: Option<Vec<u8>>,
 }
 
 impl DigestAdapter {
     pub(crate) fn sha256(reader: Box<dyn Read + Send>, hash: &[u8], url: Url) -> Self {
+        Self::new(reader, &Sha256, hash, url)
+    }
+
+    pub(crate) fn new(
+        reader: Box<dyn Read + Send>,
+        algorithm: &dyn HashAlgorithm,
+        hash: &[u8],
+        url: Url,
+    ) -> Self {
         Self {
             url,
             reader,
             hash: hash.to_owned(),
-            digest: Some(Context::new(&SHA256)),
+            digest: Some(algorithm.start()),
+            calculated: None,
         }
     }
 }
 
 impl Read for DigestAdapter {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        assert!(
-            self.digest.is_some(),
-            "DigestAdapter::read called after end of file"
-        );
+        if let Some(digest) = &mut self.digest {
+            let size = self.reader.read(buf)?;
+            if size != 0 {
+                digest.update(&buf[..size]);
+                return Ok(size);
+            }
+            self.calculated = Some(self.digest.take().unwrap().finish());
+        }
 
-        let size = self.reader.read(buf)?;
-        if size == 0 {
-            let result = std::mem::replace(&mut self.digest, None).unwrap().finish();
-            if result.as_ref() != self.hash.as_slice() {
-                error::HashMismatch {
-                    context: self.url.to_string(),
-                    calculated: hex::encode(result),
-                    expected: hex::encode(&self.hash),
-                }
-                .fail()?;
+        // We're at the end of file. Reads past the end fail the same way as the first, so that a
+        // mismatch can't be missed by a caller that reads again.
+        let calculated = self.calculated.as_deref().unwrap_or_default();
+        if calculated != self.hash.as_slice() {
+            error::HashMismatch {
+                context: self.url.to_string(),
+                calculated: hex::encode(calculated),
+                expected: hex::encode(&self.hash),
             }
-            Ok(size)
-        } else if let Some(digest) = &mut self.digest {
-            digest.update(&buf[..size]);
-            Ok(size)
-        } else {
-            unreachable!();
+            .fail()?;
         }
+        Ok(0)
     }
 }
 
@@ -92,6 +104,41 @@ impl Read for MaxSizeAdapter {
     }
 }
 
+/// Copies what's read from `reader` into `pending`, which is persisted once `reader` reaches the
+/// end of file without error. If `reader` fails, such as when its digest doesn't match, the copy
+/// is dropped instead. Failing to make the copy doesn't fail the read.
+pub(crate) struct CachingReader<R> {
+    reader: R,
+    pending: Option<PendingFile>,
+}
+
+impl<R> CachingReader<R> {
+    pub(crate) fn new(reader: R, pending: PendingFile) -> Self {
+        Self {
+            reader,
+            pending: Some(pending),
+        }
+    }
+}
+
+impl<R: Read> Read for CachingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.reader.read(buf)?;
+        if let Some(mut pending) = self.pending.take() {
+            let result = if size == 0 {
+                pending.persist().map(|()| None)
+            } else {
+                pending.write_all(&buf[..size]).map(|()| Some(pending))
+            };
+            match result {
+                Ok(pending) => self.pending = pending,
+                Err(err) => warn!("failed to cache target: {err}"),
+            }
+        }
+        Ok(size)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::io::{DigestAdapter, MaxSizeAdapter};
@@ -121,6 +168,7 @@ mod tests {
         let mut buf = Vec::new();
         assert!(reader.read_to_end(&mut buf).is_ok());
         assert_eq!(buf, b"hello");
+        assert_eq!(reader.read(&mut [0; 1]).unwrap(), 0);
 
         let mut reader = DigestAdapter::sha256(
             Box::new(Cursor::new(b"hello".to_vec())),
@@ -129,5 +177,7 @@ mod tests {
         );
         let mut buf = Vec::new();
         assert!(reader.read_to_end(&mut buf).is_err());
+        // Reading past the end reports the mismatch again
+        assert!(reader.read(&mut [0; 1]).is_err());
     }
 }
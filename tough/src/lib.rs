@@ -11,12 +11,41 @@
 //! [TUF repositories]: https://theupdateframework.github.io/
 //! [spec]: https://github.com/theupdateframework/specification/blob/9f148556ca15da2ec5c022c8b3e6f99a028e5fe5/tuf-spec.md
 //!
+//! # Fetching over HTTP
+//!
+//! With the `http` feature enabled, `HttpTransport` (also used by [`DefaultTransport`] for
+//! `http` and `https` URLs) fetches metadata and targets from a remote repository. It retries
+//! failed requests with exponential backoff, and resumes an interrupted download with a range
+//! request where the server supports them; `HttpTransportBuilder` configures both. One
+//! `HttpTransport`, and its clones, reuse their connections to the server.
+//!
+//! # Async
+//!
+//! With the `async` feature enabled, `RepositoryLoader::load_async` and
+//! `Repository::read_target_stream` can be awaited from a tokio runtime without blocking its
+//! worker threads. See the `asynchronous` module.
+//!
+//! # Observing progress
+//!
+//! An [`Observer`] set with [`RepositoryLoader::observer`] is told as each
+//! metadata role is verified and as each target is read, for example to render progress bars or
+//! keep an audit log. See the [`observer`] module.
+//!
+//! # Tracing verification
+//!
+//! To help tell whether a failure is caused by the transport (the wrong file was fetched) or by
+//! cryptography (the expected file was fetched, but isn't properly signed), the client can log a
+//! record of each file it fetches, with its length and sha256 digest next to what the parent
+//! metadata expected, and of each signature check. The trace is logged at the `debug` level to
+//! the `tough::trace` target; enable that target in your logger, such as with
+//! `RUST_LOG=tough::trace=debug` for `env_logger`.
+//!
 //! # Testing
 //!
 //! Unit tests are run in the usual manner: `cargo test`.
 //! Integration tests require docker and are disabled by default behind a feature named `integ`.
 //! To run all tests, including integration tests: `cargo test --all-features` or
-//! `cargo test --features 'http,integ'`.
+//! `cargo test --features 'http,gzip,zstd,integ'`.
 
 #![forbid(missing_debug_implementations, missing_copy_implementations)]
 #![deny(rust_2018_idioms)]
@@ -29,36 +58,54 @@
     clippy::missing_errors_doc
 )]
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod bundle;
 mod cache;
 mod datastore;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub mod decompress;
 pub mod editor;
 pub mod error;
 mod fetch;
+pub mod hash;
 #[cfg(feature = "http")]
 pub mod http;
 mod io;
 pub mod key_source;
+pub mod observer;
 pub mod schema;
 pub mod sign;
+mod trace;
 mod transport;
 
 use crate::datastore::Datastore;
 use crate::error::Result;
 use crate::fetch::{fetch_max_size, fetch_sha256};
+use crate::hash::HashAlgorithm;
 /// An HTTP transport that includes retries.
 #[cfg(feature = "http")]
 pub use crate::http::{HttpTransport, HttpTransportBuilder, RetryRead};
+use crate::observer::{Event, Observer, ObservingReader};
 use crate::schema::{DelegatedRole, Delegations};
-use crate::schema::{Role, RoleType, Root, Signed, Snapshot, Timestamp};
+use crate::schema::{Role, RoleType, Root, Signed, Snapshot, Target, Timestamp};
 pub use crate::transport::{
     DefaultTransport, FilesystemTransport, Transport, TransportError, TransportErrorKind,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use snafu::{ensure, OptionExt, ResultExt};
-use std::collections::HashMap;
-use std::io::Read;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
 use url::Url;
+use walkdir::WalkDir;
 
 /// Represents whether a Repository should fail to load when metadata is expired (`Safe`) or whether
 /// it should ignore expired metadata (`Unsafe`). Only use `Unsafe` if you are sure you need it.
@@ -156,8 +203,22 @@ where
     targets_base_url: Url,
     transport: Option<Box<dyn Transport>>,
     limits: Option<Limits>,
+    max_delegation_depth: Option<u64>,
     datastore: Option<PathBuf>,
+    datastore_file_mode: Option<u32>,
     expiration_enforcement: Option<ExpirationEnforcement>,
+    expiration_grace: HashMap<RoleType, Duration>,
+    snapshot_version: Option<NonZeroU64>,
+    skip_unreachable_delegations: bool,
+    delegated_targets_base_urls: HashMap<String, Url>,
+    expiration_warning: Option<Duration>,
+    compressed_metadata: bool,
+    read_only_metadata: bool,
+    cache_targets: bool,
+    hash_algorithms: Vec<Box<dyn HashAlgorithm>>,
+    target_url_custom_key: Option<String>,
+    storage_path_custom_key: Option<String>,
+    observer: Option<Arc<dyn Observer>>,
 }
 
 impl<R: Read> RepositoryLoader<R> {
@@ -177,11 +238,44 @@ impl<R: Read> RepositoryLoader<R> {
             targets_base_url,
             transport: None,
             limits: None,
+            max_delegation_depth: None,
             datastore: None,
+            datastore_file_mode: None,
             expiration_enforcement: None,
+            expiration_grace: HashMap::new(),
+            snapshot_version: None,
+            skip_unreachable_delegations: false,
+            delegated_targets_base_urls: HashMap::new(),
+            expiration_warning: None,
+            compressed_metadata: false,
+            read_only_metadata: false,
+            cache_targets: false,
+            hash_algorithms: hash::built_in(),
+            target_url_custom_key: None,
+            storage_path_custom_key: None,
+            observer: None,
         }
     }
 
+    /// Create a new `RepositoryLoader` for a repository in local directories, such as one written
+    /// by `tuftool create`, so that it can be used without a server. It fetches with
+    /// [`FilesystemTransport`].
+    ///
+    /// `metadata_dir` and `targets_dir` may be relative to the current directory. See
+    /// [`RepositoryLoader::new`] for `root`.
+    pub fn from_directories<P1, P2>(root: R, metadata_dir: P1, targets_dir: P2) -> Result<Self>
+    where
+        P1: AsRef<Path>,
+        P2: AsRef<Path>,
+    {
+        Ok(Self::new(
+            root,
+            dir_url(metadata_dir.as_ref())?,
+            dir_url(targets_dir.as_ref())?,
+        )
+        .transport(FilesystemTransport))
+    }
+
     /// Load and verify TUF repository metadata.
     pub fn load(self) -> Result<Repository> {
         Repository::load(self)
@@ -199,6 +293,15 @@ impl<R: Read> RepositoryLoader<R> {
         self
     }
 
+    /// Set the maximum number of delegations between the top-level targets role and a delegated
+    /// targets role that is loaded. A role delegated to directly by the top-level targets role is
+    /// one delegation deep. A deeper delegated role is an error, or is skipped if
+    /// [`skip_unreachable_delegations`](Self::skip_unreachable_delegations) is set. Defaults to 32.
+    pub fn max_delegation_depth(mut self, depth: u64) -> Self {
+        self.max_delegation_depth = Some(depth);
+        self
+    }
+
     /// Set a `datastore` directory path. `datastore` is a directory on a persistent filesystem.
     /// This directory's contents store the most recently fetched timestamp, snapshot, and targets
     /// metadata files to detect version rollback attacks.
@@ -211,6 +314,65 @@ impl<R: Read> RepositoryLoader<R> {
         self
     }
 
+    /// Set the mode of files created in the datastore, such as `0o600` to make them readable by
+    /// their owner only. Defaults to `0o644`. The mode may not be world-writable. This setting is
+    /// ignored on platforms other than Unix.
+    pub fn datastore_file_mode(mut self, mode: u32) -> Self {
+        self.datastore_file_mode = Some(mode);
+        self
+    }
+
+    /// Keep each target that's read and verified in the datastore, so that it's served from there
+    /// rather than fetched again for as long as the targets metadata lists it with the same
+    /// digest. This suits a client polling for updates with a persistent
+    /// [`datastore`](Self::datastore).
+    ///
+    /// A cached target is verified against the digest before it's reused; if it doesn't match, it's
+    /// removed and the target is fetched again. Targets are only added to the cache once they've
+    /// been read to the end and verified.
+    pub fn cache_targets(mut self, cache: bool) -> Self {
+        self.cache_targets = cache;
+        self
+    }
+
+    /// Download each target whose `custom` object has a string named `key` from that string, which
+    /// must be an absolute URL, rather than from its path under the targets base URL. This suits
+    /// repositories whose targets are hosted elsewhere, such as in a separate object store.
+    /// Targets without `key` in their `custom` object are downloaded as usual.
+    ///
+    /// Wherever a target is downloaded from, it's verified against the length and digest listed
+    /// in the signed targets metadata.
+    pub fn target_url_custom_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.target_url_custom_key = Some(key.into());
+        self
+    }
+
+    /// Fetch each target whose `custom` object has a string named `key` from that path under the
+    /// targets base URL, rather than from the path derived from its name, and save it there when
+    /// caching the repository. The path must be relative and stay within the targets directory.
+    /// Repositories written with [`Target::STORAGE_PATH_FIELD`] use the key `storage_path`.
+    ///
+    /// [`Target::STORAGE_PATH_FIELD`]: crate::schema::Target::STORAGE_PATH_FIELD
+    pub fn storage_path_custom_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.storage_path_custom_key = Some(key.into());
+        self
+    }
+
+    /// Recognize `algorithm` when verifying targets, in addition to the built-in
+    /// [`Sha256`](hash::Sha256) and [`Sha512`](hash::Sha512). Each target is verified with the
+    /// strongest recognized algorithm it lists a digest for; see [`hash`] for details.
+    pub fn hash_algorithm<H: HashAlgorithm + 'static>(mut self, algorithm: H) -> Self {
+        self.hash_algorithms.push(Box::new(algorithm));
+        self
+    }
+
+    /// Set an [`Observer`] to be told which metadata the repository verifies and how the targets
+    /// it reads progress. See the [`observer`] module for details.
+    pub fn observer<O: Observer + 'static>(mut self, observer: O) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
     /// Set the [`ExpirationEnforcement`].
     ///
     /// **CAUTION:** TUF metadata expiration dates, particularly `timestamp.json`, are designed to
@@ -220,6 +382,156 @@ impl<R: Read> RepositoryLoader<R> {
         self.expiration_enforcement = Some(exp);
         self
     }
+
+    /// Accept metadata for the top-level `role` for up to `grace` after it expires. By default,
+    /// every role is rejected as soon as it expires. A negative `grace` is treated as zero.
+    ///
+    /// An expired `timestamp.json` often just means that it wasn't re-signed in time, so a short
+    /// grace window for the timestamp role can keep clients working through a late signing.
+    ///
+    /// **CAUTION:** Expiration limits how long an attacker can replay old metadata, keeping a
+    /// client from seeing that an update or a key revocation is available. A grace window extends
+    /// that period for `role` by `grace`. Keep windows short, and avoid them for `root`, whose
+    /// expiration is the last line of defense against compromised keys. Expiration is not
+    /// enforced at all, grace window or not, with [`ExpirationEnforcement::Unsafe`].
+    pub fn expiration_grace(mut self, role: RoleType, grace: Duration) -> Self {
+        self.expiration_grace
+            .insert(role, std::cmp::max(grace, Duration::zero()));
+        self
+    }
+
+    /// Warn about metadata that is valid but expires within `within` of the time it is loaded.
+    ///
+    /// Loading still succeeds; each role close to expiring is logged at the `warn` level and
+    /// listed by [`Repository::expiration_warnings`]. This gives you a chance to notice that a
+    /// repository isn't being refreshed before its metadata expires and clients start to fail.
+    pub fn expiration_warning(mut self, within: Duration) -> Self {
+        self.expiration_warning = Some(within);
+        self
+    }
+
+    /// Check that the repository's metadata can't be modified by the current user, as when it's
+    /// served from read-only media or an immutable deployment.
+    ///
+    /// This applies to a `file://` metadata base URL: the directory and every file directly in it
+    /// are checked once the repository is loaded. Loading still succeeds; each one that the
+    /// current user could write to is logged at the `warn` level and listed by
+    /// [`Repository::writable_metadata`]. Anything that grants write permission to no one, or that
+    /// lives on a read-only filesystem, is considered read-only, even for the superuser.
+    pub fn read_only_metadata(mut self, check: bool) -> Self {
+        self.read_only_metadata = check;
+        self
+    }
+
+    /// Pin the repository to a specific snapshot version, rather than the latest snapshot listed
+    /// in `timestamp.json`. The repository must use consistent snapshots so that older versions
+    /// of `snapshot.json` and `targets.json` remain available as `{version}.{role}.json`.
+    ///
+    /// Root is updated and the latest timestamp is verified as usual. The pinned snapshot, and
+    /// the targets it lists, must be signed by the keys in the trusted root. Because the
+    /// timestamp does not vouch for the pinned snapshot, its size is limited by
+    /// `max_targets_size` in the [`Limits`].
+    ///
+    /// **CAUTION:** This is intended for reproducible deployments only. A pinned load bypasses
+    /// TUF's freshness guarantees: an old snapshot may list targets that have since been revoked,
+    /// and it is not checked against (or recorded in) the datastore for rollback protection.
+    /// Expiration is still enforced according to [`ExpirationEnforcement`], so pinning to an
+    /// expired snapshot also requires `ExpirationEnforcement::Unsafe`.
+    pub fn snapshot_version(mut self, version: NonZeroU64) -> Self {
+        self.snapshot_version = Some(version);
+        self
+    }
+
+    /// Load the repository even if the metadata of some delegated roles can't be fetched or
+    /// verified. Such roles, and any roles they delegate to, are left without targets and are
+    /// listed by [`Repository::unreachable_delegations`]. By default, any delegated role that
+    /// can't be loaded is an error.
+    ///
+    /// The repository only ever contains verified targets. Targets that an unreachable role would
+    /// have provided are missing, and [`Repository::all_targets`] does not consult later roles
+    /// for the paths delegated to an unreachable role, since the unreachable role might have
+    /// taken priority.
+    pub fn skip_unreachable_delegations(mut self, skip: bool) -> Self {
+        self.skip_unreachable_delegations = skip;
+        self
+    }
+
+    /// Fetch the targets listed by the delegated role `role` from `targets_base_url` rather than
+    /// the repository's default targets base URL. This supports repositories whose delegated
+    /// roles keep their targets in different locations, such as under different CDN prefixes.
+    ///
+    /// A target is fetched from the base URL of the role that lists it; targets listed by roles
+    /// without an override, including the top-level targets role, use the default.
+    pub fn delegated_targets_base_url<S: Into<String>>(
+        mut self,
+        role: S,
+        targets_base_url: Url,
+    ) -> Self {
+        self.delegated_targets_base_urls
+            .insert(role.into(), targets_base_url);
+        self
+    }
+
+    /// Fetch the metadata of the top-level and delegated targets roles compressed with gzip, from
+    /// the names given by [`compressed_metadata_name`], such as `42.targets.json.gz`.
+    ///
+    /// Compression is only a matter of transfer: the metadata is decompressed before it's parsed,
+    /// and the length and hashes listed in snapshot.json are checked against the decompressed
+    /// bytes, so the same snapshot describes both forms.
+    #[cfg(feature = "gzip")]
+    pub fn compressed_metadata(mut self, compressed: bool) -> Self {
+        self.compressed_metadata = compressed;
+        self
+    }
+}
+
+impl RepositoryLoader<Cursor<Vec<u8>>> {
+    /// Create a new `RepositoryLoader` that bootstraps trust from a root fingerprint rather than a
+    /// full copy of root.json.
+    ///
+    /// Version `root_version` of root.json is fetched from `metadata_base_url` using `transport`,
+    /// and is only trusted if its [`fingerprint`] matches `root_fingerprint`. Loading then
+    /// proceeds as it would for a shipped root.json: the root must be signed by its own keys and
+    /// is updated to the latest version. The fetch is limited to the default `max_root_size` in
+    /// [`Limits`], since it happens before any other settings are given.
+    ///
+    /// [`fingerprint`]: crate::schema::Root::fingerprint
+    pub fn from_root_fingerprint<T: Transport + 'static>(
+        root_fingerprint: &[u8],
+        root_version: NonZeroU64,
+        transport: T,
+        metadata_base_url: Url,
+        targets_base_url: Url,
+    ) -> Result<Self> {
+        let metadata_base_url = parse_url(metadata_base_url)?;
+        let path = format!("{root_version}.root.json");
+        let url = metadata_base_url.join(&path).context(error::JoinUrl {
+            path,
+            url: metadata_base_url.clone(),
+        })?;
+        let root: Signed<Root> = parse_metadata(fetch_max_size(
+            &transport,
+            url,
+            Limits::default().max_root_size,
+            "max_root_size argument",
+        )?)?;
+
+        let calculated = root
+            .signed
+            .fingerprint()
+            .context(error::JsonSerialization)?;
+        ensure!(
+            *calculated == *root_fingerprint,
+            error::RootFingerprintMismatch {
+                expected: hex::encode(root_fingerprint),
+                calculated: hex::encode(&calculated),
+            }
+        );
+        let root = serde_json::to_vec(&root).context(error::SerializeSignedRole {
+            role: RoleType::Root.to_string(),
+        })?;
+        Ok(Self::new(Cursor::new(root), metadata_base_url, targets_base_url).transport(transport))
+    }
 }
 
 /// Limits used when fetching repository metadata.
@@ -268,6 +580,9 @@ impl Default for Limits {
     }
 }
 
+/// The default for [`RepositoryLoader::max_delegation_depth`].
+const DEFAULT_MAX_DELEGATION_DEPTH: u64 = 32;
+
 /// A TUF repository.
 ///
 /// You can create a `Repository` using a [`RepositoryLoader`].
@@ -283,22 +598,118 @@ pub struct Repository {
     timestamp: Signed<Timestamp>,
     targets: Signed<crate::schema::Targets>,
     limits: Limits,
+    max_delegation_depth: u64,
     metadata_base_url: Url,
     targets_base_url: Url,
+    delegated_targets_base_urls: HashMap<String, Url>,
     expiration_enforcement: ExpirationEnforcement,
+    expiration_grace: HashMap<RoleType, Duration>,
+    snapshot_version: Option<NonZeroU64>,
+    unreachable_delegations: Option<Vec<UnreachableDelegation>>,
+    expiration_warning: Option<Duration>,
+    expiration_warnings: Vec<ExpirationWarning>,
+    compressed_metadata: bool,
+    read_only_metadata: bool,
+    writable_metadata: Vec<PathBuf>,
+    cache_targets: bool,
+    hash_algorithms: Vec<Box<dyn HashAlgorithm>>,
+    target_url_custom_key: Option<String>,
+    storage_path_custom_key: Option<String>,
+    observer: Option<Arc<dyn Observer>>,
+}
+
+/// A delegated role whose metadata couldn't be loaded, as listed by
+/// [`Repository::unreachable_delegations`].
+#[derive(Debug, Clone)]
+pub struct UnreachableDelegation {
+    /// The name of the delegated role.
+    pub name: String,
+    /// Why the role's metadata couldn't be fetched or verified.
+    pub error: Arc<error::Error>,
+}
+
+/// A role whose metadata was valid when loaded but expires soon, as listed by
+/// [`Repository::expiration_warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpirationWarning {
+    /// The name of the role, such as `timestamp` or the name of a delegated role.
+    pub role: String,
+    /// When the role's metadata expires.
+    pub expires: DateTime<Utc>,
+}
+
+/// The roles that changed during a [`Repository::refresh`].
+///
+/// A role is considered changed if its signed metadata differs from what was trusted before the
+/// refresh. Changes to delegated targets roles are reported as changes to `targets`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // one independent flag per role
+pub struct RoleChanges {
+    /// Whether a new root was trusted.
+    pub root: bool,
+    /// Whether the timestamp changed.
+    pub timestamp: bool,
+    /// Whether the snapshot changed.
+    pub snapshot: bool,
+    /// Whether the top-level targets, or any delegated targets, changed.
+    pub targets: bool,
+}
+
+impl RoleChanges {
+    /// Returns true if any role changed.
+    pub fn any(&self) -> bool {
+        self.root || self.timestamp || self.snapshot || self.targets
+    }
 }
 
 impl Repository {
     /// Load and verify TUF repository metadata using a [`RepositoryLoader`] for the settings.
     fn load<R: Read>(loader: RepositoryLoader<R>) -> Result<Self> {
-        let datastore = Datastore::new(loader.datastore)?;
+        let datastore = Datastore::new(
+            loader.datastore.clone(),
+            loader
+                .datastore_file_mode
+                .unwrap_or(datastore::DEFAULT_FILE_MODE),
+        )?;
+        Self::load_with_datastore(loader, datastore)
+    }
+
+    /// Load and verify TUF repository metadata, using `datastore` in place of the one named by
+    /// the [`RepositoryLoader`].
+    #[allow(clippy::too_many_lines)]
+    fn load_with_datastore<R: Read>(
+        loader: RepositoryLoader<R>,
+        datastore: Datastore,
+    ) -> Result<Self> {
         let transport = loader
             .transport
             .unwrap_or_else(|| Box::new(DefaultTransport::new()));
         let limits = loader.limits.unwrap_or_default();
+        let max_delegation_depth = loader
+            .max_delegation_depth
+            .unwrap_or(DEFAULT_MAX_DELEGATION_DEPTH);
         let expiration_enforcement = loader.expiration_enforcement.unwrap_or_default();
+        let expiration = ExpirationPolicy {
+            enforcement: expiration_enforcement,
+            grace: loader.expiration_grace,
+        };
         let metadata_base_url = parse_url(loader.metadata_base_url)?;
         let targets_base_url = parse_url(loader.targets_base_url)?;
+        let delegated_targets_base_urls = loader
+            .delegated_targets_base_urls
+            .into_iter()
+            .map(|(role, url)| Ok((role, parse_url(url)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        let observer = loader.observer;
+        let verified = |role: RoleType, name: &str, version: NonZeroU64| {
+            if let Some(observer) = &observer {
+                observer.event(&Event::MetadataVerified {
+                    role,
+                    name,
+                    version,
+                });
+            }
+        };
 
         // 0. Load the trusted root metadata file + 1. Update the root metadata file
         let root = load_root(
@@ -308,8 +719,9 @@ impl Repository {
             limits.max_root_size,
             limits.max_root_updates,
             &metadata_base_url,
-            expiration_enforcement,
+            &expiration,
         )?;
+        verified(RoleType::Root, "root", root.signed.version);
 
         // 2. Download the timestamp metadata file
         let timestamp = load_timestamp(
@@ -318,29 +730,72 @@ impl Repository {
             &datastore,
             limits.max_timestamp_size,
             &metadata_base_url,
-            expiration_enforcement,
-        )?;
-
-        // 3. Download the snapshot metadata file
-        let snapshot = load_snapshot(
-            transport.as_ref(),
-            &root,
-            &timestamp,
-            &datastore,
-            &metadata_base_url,
-            expiration_enforcement,
+            &expiration,
         )?;
+        verified(RoleType::Timestamp, "timestamp", timestamp.signed.version);
+
+        // 3. Download the snapshot metadata file. A pinned snapshot, and the targets it lists, are
+        // verified against a scratch datastore so that they neither fail nor weaken the rollback
+        // checks of regular updates.
+        let pinned_datastore;
+        let (snapshot, snapshot_datastore) = match loader.snapshot_version {
+            None => (
+                load_snapshot(
+                    transport.as_ref(),
+                    &root,
+                    &timestamp,
+                    &datastore,
+                    &metadata_base_url,
+                    &expiration,
+                )?,
+                &datastore,
+            ),
+            Some(version) => {
+                pinned_datastore = Datastore::new(None, datastore::DEFAULT_FILE_MODE)?;
+                (
+                    load_pinned_snapshot(
+                        transport.as_ref(),
+                        &root,
+                        version,
+                        &pinned_datastore,
+                        limits.max_targets_size,
+                        &metadata_base_url,
+                        &expiration,
+                    )?,
+                    &pinned_datastore,
+                )
+            }
+        };
+        verified(RoleType::Snapshot, "snapshot", snapshot.signed.version);
 
         // 4. Download the targets metadata file
+        let mut unreachable_delegations = if loader.skip_unreachable_delegations {
+            Some(Vec::new())
+        } else {
+            None
+        };
         let targets = load_targets(
             transport.as_ref(),
             &root,
             &snapshot,
-            &datastore,
+            snapshot_datastore,
             limits.max_targets_size,
             &metadata_base_url,
-            expiration_enforcement,
+            loader.compressed_metadata,
+            &expiration,
+            max_delegation_depth,
+            unreachable_delegations.as_mut(),
         )?;
+        verified(RoleType::Targets, "targets", targets.signed.version);
+        for name in targets.signed.loaded_role_names() {
+            if let Ok(DelegatedRole {
+                targets: Some(role),
+                ..
+            }) = targets.signed.delegated_role(name)
+            {
+                verified(RoleType::DelegatedTargets, name, role.signed.version);
+            }
+        }
 
         let expires_iter = [
             (root.signed.expires, RoleType::Root),
@@ -348,31 +803,153 @@ impl Repository {
             (snapshot.signed.expires, RoleType::Snapshot),
             (targets.signed.expires, RoleType::Targets),
         ];
-        let (earliest_expiration, earliest_expiration_role) =
-            expires_iter.iter().min_by_key(|tup| tup.0).unwrap();
+        let (earliest_expiration, earliest_expiration_role) = expires_iter
+            .iter()
+            .map(|(expires, role)| (expiration.deadline(*role, *expires), *role))
+            .min_by_key(|tup| tup.0)
+            .unwrap();
+
+        let expiration_warnings = loader
+            .expiration_warning
+            .map(|within| expiration_warnings(within, &expires_iter, &targets.signed))
+            .unwrap_or_default();
+
+        let writable_metadata = if loader.read_only_metadata {
+            writable_metadata(&metadata_base_url)?
+        } else {
+            Vec::new()
+        };
 
         Ok(Self {
             transport,
             consistent_snapshot: root.signed.consistent_snapshot,
             datastore,
-            earliest_expiration: earliest_expiration.to_owned(),
-            earliest_expiration_role: *earliest_expiration_role,
+            earliest_expiration,
+            earliest_expiration_role,
             root,
             snapshot,
             timestamp,
             targets,
             limits,
+            max_delegation_depth,
             metadata_base_url,
             targets_base_url,
+            delegated_targets_base_urls,
             expiration_enforcement,
+            expiration_grace: expiration.grace,
+            snapshot_version: loader.snapshot_version,
+            unreachable_delegations,
+            expiration_warning: loader.expiration_warning,
+            expiration_warnings,
+            compressed_metadata: loader.compressed_metadata,
+            read_only_metadata: loader.read_only_metadata,
+            writable_metadata,
+            cache_targets: loader.cache_targets,
+            hash_algorithms: loader.hash_algorithms,
+            target_url_custom_key: loader.target_url_custom_key,
+            storage_path_custom_key: loader.storage_path_custom_key,
+            observer,
         })
     }
 
+    /// Updates the repository metadata, starting from the currently trusted root and using the
+    /// same settings and datastore that it was loaded with. Returns which roles changed.
+    ///
+    /// If the update fails, `self` is left unchanged.
+    pub fn refresh(&mut self) -> Result<RoleChanges> {
+        let root = serde_json::to_vec(&self.root).context(error::SerializeSignedRole {
+            role: RoleType::Root.to_string(),
+        })?;
+        let loader = RepositoryLoader {
+            root: root.as_slice(),
+            metadata_base_url: self.metadata_base_url.clone(),
+            targets_base_url: self.targets_base_url.clone(),
+            transport: Some(self.transport.clone()),
+            limits: Some(self.limits),
+            max_delegation_depth: Some(self.max_delegation_depth),
+            datastore: None,
+            datastore_file_mode: None,
+            expiration_enforcement: Some(self.expiration_enforcement),
+            expiration_grace: self.expiration_grace.clone(),
+            snapshot_version: self.snapshot_version,
+            skip_unreachable_delegations: self.unreachable_delegations.is_some(),
+            delegated_targets_base_urls: self.delegated_targets_base_urls.clone(),
+            expiration_warning: self.expiration_warning,
+            compressed_metadata: self.compressed_metadata,
+            read_only_metadata: self.read_only_metadata,
+            cache_targets: self.cache_targets,
+            hash_algorithms: self.hash_algorithms.clone(),
+            target_url_custom_key: self.target_url_custom_key.clone(),
+            storage_path_custom_key: self.storage_path_custom_key.clone(),
+            observer: self.observer.clone(),
+        };
+        let refreshed = Self::load_with_datastore(loader, self.datastore.clone())?;
+
+        let changes = RoleChanges {
+            root: refreshed.root.signed != self.root.signed,
+            timestamp: refreshed.timestamp.signed != self.timestamp.signed,
+            snapshot: refreshed.snapshot.signed != self.snapshot.signed,
+            targets: refreshed.targets.signed != self.targets.signed,
+        };
+        *self = refreshed;
+        Ok(changes)
+    }
+
     /// Returns the list of targets present in the repository.
     pub fn targets(&self) -> &Signed<crate::schema::Targets> {
         &self.targets
     }
 
+    /// Returns the verified top-level targets metadata, with every field as it was signed,
+    /// including custom target data and delegations. Delegated roles that were loaded are
+    /// available through [`DelegatedRole::targets`](crate::schema::DelegatedRole::targets).
+    pub fn targets_metadata(&self) -> &crate::schema::Targets {
+        &self.targets.signed
+    }
+
+    /// Returns an iterator over every verified target in the repository, including the targets of
+    /// delegated roles. Each target name is yielded once; see [`Targets::all_targets`] for how
+    /// targets listed by more than one role are resolved.
+    ///
+    /// [`Targets::all_targets`]: crate::schema::Targets::all_targets
+    pub fn all_targets(&self) -> impl Iterator<Item = (&String, &Target)> + '_ {
+        self.targets.signed.all_targets()
+    }
+
+    /// Returns the names of every verified target in the repository, including the targets of
+    /// delegated roles.
+    pub fn target_names(&self) -> HashSet<&String> {
+        self.targets.signed.target_names()
+    }
+
+    /// Returns the names of every delegated targets role reachable from the top-level targets
+    /// role whose metadata was loaded and verified. See [`Targets::loaded_role_names`] for the
+    /// order they're listed in.
+    ///
+    /// [`Targets::loaded_role_names`]: crate::schema::Targets::loaded_role_names
+    pub fn delegated_roles(&self) -> Vec<&str> {
+        self.targets.signed.loaded_role_names()
+    }
+
+    /// Returns the delegated roles whose metadata couldn't be loaded. This is always empty unless
+    /// the repository was loaded with [`RepositoryLoader::skip_unreachable_delegations`].
+    pub fn unreachable_delegations(&self) -> &[UnreachableDelegation] {
+        self.unreachable_delegations.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the roles that expire soon. This is always empty unless the repository was loaded
+    /// with [`RepositoryLoader::expiration_warning`].
+    pub fn expiration_warnings(&self) -> &[ExpirationWarning] {
+        &self.expiration_warnings
+    }
+
+    /// Returns the metadata files, and the metadata directory, that the current user could write
+    /// to. This is always empty unless the repository was loaded with
+    /// [`RepositoryLoader::read_only_metadata`].
+    pub fn writable_metadata(&self) -> &[PathBuf] {
+        &self.writable_metadata
+    }
+
     /// Returns a reference to the signed root
     pub fn root(&self) -> &Signed<Root> {
         &self.root
@@ -388,17 +965,13 @@ impl Repository {
         &self.timestamp
     }
 
-    ///return a vec of all targets including all target files delegated by targets
-    pub fn all_targets(&self) -> impl Iterator + '_ {
-        self.targets.signed.targets_iter()
-    }
-
     /// Fetches a target from the repository.
     ///
     /// If the repository metadata is expired or there is an issue making the request, `Err` is
     /// returned.
     ///
-    /// If the requested target is not listed in the repository metadata, `Ok(None)` is returned.
+    /// If the requested target can't be resolved from the repository metadata, `Ok(None)` is
+    /// returned; [`Repository::resolve_target`] reports why.
     ///
     /// Otherwise, a reader is returned, which provides streaming access to the target contents
     /// before its checksum is validated. If the maximum size is reached or there is a checksum
@@ -432,14 +1005,165 @@ impl Repository {
         //   HASH is one of the hashes of the targets file listed in the targets metadata file
         //   found earlier in step 4. In either case, the client MUST write the file to
         //   non-volatile storage as FILENAME.EXT.
-        Ok(if let Ok(target) = self.targets.signed.find_target(name) {
-            let (sha256, file) = self.target_digest_and_filename(target, name);
-            Some(self.fetch_target(target, &sha256, file.as_str())?)
+        Ok(
+            if let Ok((role, target)) = self.targets.signed.resolve_target(name) {
+                if let Some(observer) = &self.observer {
+                    observer.event(&Event::TargetStarted {
+                        name,
+                        length: target.length,
+                    });
+                }
+                let reader = match (self.open_target(role, target, name), &self.observer) {
+                    (Ok(reader), Some(observer)) => Box::new(ObservingReader::new(
+                        reader,
+                        Arc::clone(observer),
+                        name,
+                        target.length,
+                    )),
+                    (Ok(reader), None) => reader,
+                    (Err(err), Some(observer)) => {
+                        let error = std::io::Error::new(std::io::ErrorKind::Other, err.to_string());
+                        observer.event(&Event::TargetFailed {
+                            name,
+                            error: &error,
+                        });
+                        return Err(err);
+                    }
+                    (Err(err), None) => return Err(err),
+                };
+                Some(reader)
+            } else {
+                None
+            },
+        )
+    }
+
+    /// Returns a reader for the target `name`, delegated to by `role`, from the cache if targets are
+    /// cached or else from the transport.
+    fn open_target(
+        &self,
+        role: Option<&str>,
+        target: &Target,
+        name: &str,
+    ) -> Result<Box<dyn Read + Send>> {
+        let file = self.target_filename(target, name)?;
+        Ok(if self.cache_targets {
+            self.read_cached_target(role, target, name, &file)?
         } else {
-            None
+            Box::new(self.fetch_target(role, target, &file)?)
         })
     }
 
+    /// Resolves a target from the repository metadata, as [`Repository::read_target`] does before
+    /// fetching it, without fetching it. If the target can't be resolved, the returned
+    /// [`TargetResolutionError`] says why.
+    ///
+    /// [`TargetResolutionError`]: crate::error::TargetResolutionError
+    pub fn resolve_target(
+        &self,
+        name: &str,
+    ) -> std::result::Result<&Target, error::TargetResolutionError> {
+        self.targets
+            .signed
+            .resolve_target(name)
+            .map(|(_, target)| target)
+    }
+
+    /// Fetches a target from the repository, as [`Repository::read_target`] does, and saves it to
+    /// `dest`.
+    ///
+    /// The target is written to a temporary file in the same directory as `dest`, which is only
+    /// renamed to `dest` once the whole target has been read and verified. If verification fails,
+    /// the temporary file is removed, and any file that was already at `dest` is left unchanged.
+    /// The saved file is readable and writable by its owner only.
+    ///
+    /// If the requested target is not listed in the repository metadata, `Err` is returned.
+    pub fn save_target<P: AsRef<Path>>(&self, name: &str, dest: P) -> Result<()> {
+        let dest = dest.as_ref();
+        let mut reader = self
+            .read_target(name)?
+            .context(error::SaveTargetMissing { name })?;
+        let dir = match dest.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut file = NamedTempFile::new_in(dir).context(error::SaveTarget { path: dest })?;
+        std::io::copy(&mut reader, &mut file).context(error::SaveTarget { path: dest })?;
+        file.as_file()
+            .sync_all()
+            .context(error::SaveTarget { path: dest })?;
+        file.persist(dest)
+            .context(error::SaveTargetPersist { path: dest })?;
+        Ok(())
+    }
+
+    /// Fetches each of the targets `names` from the repository, as [`Repository::save_target`]
+    /// does, and saves it under `outdir` at the path given by its name. Up to `parallelism`
+    /// targets are fetched at once, sharing the repository's transport and its connections.
+    ///
+    /// `progress` is called with the name and outcome of each target as soon as it's saved or
+    /// fails. After the first failure no more targets are started, and once those already started
+    /// have finished, the first error is returned. Targets that were saved are left in place.
+    ///
+    /// A name that would be saved outside of `outdir`, such as one containing `..`, fails with
+    /// [`Error::SaveTargetName`](crate::error::Error::SaveTargetName).
+    pub fn save_targets<S, P, F>(
+        &self,
+        names: &[S],
+        outdir: P,
+        parallelism: NonZeroUsize,
+        progress: F,
+    ) -> Result<()>
+    where
+        S: AsRef<str> + Sync,
+        P: AsRef<Path>,
+        F: Fn(&str, &Result<()>) + Sync,
+    {
+        let outdir = outdir.as_ref();
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let first_error = Mutex::new(None);
+        std::thread::scope(|scope| {
+            for _ in 0..parallelism.get().min(names.len()) {
+                scope.spawn(|| {
+                    while !failed.load(Ordering::SeqCst) {
+                        let name = match names.get(next.fetch_add(1, Ordering::SeqCst)) {
+                            Some(name) => name.as_ref(),
+                            None => break,
+                        };
+                        let result = self.save_target_under(name, outdir);
+                        progress(name, &result);
+                        if let Err(err) = result {
+                            failed.store(true, Ordering::SeqCst);
+                            first_error.lock().unwrap().get_or_insert(err);
+                        }
+                    }
+                });
+            }
+        });
+        match first_error.into_inner().unwrap() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Saves the target `name` under `outdir` at the path given by its name.
+    fn save_target_under(&self, name: &str, outdir: &Path) -> Result<()> {
+        let relative = PathBuf::from(name);
+        ensure!(
+            relative.components().next().is_some()
+                && relative
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_))),
+            error::SaveTargetName { name }
+        );
+        let dest = outdir.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).context(error::DirCreate { path: parent })?;
+        }
+        self.save_target(name, dest)
+    }
+
     /// Return the named `DelegatedRole` if found.
     pub fn delegated_role(&self, name: &str) -> Option<&DelegatedRole> {
         self.targets.signed.delegated_role(name).ok()
@@ -452,10 +1176,7 @@ fn system_time(datastore: &Datastore) -> Result<DateTime<Utc>> {
     // Get 'current' system time
     let sys_time = Utc::now();
     // Load the latest known system time, if it exists
-    if let Some(Ok(latest_known_time)) = datastore
-        .reader(file)?
-        .map(serde_json::from_reader::<_, DateTime<Utc>>)
-    {
+    if let Some(latest_known_time) = datastore.cached::<DateTime<Utc>>(file)? {
         // Make sure the sampled system time did not go back in time
         ensure!(
             sys_time >= latest_known_time,
@@ -471,14 +1192,158 @@ fn system_time(datastore: &Datastore) -> Result<DateTime<Utc>> {
     Ok(sys_time)
 }
 
-/// TUF v1.0.16, 5.2.9, 5.3.3, 5.4.5, 5.5.4, The expiration timestamp in the `[metadata]` file MUST
-/// be higher than the fixed update start time.
-fn check_expired<T: Role>(datastore: &Datastore, role: &T) -> Result<()> {
-    ensure!(
-        system_time(datastore)? <= role.expires(),
-        error::ExpiredMetadata { role: T::TYPE }
-    );
-    Ok(())
+/// How the expiration of each top-level role is enforced while loading a repository.
+#[derive(Debug, Clone)]
+struct ExpirationPolicy {
+    enforcement: ExpirationEnforcement,
+    grace: HashMap<RoleType, Duration>,
+}
+
+impl ExpirationPolicy {
+    /// Returns when metadata for `role` that expires at `expires` stops being accepted, which is
+    /// later than `expires` if the role has a grace window.
+    fn deadline(&self, role: RoleType, expires: DateTime<Utc>) -> DateTime<Utc> {
+        match self.grace.get(&role) {
+            Some(grace) => expires
+                .checked_add_signed(*grace)
+                .unwrap_or(chrono::MAX_DATETIME),
+            None => expires,
+        }
+    }
+
+    /// TUF v1.0.16, 5.2.9, 5.3.3, 5.4.5, 5.5.4, The expiration timestamp in the `[metadata]` file
+    /// MUST be higher than the fixed update start time. The role's grace window, if any, is added
+    /// to its expiration. Nothing is checked if expiration isn't enforced.
+    fn check<T: Role>(&self, datastore: &Datastore, role: &T) -> Result<()> {
+        if self.enforcement == ExpirationEnforcement::Safe {
+            ensure!(
+                system_time(datastore)? <= self.deadline(T::TYPE, role.expires()),
+                error::ExpiredMetadata { role: T::TYPE }
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns, and logs, the roles that expire within `within` of now, given the expiration of each
+/// top-level role and the top-level targets that delegated roles are loaded under.
+fn expiration_warnings(
+    within: Duration,
+    top_level: &[(DateTime<Utc>, RoleType)],
+    targets: &crate::schema::Targets,
+) -> Vec<ExpirationWarning> {
+    let mut expiring = top_level
+        .iter()
+        .map(|(expires, role)| (role.to_string(), *expires))
+        .collect::<Vec<_>>();
+    delegated_expirations(targets, &mut expiring);
+    let deadline = Utc::now() + within;
+    expiring
+        .into_iter()
+        .filter(|(_, expires)| *expires <= deadline)
+        .map(|(role, expires)| {
+            warn!("{role} metadata expires soon, at {expires}");
+            ExpirationWarning { role, expires }
+        })
+        .collect()
+}
+
+/// Returns, and logs, the metadata directory given by `metadata_base_url` and the files directly in
+/// it that the current user could write to. Metadata that isn't local can't be checked.
+fn writable_metadata(metadata_base_url: &Url) -> Result<Vec<PathBuf>> {
+    let dir = match metadata_base_url.to_file_path() {
+        Ok(dir) if metadata_base_url.scheme() == "file" => dir,
+        _ => {
+            warn!("can't check that metadata at {metadata_base_url} is read-only");
+            return Ok(Vec::new());
+        }
+    };
+    let mut writable = Vec::new();
+    for entry in WalkDir::new(&dir).max_depth(1) {
+        let entry = entry.context(error::WalkDir { directory: &dir })?;
+        let metadata = entry
+            .metadata()
+            .context(error::WalkDir { directory: &dir })?;
+        if (metadata.is_dir() || metadata.is_file()) && is_writable(entry.path(), &metadata) {
+            warn!("metadata at '{}' is writable", entry.path().display());
+            writable.push(entry.into_path());
+        }
+    }
+    Ok(writable)
+}
+
+/// Whether the current user could write to `path`, whose metadata is `metadata`. Rather than
+/// trusting the permission bits, which don't account for ownership or read-only filesystems, this
+/// opens the file for writing (without truncating it), or creates a temporary file in the
+/// directory.
+fn is_writable(path: &Path, metadata: &std::fs::Metadata) -> bool {
+    if metadata.permissions().readonly() {
+        return false;
+    }
+    if metadata.is_dir() {
+        tempfile::tempfile_in(path).is_ok()
+    } else {
+        std::fs::OpenOptions::new().write(true).open(path).is_ok()
+    }
+}
+
+/// Collects the name and expiration of every delegated role loaded under `targets`.
+fn delegated_expirations(targets: &crate::schema::Targets, out: &mut Vec<(String, DateTime<Utc>)>) {
+    if let Some(delegations) = &targets.delegations {
+        for role in &delegations.roles {
+            if let Some(role_targets) = &role.targets {
+                out.push((role.name.clone(), role_targets.signed.expires));
+                delegated_expirations(&role_targets.signed, out);
+            }
+        }
+    }
+}
+
+/// Returns the filename of a target in a repository that uses consistent snapshots, given the
+/// target's SHA-256 digest and its name. Per the TUF specification, the hex-encoded digest is
+/// prepended to the final component of the name, so `dir/file.txt` becomes `dir/{hash}.file.txt`.
+///
+/// This is the path the client fetches, relative to the targets base URL, and the path the editor
+/// writes targets to. The specification allows any of the target's hashes to be used, so the
+/// editor and the client must agree on one: both always use SHA-256, which every target lists,
+/// whatever other hashes are recorded. Use [`Target::consistent_name`] to get this from a target.
+pub fn consistent_target_name(sha256: &[u8], name: &str) -> String {
+    let hash = hex::encode(sha256);
+    match name.rsplit_once('/') {
+        Some((dir, file_name)) => format!("{dir}/{hash}.{file_name}"),
+        None => format!("{hash}.{name}"),
+    }
+}
+
+/// Returns the target name for the relative path `path`, or `None` if it isn't valid UTF-8.
+///
+/// The TUF specification separates the components of target names with `/` regardless of the
+/// platform. On Windows, where paths are separated with `\\`, the components of `path` are joined
+/// with `/`; elsewhere, `path` is used as is, since `\\` is an ordinary character in file names.
+pub fn target_name_from_path(path: &Path) -> Option<String> {
+    #[cfg(windows)]
+    {
+        let components = path
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?;
+        Some(components.join("/"))
+    }
+    #[cfg(not(windows))]
+    {
+        path.to_str().map(str::to_owned)
+    }
+}
+
+/// Returns the name of the gzip-compressed form of the metadata file `name`.
+///
+/// `name` is the name of the uncompressed file, including any version prefix added for consistent
+/// snapshots, and the compressed form appends `.gz` to it. With consistent snapshots, version 42
+/// of targets.json is `42.targets.json`, so it's stored compressed as `42.targets.json.gz`; the
+/// version always comes first and the compression extension last.
+#[cfg(feature = "gzip")]
+pub fn compressed_metadata_name(name: &str) -> String {
+    format!("{name}.gz")
 }
 
 /// Checks to see if the `Url` has a trailing slash and adds one if not. Without a trailing slash,
@@ -494,6 +1359,123 @@ fn parse_url(url: Url) -> Result<Url> {
     }
 }
 
+/// Converts the directory `path`, which may be relative to the current directory, to a file URL.
+fn dir_url(path: &Path) -> Result<Url> {
+    let path = if path.is_absolute() {
+        path.to_owned()
+    } else {
+        std::env::current_dir()
+            .context(error::AbsolutePath { path })?
+            .join(path)
+    };
+    Url::from_directory_path(&path)
+        .ok()
+        .context(error::FileUrl { path })
+}
+
+/// Returns the error of a reader from this library, such as a `DigestAdapter` or a
+/// `MaxSizeAdapter`, if that's what `err` is, or `err` itself if it's a failure of the JSON read.
+fn reader_error(err: serde_json::Error) -> std::result::Result<error::Error, serde_json::Error> {
+    if !err.is_io() {
+        return Err(err);
+    }
+    let err = std::io::Error::from(err);
+    let kind = err.kind();
+    match err
+        .into_inner()
+        .map(|inner| inner.downcast::<error::Error>())
+    {
+        Some(Ok(err)) => Ok(*err),
+        Some(Err(inner)) => Err(serde_json::Error::io(std::io::Error::new(kind, inner))),
+        None => Err(serde_json::Error::io(kind.into())),
+    }
+}
+
+/// Parses signed metadata for the role `T`. Before deserializing, the `_type` field is checked
+/// against the expected role so that a mirror serving the wrong file gets a clear error instead of
+/// a confusing schema error.
+pub(crate) fn parse_metadata<T, R>(reader: R) -> Result<Signed<T>>
+where
+    T: Role + DeserializeOwned,
+    R: Read,
+{
+    let value: serde_json::Value = match serde_json::from_reader(reader) {
+        Ok(value) => value,
+        // An end of file before the first character means the file was empty, which is never a
+        // valid metadata file (unlike a target, which may legitimately have a length of zero).
+        Err(err) if err.is_eof() && err.line() == 1 && err.column() == 0 => {
+            return error::EmptyMetadata { role: T::TYPE }.fail();
+        }
+        // A failure reported by the reader, such as a length or hash that doesn't match what
+        // another role's metadata recorded, is returned as is rather than as a parse error.
+        Err(err) => match reader_error(err) {
+            Ok(err) => return Err(err),
+            Err(err) => return Err(err).context(error::ParseMetadata { role: T::TYPE }),
+        },
+    };
+    let expected = match T::TYPE {
+        RoleType::Root => "root",
+        RoleType::Snapshot => "snapshot",
+        RoleType::Targets | RoleType::DelegatedTargets => "targets",
+        RoleType::Timestamp => "timestamp",
+    };
+    if let Some(signed) = value.get("signed").and_then(serde_json::Value::as_object) {
+        let found = signed.get("_type");
+        ensure!(
+            found.and_then(serde_json::Value::as_str) == Some(expected),
+            error::WrongMetadataType {
+                expected,
+                found: found.map(|found| found
+                    .as_str()
+                    .map_or_else(|| found.to_string(), str::to_owned)),
+            }
+        );
+    }
+    serde_json::from_value(value).context(error::ParseMetadata { role: T::TYPE })
+}
+
+/// Reads signed metadata for the role `T` from `reader`, which may provide it in chunks of any
+/// size, such as a pipe or socket. At most `max_size` bytes are buffered; larger metadata is
+/// rejected without reading further.
+///
+/// The metadata is not verified. Use [`read_verified_metadata`] to also check it against a
+/// trusted root.
+pub fn read_metadata<T, R>(reader: R, max_size: u64) -> Result<Signed<T>>
+where
+    T: Role + DeserializeOwned,
+    R: Read,
+{
+    let mut buf = Vec::new();
+    reader
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut buf)
+        .context(error::MetadataRead { role: T::TYPE })?;
+    ensure!(
+        buf.len() as u64 <= max_size,
+        error::MaxSizeExceeded {
+            max_size,
+            specifier: "max_size argument",
+        }
+    );
+    parse_metadata(buf.as_slice())
+}
+
+/// Reads signed metadata for the role `T` as [`read_metadata`] does, then verifies that it is
+/// signed by a threshold of the keys that `root` lists for the role.
+///
+/// Signatures are checked against the canonical form of the complete metadata, so the result is
+/// the same however the metadata was split into chunks.
+pub fn read_verified_metadata<T, R>(reader: R, max_size: u64, root: &Root) -> Result<Signed<T>>
+where
+    T: Role + DeserializeOwned + Serialize,
+    R: Read,
+{
+    let role = read_metadata(reader, max_size)?;
+    root.verify_role(&role)
+        .context(error::VerifyMetadata { role: T::TYPE })?;
+    Ok(role)
+}
+
 /// Steps 0 and 1 of the client application, which load the current root metadata file based on a
 /// trusted root metadata file.
 fn load_root<R: Read>(
@@ -503,7 +1485,7 @@ fn load_root<R: Read>(
     max_root_size: u64,
     max_root_updates: u64,
     metadata_base_url: &Url,
-    expiration_enforcement: ExpirationEnforcement,
+    expiration: &ExpirationPolicy,
 ) -> Result<Signed<Root>> {
     // 0. Load the trusted root metadata file. We assume that a good, trusted copy of this file was
     //    shipped with the package manager or software updater using an out-of-band process. Note
@@ -511,8 +1493,7 @@ fn load_root<R: Read>(
     //    attempt to update it in the next step.
     let mut root: Signed<Root> =
         serde_json::from_reader(root).context(error::ParseTrustedMetadata)?;
-    root.signed
-        .verify_role(&root)
+    trace::verified("trusted root.json", root.signed.verify_role(&root))
         .context(error::VerifyTrustedMetadata)?;
 
     // Used in step 1.2
@@ -554,7 +1535,7 @@ fn load_root<R: Read>(
         match fetch_max_size(
             transport,
             metadata_base_url.join(&path).context(error::JoinUrl {
-                path,
+                path: &path,
                 url: metadata_base_url.to_owned(),
             })?,
             max_root_size,
@@ -562,10 +1543,7 @@ fn load_root<R: Read>(
         ) {
             Err(_) => break, // If this file is not available, then go to step 1.8.
             Ok(reader) => {
-                let new_root: Signed<Root> =
-                    serde_json::from_reader(reader).context(error::ParseMetadata {
-                        role: RoleType::Root,
-                    })?;
+                let new_root: Signed<Root> = parse_metadata(reader)?;
 
                 // 1.3. Check signatures. Version N+1 of the root metadata file MUST have been
                 //   signed by: (1) a threshold of keys specified in the trusted root metadata file
@@ -573,17 +1551,20 @@ fn load_root<R: Read>(
                 //   file being validated (version N+1). If version N+1 is not signed as required,
                 //   discard it, abort the update cycle, and report the signature failure. On the
                 //   next update cycle, begin at step 0 and version N of the root metadata file.
-                root.signed
-                    .verify_role(&new_root)
-                    .context(error::VerifyMetadata {
-                        role: RoleType::Root,
-                    })?;
-                new_root
-                    .signed
-                    .verify_role(&new_root)
-                    .context(error::VerifyMetadata {
-                        role: RoleType::Root,
-                    })?;
+                trace::verified(
+                    &format!("{} (by trusted root)", path),
+                    root.signed.verify_role(&new_root),
+                )
+                .context(error::VerifyMetadata {
+                    role: RoleType::Root,
+                })?;
+                trace::verified(
+                    &format!("{} (by itself)", path),
+                    new_root.signed.verify_role(&new_root),
+                )
+                .context(error::VerifyMetadata {
+                    role: RoleType::Root,
+                })?;
 
                 // 1.4. Check for a rollback attack. The version number of the trusted root
                 //   metadata file (version N) must be less than or equal to the version number of
@@ -630,9 +1611,7 @@ fn load_root<R: Read>(
     // metadata file MUST be higher than the fixed update start time. If the trusted root metadata
     // file has expired, abort the update cycle, report the potential freeze attack. On the next
     // update cycle, begin at step 5.1 and version N of the root metadata file.
-    if expiration_enforcement == ExpirationEnforcement::Safe {
-        check_expired(datastore, &root.signed)?;
-    }
+    expiration.check(datastore, &root.signed)?;
 
     // 1.9. If the timestamp and / or snapshot keys have been rotated, then delete the trusted
     //   timestamp and snapshot metadata files. This is done in order to recover from fast-forward
@@ -668,7 +1647,7 @@ fn load_timestamp(
     datastore: &Datastore,
     max_timestamp_size: u64,
     metadata_base_url: &Url,
-    expiration_enforcement: ExpirationEnforcement,
+    expiration: &ExpirationPolicy,
 ) -> Result<Signed<Timestamp>> {
     // 2. Download the timestamp metadata file, up to Y number of bytes (because the size is
     //    unknown.) The value for Y is set by the authors of the application using TUF. For
@@ -684,16 +1663,22 @@ fn load_timestamp(
         max_timestamp_size,
         "max_timestamp_size argument",
     )?;
-    let timestamp: Signed<Timestamp> =
-        serde_json::from_reader(reader).context(error::ParseMetadata {
-            role: RoleType::Timestamp,
-        })?;
+    let timestamp: Signed<Timestamp> = parse_metadata(reader)?;
 
     // 2.1. Check signatures. The new timestamp metadata file must have been signed by a threshold
     //   of keys specified in the trusted root metadata file. If the new timestamp metadata file is
     //   not properly signed, discard it, abort the update cycle, and report the signature failure.
-    root.signed
-        .verify_role(&timestamp)
+    trace::verified("timestamp.json", root.signed.verify_role(&timestamp)).context(
+        error::VerifyMetadata {
+            role: RoleType::Timestamp,
+        },
+    )?;
+
+    // The timestamp MUST only include a description of the snapshot.json file. A timestamp that
+    // lists anything else is malformed, even if it's properly signed.
+    timestamp
+        .signed
+        .snapshot_meta()
         .context(error::VerifyMetadata {
             role: RoleType::Timestamp,
         })?;
@@ -702,10 +1687,7 @@ fn load_timestamp(
     //   if any, must be less than or equal to the version number of the new timestamp metadata
     //   file. If the new timestamp metadata file is older than the trusted timestamp metadata
     //   file, discard it, abort the update cycle, and report the potential rollback attack.
-    if let Some(Ok(old_timestamp)) = datastore
-        .reader("timestamp.json")?
-        .map(serde_json::from_reader::<_, Signed<Timestamp>>)
-    {
+    if let Some(old_timestamp) = datastore.cached::<Signed<Timestamp>>("timestamp.json")? {
         if root.signed.verify_role(&old_timestamp).is_ok() {
             ensure!(
                 old_timestamp.signed.version <= timestamp.signed.version,
@@ -715,6 +1697,30 @@ fn load_timestamp(
                     new_version: timestamp.signed.version
                 }
             );
+
+            // A version number may only be reused for identical content. Otherwise an attacker
+            // could replay different metadata without tripping the version check above.
+            ensure!(
+                old_timestamp.signed.version != timestamp.signed.version
+                    || old_timestamp.signed == timestamp.signed,
+                error::ContentChangedWithoutVersionBump {
+                    role: RoleType::Timestamp,
+                    version: timestamp.signed.version,
+                }
+            );
+            if let (Some(old_snapshot_meta), Some(snapshot_meta)) = (
+                old_timestamp.signed.meta.get("snapshot.json"),
+                timestamp.signed.meta.get("snapshot.json"),
+            ) {
+                ensure!(
+                    old_snapshot_meta.version != snapshot_meta.version
+                        || old_snapshot_meta.hashes.sha256 == snapshot_meta.hashes.sha256,
+                    error::ContentChangedWithoutVersionBump {
+                        role: RoleType::Snapshot,
+                        version: snapshot_meta.version,
+                    }
+                );
+            }
         }
     }
 
@@ -722,9 +1728,7 @@ fn load_timestamp(
     // metadata file MUST be higher than the fixed update start time. If so, the new timestamp
     // metadata file becomes the trusted timestamp metadata file. If the new timestamp metadata file
     // has expired, discard it, abort the update cycle, and report the potential freeze attack.
-    if expiration_enforcement == ExpirationEnforcement::Safe {
-        check_expired(datastore, &timestamp.signed)?;
-    }
+    expiration.check(datastore, &timestamp.signed)?;
 
     // Now that everything seems okay, write the timestamp file to the datastore.
     datastore.create("timestamp.json", &timestamp)?;
@@ -733,13 +1737,14 @@ fn load_timestamp(
 }
 
 /// Step 3 of the client application, which loads the snapshot metadata file.
+#[allow(clippy::too_many_lines)]
 fn load_snapshot(
     transport: &dyn Transport,
     root: &Signed<Root>,
     timestamp: &Signed<Timestamp>,
     datastore: &Datastore,
     metadata_base_url: &Url,
-    expiration_enforcement: ExpirationEnforcement,
+    expiration: &ExpirationPolicy,
 ) -> Result<Signed<Snapshot>> {
     // 3. Download snapshot metadata file, up to the number of bytes specified in the timestamp
     //    metadata file. If consistent snapshots are not used (see Section 7), then the filename
@@ -750,12 +1755,17 @@ fn load_snapshot(
     //    file to non-volatile storage as FILENAME.EXT.
     let snapshot_meta = timestamp
         .signed
-        .meta
-        .get("snapshot.json")
-        .context(error::MetaMissing {
-            file: "snapshot.json",
+        .snapshot_meta()
+        .context(error::VerifyMetadata {
             role: RoleType::Timestamp,
         })?;
+    ensure!(
+        snapshot_meta.length != 0,
+        error::MetaLengthZero {
+            file: "snapshot.json",
+            role: RoleType::Timestamp,
+        }
+    );
     let path = if root.signed.consistent_snapshot {
         format!("{}.snapshot.json", snapshot_meta.version)
     } else {
@@ -764,17 +1774,14 @@ fn load_snapshot(
     let reader = fetch_sha256(
         transport,
         metadata_base_url.join(&path).context(error::JoinUrl {
-            path,
+            path: &path,
             url: metadata_base_url.to_owned(),
         })?,
         snapshot_meta.length,
         "timestamp.json",
         &snapshot_meta.hashes.sha256,
     )?;
-    let snapshot: Signed<Snapshot> =
-        serde_json::from_reader(reader).context(error::ParseMetadata {
-            role: RoleType::Snapshot,
-        })?;
+    let snapshot: Signed<Snapshot> = parse_metadata(reader)?;
 
     // 3.1. Check against timestamp metadata. The hashes and version number of the new snapshot
     //   metadata file MUST match the hashes and version number listed in timestamp metadata. If
@@ -795,20 +1802,16 @@ fn load_snapshot(
     //   of keys specified in the trusted root metadata file. If the new snapshot metadata file is
     //   not signed as required, discard it, abort the update cycle, and report the signature
     //   failure.
-    root.signed
-        .verify_role(&snapshot)
-        .context(error::VerifyMetadata {
-            role: RoleType::Snapshot,
-        })?;
+    trace::verified(&path, root.signed.verify_role(&snapshot)).context(error::VerifyMetadata {
+        role: RoleType::Snapshot,
+    })?;
+    check_snapshot_root_meta(&snapshot.signed)?;
 
     // 3.3. Check for a rollback attack.
     //
     // 3.3.1. Note that the trusted snapshot metadata file may be checked for authenticity, but its
     //   expiration does not matter for the following purposes.
-    if let Some(Ok(old_snapshot)) = datastore
-        .reader("snapshot.json")?
-        .map(serde_json::from_reader::<_, Signed<Snapshot>>)
-    {
+    if let Some(old_snapshot) = datastore.cached::<Signed<Snapshot>>("snapshot.json")? {
         // 3.3.2. The version number of the trusted snapshot metadata file, if any, MUST be less
         //   than or equal to the version number of the new snapshot metadata file. If the new
         //   snapshot metadata file is older than the trusted metadata file, discard it, abort the
@@ -822,6 +1825,14 @@ fn load_snapshot(
                     new_version: snapshot.signed.version
                 }
             );
+            ensure!(
+                old_snapshot.signed.version != snapshot.signed.version
+                    || old_snapshot.signed == snapshot.signed,
+                error::ContentChangedWithoutVersionBump {
+                    role: RoleType::Snapshot,
+                    version: snapshot.signed.version,
+                }
+            );
 
             // 3.3.3. The version number of the targets metadata file, and all delegated targets
             //   metadata files (if any), in the trusted snapshot metadata file, if any, MUST be
@@ -830,24 +1841,36 @@ fn load_snapshot(
             //   metadata file, if any, MUST continue to be listed in the new snapshot metadata
             //   file. If any of these conditions are not met, discard the new snaphot metadadata
             //   file, abort the update cycle, and report the failure.
-            if let Some(old_targets_meta) = old_snapshot.signed.meta.get("targets.json") {
-                let targets_meta =
-                    snapshot
-                        .signed
-                        .meta
-                        .get("targets.json")
-                        .context(error::MetaMissing {
-                            file: "targets.json",
-                            role: RoleType::Snapshot,
-                        })?;
+            for (file, old_targets_meta) in &old_snapshot.signed.meta {
+                let role = match file.as_str() {
+                    "root.json" => continue,
+                    "targets.json" => RoleType::Targets,
+                    _ => RoleType::DelegatedTargets,
+                };
+                let targets_meta = snapshot.signed.meta.get(file).context(error::MetaMissing {
+                    file,
+                    role: RoleType::Snapshot,
+                })?;
                 ensure!(
                     old_targets_meta.version <= targets_meta.version,
                     error::OlderMetadata {
-                        role: RoleType::Targets,
+                        role,
                         current_version: old_targets_meta.version,
                         new_version: targets_meta.version,
                     }
                 );
+                if let (Some(old_hashes), Some(hashes)) =
+                    (&old_targets_meta.hashes, &targets_meta.hashes)
+                {
+                    ensure!(
+                        old_targets_meta.version != targets_meta.version
+                            || old_hashes.sha256 == hashes.sha256,
+                        error::ContentChangedWithoutVersionBump {
+                            role,
+                            version: targets_meta.version,
+                        }
+                    );
+                }
             }
         }
     }
@@ -856,9 +1879,7 @@ fn load_snapshot(
     // metadata file MUST be higher than the fixed update start time. If so, the new snapshot
     // metadata file becomes the trusted snapshot metadata file. If the new snapshot metadata file
     // is expired, discard it, abort the update cycle, and report the potential freeze attack.
-    if expiration_enforcement == ExpirationEnforcement::Safe {
-        check_expired(datastore, &snapshot.signed)?;
-    }
+    expiration.check(datastore, &snapshot.signed)?;
 
     // Now that everything seems okay, write the snapshot file to the datastore.
     datastore.create("snapshot.json", &snapshot)?;
@@ -866,7 +1887,69 @@ fn load_snapshot(
     Ok(snapshot)
 }
 
+/// Loads a specific version of the snapshot metadata file in place of step 3 of the client
+/// application. See [`RepositoryLoader::snapshot_version`].
+fn load_pinned_snapshot(
+    transport: &dyn Transport,
+    root: &Signed<Root>,
+    version: NonZeroU64,
+    datastore: &Datastore,
+    max_snapshot_size: u64,
+    metadata_base_url: &Url,
+    expiration: &ExpirationPolicy,
+) -> Result<Signed<Snapshot>> {
+    // Older versions of snapshot.json are only addressable with consistent snapshots.
+    ensure!(
+        root.signed.consistent_snapshot,
+        error::SnapshotVersionRequiresConsistentSnapshot { version }
+    );
+    let path = format!("{version}.snapshot.json");
+    let reader = fetch_max_size(
+        transport,
+        metadata_base_url.join(&path).context(error::JoinUrl {
+            path: &path,
+            url: metadata_base_url.to_owned(),
+        })?,
+        max_snapshot_size,
+        "max_targets_size argument",
+    )?;
+    let snapshot: Signed<Snapshot> = parse_metadata(reader)?;
+
+    // Without timestamp metadata to check against, the version and signatures are all that tie
+    // the file to the requested snapshot.
+    ensure!(
+        snapshot.signed.version == version,
+        error::VersionMismatch {
+            role: RoleType::Snapshot,
+            fetched: snapshot.signed.version,
+            expected: version
+        }
+    );
+    trace::verified(&path, root.signed.verify_role(&snapshot)).context(error::VerifyMetadata {
+        role: RoleType::Snapshot,
+    })?;
+    check_snapshot_root_meta(&snapshot.signed)?;
+
+    expiration.check(datastore, &snapshot.signed)?;
+
+    Ok(snapshot)
+}
+
+/// Checks that `snapshot` lists root.json if the version of the specification it follows requires
+/// it to.
+fn check_snapshot_root_meta(snapshot: &Snapshot) -> Result<()> {
+    ensure!(
+        !snapshot.requires_root_meta() || snapshot.meta.contains_key("root.json"),
+        error::MetaMissing {
+            file: "root.json",
+            role: RoleType::Snapshot,
+        }
+    );
+    Ok(())
+}
+
 /// Step 4 of the client application, which loads the targets metadata file.
+#[allow(clippy::too_many_arguments)]
 fn load_targets(
     transport: &dyn Transport,
     root: &Signed<Root>,
@@ -874,7 +1957,10 @@ fn load_targets(
     datastore: &Datastore,
     max_targets_size: u64,
     metadata_base_url: &Url,
-    expiration_enforcement: ExpirationEnforcement,
+    compressed_metadata: bool,
+    expiration: &ExpirationPolicy,
+    max_delegation_depth: u64,
+    unreachable_delegations: Option<&mut Vec<UnreachableDelegation>>,
 ) -> Result<Signed<crate::schema::Targets>> {
     // 4. Download the top-level targets metadata file, up to either the number of bytes specified
     //    in the snapshot metadata file, or some Z number of bytes. The value for Z is set by the
@@ -898,34 +1984,25 @@ fn load_targets(
     } else {
         "targets.json".to_owned()
     };
-    let targets_url = metadata_base_url.join(&path).context(error::JoinUrl {
-        path,
-        url: metadata_base_url.to_owned(),
-    })?;
-    let (max_targets_size, specifier) = match targets_meta.length {
+    let (max_size, specifier) = match targets_meta.length {
+        Some(0) => error::MetaLengthZero {
+            file: "targets.json",
+            role: RoleType::Snapshot,
+        }
+        .fail()?,
         Some(length) => (length, "snapshot.json"),
         None => (max_targets_size, "max_targets_size parameter"),
     };
-    let reader = if let Some(hashes) = &targets_meta.hashes {
-        Box::new(fetch_sha256(
-            transport,
-            targets_url,
-            max_targets_size,
-            specifier,
-            &hashes.sha256,
-        )?) as Box<dyn Read>
-    } else {
-        Box::new(fetch_max_size(
-            transport,
-            targets_url,
-            max_targets_size,
-            specifier,
-        )?)
-    };
-    let mut targets: Signed<crate::schema::Targets> =
-        serde_json::from_reader(reader).context(error::ParseMetadata {
-            role: RoleType::Targets,
-        })?;
+    let reader = fetch_targets_metadata(
+        transport,
+        metadata_base_url,
+        &path,
+        compressed_metadata,
+        max_size,
+        specifier,
+        targets_meta.hashes.as_ref().map(|hashes| &*hashes.sha256),
+    )?;
+    let mut targets: Signed<crate::schema::Targets> = parse_metadata(reader)?;
 
     // 4.1. Check against snapshot metadata. The hashes (if any), and version number of the new
     //   targets metadata file MUST match the trusted snapshot metadata. This is done, in part, to
@@ -946,11 +2023,9 @@ fn load_targets(
     //   signed by a threshold of keys specified in the trusted root metadata file. If the new
     //   targets metadata file is not signed as required, discard it, abort the update cycle, and
     //   report the failure.
-    root.signed
-        .verify_role(&targets)
-        .context(error::VerifyMetadata {
-            role: RoleType::Targets,
-        })?;
+    trace::verified(&path, root.signed.verify_role(&targets)).context(error::VerifyMetadata {
+        role: RoleType::Targets,
+    })?;
 
     // 4.3. Check for a rollback attack. The version number of the trusted targets metadata file,
     //   if any, MUST be less than or equal to the version number of the new targets metadata file.
@@ -969,6 +2044,14 @@ fn load_targets(
                     new_version: targets.signed.version
                 }
             );
+            ensure!(
+                old_targets.signed.version != targets.signed.version
+                    || old_targets.signed == targets.signed,
+                error::ContentChangedWithoutVersionBump {
+                    role: RoleType::Targets,
+                    version: targets.signed.version,
+                }
+            );
         }
     }
 
@@ -976,9 +2059,7 @@ fn load_targets(
     // metadata file MUST be higher than the fixed update start time. If so, the new targets
     // metadata file becomes the trusted targets metadata file. If the new targets metadata file is
     // expired, discard it, abort the update cycle, and report the potential freeze attack.
-    if expiration_enforcement == ExpirationEnforcement::Safe {
-        check_expired(datastore, &targets.signed)?;
-    }
+    expiration.check(datastore, &targets.signed)?;
 
     // Now that everything seems okay, write the targets file to the datastore.
     datastore.create("targets.json", &targets)?;
@@ -991,81 +2072,116 @@ fn load_targets(
             snapshot,
             root.signed.consistent_snapshot,
             metadata_base_url,
+            compressed_metadata,
             max_targets_size,
             delegations,
             &datastore,
+            max_delegation_depth,
+            &mut vec![RoleType::Targets.to_string()],
+            unreachable_delegations,
         )?;
     }
 
     Ok(targets)
 }
 
-// Follow the paths of delegations starting with the top level targets.json delegation
+/// Fetches the targets metadata file `path`, or its gzip-compressed form if `compressed` is set,
+/// up to `max_size` bytes of (uncompressed) metadata and checking its sha256 digest, if given.
+fn fetch_targets_metadata(
+    transport: &dyn Transport,
+    metadata_base_url: &Url,
+    path: &str,
+    compressed: bool,
+    max_size: u64,
+    specifier: &'static str,
+    sha256: Option<&[u8]>,
+) -> Result<Box<dyn Read>> {
+    #[cfg(feature = "gzip")]
+    {
+        if compressed {
+            let path = compressed_metadata_name(path);
+            let url = metadata_base_url.join(&path).context(error::JoinUrl {
+                path,
+                url: metadata_base_url.to_owned(),
+            })?;
+            return Ok(fetch::fetch_gzip(
+                transport, url, max_size, specifier, sha256,
+            )?);
+        }
+    }
+    #[cfg(not(feature = "gzip"))]
+    debug_assert!(!compressed, "compressed metadata requires the gzip feature");
+
+    let url = metadata_base_url.join(path).context(error::JoinUrl {
+        path,
+        url: metadata_base_url.to_owned(),
+    })?;
+    Ok(match sha256 {
+        Some(sha256) => Box::new(fetch_sha256(transport, url, max_size, specifier, sha256)?),
+        None => Box::new(fetch_max_size(transport, url, max_size, specifier)?),
+    })
+}
+
+// Follow the paths of delegations starting with the top level targets.json delegation.
+// `ancestors` holds the names of the roles that delegated, in turn, to the one whose `delegation`
+// this is, ending with that role itself, so that cycles and overly deep delegations are caught.
+#[allow(clippy::too_many_arguments)]
 fn load_delegations(
     transport: &dyn Transport,
     snapshot: &Signed<Snapshot>,
     consistent_snapshot: bool,
     metadata_base_url: &Url,
+    compressed_metadata: bool,
     max_targets_size: u64,
     delegation: &mut Delegations,
     datastore: &Datastore,
+    max_delegation_depth: u64,
+    ancestors: &mut Vec<String>,
+    mut unreachable_delegations: Option<&mut Vec<UnreachableDelegation>>,
 ) -> Result<()> {
     let mut delegated_roles: HashMap<String, Option<Signed<crate::schema::Targets>>> =
         HashMap::new();
     for delegated_role in &delegation.roles {
-        // find the role file metadata
-        let role_meta = snapshot
-            .signed
-            .meta
-            .get(&format!("{}.json", &delegated_role.name))
-            .context(error::RoleNotInMeta {
+        let loaded = if ancestors.contains(&delegated_role.name) {
+            error::DelegationCycle {
                 name: delegated_role.name.clone(),
-            })?;
-
-        let path = if consistent_snapshot {
-            format!("{}.{}.json", &role_meta.version, &delegated_role.name)
-        } else {
-            format!("{}.json", &delegated_role.name)
-        };
-        let role_url = metadata_base_url.join(&path).context(error::JoinUrl {
-            path: path.clone(),
-            url: metadata_base_url.to_owned(),
-        })?;
-        let specifier = "max_targets_size parameter";
-        // load the role json file
-        let reader = Box::new(fetch_max_size(
-            transport,
-            role_url,
-            max_targets_size,
-            specifier,
-        )?);
-        // since each role is a targets, we load them as such
-        let role: Signed<crate::schema::Targets> =
-            serde_json::from_reader(reader).context(error::ParseMetadata {
-                role: RoleType::Targets,
-            })?;
-        // verify each role with the delegation
-        delegation
-            .verify_role(&role, &delegated_role.name)
-            .context(error::VerifyMetadata {
-                role: RoleType::Targets,
-            })?;
-        ensure!(
-            role.signed.version == role_meta.version,
-            error::VersionMismatch {
-                role: RoleType::Targets,
-                fetched: role.signed.version,
-                expected: role_meta.version
+                chain: ancestors.join(" -> "),
             }
-        );
-        {
-            if let Some(delegations) = role.signed.delegations.as_ref() {
-                delegations.verify_paths().context(error::InvalidPath {})?
+            .fail()
+        } else if ancestors.len() as u64 > max_delegation_depth {
+            error::DelegationDepth {
+                name: delegated_role.name.clone(),
+                max_depth: max_delegation_depth,
+                chain: ancestors.join(" -> "),
             }
-        }
-
-        datastore.create(&path, &role)?;
-        delegated_roles.insert(delegated_role.name.clone(), Some(role));
+            .fail()
+        } else {
+            load_delegated_role(
+                transport,
+                snapshot,
+                consistent_snapshot,
+                metadata_base_url,
+                compressed_metadata,
+                max_targets_size,
+                delegation,
+                &delegated_role.name,
+                datastore,
+            )
+        };
+        let role = match loaded {
+            Ok(role) => Some(role),
+            Err(err) => match &mut unreachable_delegations {
+                Some(unreachable) => {
+                    unreachable.push(UnreachableDelegation {
+                        name: delegated_role.name.clone(),
+                        error: Arc::new(err),
+                    });
+                    None
+                }
+                None => return Err(err),
+            },
+        };
+        delegated_roles.insert(delegated_role.name.clone(), role);
     }
     // load all roles delegated by this role
     for delegated_role in &mut delegation.roles {
@@ -1076,21 +2192,107 @@ fn load_delegations(
         )?;
         if let Some(targets) = &mut delegated_role.targets {
             if let Some(delegations) = &mut targets.signed.delegations {
+                ancestors.push(delegated_role.name.clone());
                 load_delegations(
                     transport,
                     snapshot,
                     consistent_snapshot,
                     metadata_base_url,
+                    compressed_metadata,
                     max_targets_size,
                     delegations,
                     datastore,
+                    max_delegation_depth,
+                    ancestors,
+                    unreachable_delegations.as_deref_mut(),
                 )?;
+                ancestors.pop();
             }
         }
     }
     Ok(())
 }
 
+// Fetch and verify the metadata of the role `name` delegated by `delegation`
+#[allow(clippy::too_many_arguments)]
+fn load_delegated_role(
+    transport: &dyn Transport,
+    snapshot: &Signed<Snapshot>,
+    consistent_snapshot: bool,
+    metadata_base_url: &Url,
+    compressed_metadata: bool,
+    max_targets_size: u64,
+    delegation: &Delegations,
+    name: &str,
+    datastore: &Datastore,
+) -> Result<Signed<crate::schema::Targets>> {
+    // every key the role may be signed by must be declared by the delegating role
+    if let Some(role) = delegation.roles.iter().find(|role| role.name == name) {
+        if let Some(keyid) = role
+            .keyids
+            .iter()
+            .find(|keyid| !delegation.keys.contains_key(*keyid))
+        {
+            return error::MissingDelegationKey {
+                role: name,
+                keyid: hex::encode(keyid),
+            }
+            .fail();
+        }
+    }
+
+    // find the role file metadata
+    let role_meta = snapshot
+        .signed
+        .meta
+        .get(&format!("{name}.json"))
+        .context(error::RoleNotInMeta { name })?;
+
+    let path = if consistent_snapshot {
+        format!("{}.{name}.json", &role_meta.version)
+    } else {
+        format!("{name}.json")
+    };
+    // Like the top-level targets metadata, a delegated role's metadata is fetched up to the length
+    // listed in snapshot.json, if any, so that an endless response can't exhaust the client.
+    let (max_size, specifier) = match role_meta.length {
+        Some(length) => (length, "snapshot.json"),
+        None => (max_targets_size, "max_targets_size parameter"),
+    };
+    // load the role json file
+    let reader = fetch_targets_metadata(
+        transport,
+        metadata_base_url,
+        &path,
+        compressed_metadata,
+        max_size,
+        specifier,
+        role_meta.hashes.as_ref().map(|hashes| &*hashes.sha256),
+    )?;
+    // since each role is a targets, we load them as such
+    let role: Signed<crate::schema::Targets> = parse_metadata(reader)?;
+    // verify each role with the delegation
+    trace::verified(&path, delegation.verify_role(&role, name)).context(error::VerifyMetadata {
+        role: RoleType::Targets,
+    })?;
+    ensure!(
+        role.signed.version == role_meta.version,
+        error::VersionMismatch {
+            role: RoleType::Targets,
+            fetched: role.signed.version,
+            expected: role_meta.version
+        }
+    );
+    {
+        if let Some(delegations) = role.signed.delegations.as_ref() {
+            delegations.verify_paths().context(error::InvalidPath {})?
+        }
+    }
+
+    datastore.create(&path, &role)?;
+    Ok(role)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1108,6 +2310,40 @@ mod tests {
         )
     }
 
+    // The digest is prepended to the final component of a target name
+    #[test]
+    fn consistent_target_names() {
+        let sha256 = [0xab, 0xcd];
+        assert_eq!(consistent_target_name(&sha256, "file.txt"), "abcd.file.txt");
+        assert_eq!(
+            consistent_target_name(&sha256, "dir/sub/file.txt"),
+            "dir/sub/abcd.file.txt"
+        );
+    }
+
+    // Target names derived from paths use `/` separators on every platform
+    #[test]
+    fn target_names_from_paths() {
+        assert_eq!(
+            target_name_from_path(Path::new("file.txt")).unwrap(),
+            "file.txt"
+        );
+        assert_eq!(
+            target_name_from_path(&Path::new("dir").join("sub").join("file.txt")).unwrap(),
+            "dir/sub/file.txt"
+        );
+    }
+
+    // On unix, `\\` is part of the file name, not a separator
+    #[cfg(unix)]
+    #[test]
+    fn target_name_keeps_backslash() {
+        assert_eq!(
+            target_name_from_path(Path::new("dir\\file.txt")).unwrap(),
+            "dir\\file.txt"
+        );
+    }
+
     // Ensure that the `ExpirationEnforcement` traits are not changed by mistake.
     #[test]
     fn expiration_enforcement_traits() {
@@ -1124,4 +2360,40 @@ mod tests {
         let default = ExpirationEnforcement::default();
         assert_eq!(default, ExpirationEnforcement::Safe);
     }
+
+    // Ensure that metadata loaded as the wrong role is reported as such.
+    #[test]
+    fn wrong_metadata_type() {
+        let targets = include_str!("../tests/data/tuf-reference-impl/metadata/targets.json");
+        match parse_metadata::<Snapshot, _>(targets.as_bytes()) {
+            Err(error::Error::WrongMetadataType {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "snapshot");
+                assert_eq!(found.as_deref(), Some("targets"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(parse_metadata::<crate::schema::Targets, _>(targets.as_bytes()).is_ok());
+    }
+
+    // Ensure that metadata without a `_type` is reported as the wrong type.
+    #[test]
+    fn missing_metadata_type() {
+        let mut targets: serde_json::Value = serde_json::from_str(include_str!(
+            "../tests/data/tuf-reference-impl/metadata/targets.json"
+        ))
+        .unwrap();
+        targets["signed"].as_object_mut().unwrap().remove("_type");
+        let targets = serde_json::to_vec(&targets).unwrap();
+        match parse_metadata::<crate::schema::Targets, _>(targets.as_slice()) {
+            Err(error::Error::WrongMetadataType {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "targets");
+                assert_eq!(found, None);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,143 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides a hook for following what a repository fetches and verifies, such as to render
+//! progress bars or keep an audit log, without wrapping its transport or the readers it returns.
+//!
+//! Set an [`Observer`] with [`RepositoryLoader::observer`]. It's called with an [`Event`] as each
+//! metadata role is verified while the repository is loaded or refreshed, and as each target read
+//! with [`Repository::read_target`] starts, progresses, and finishes. Targets saved with
+//! [`Repository::save_target`] or [`Repository::save_targets`] are read the same way.
+//!
+//! [`RepositoryLoader::observer`]: crate::RepositoryLoader::observer
+//! [`Repository::read_target`]: crate::Repository::read_target
+//! [`Repository::save_target`]: crate::Repository::save_target
+//! [`Repository::save_targets`]: crate::Repository::save_targets
+
+use crate::schema::RoleType;
+use std::fmt::Debug;
+use std::io::{self, Read};
+use std::num::NonZeroU64;
+use std::sync::Arc;
+
+/// Receives the [`Event`]s of a repository.
+///
+/// The observer is called on the thread doing the work, so it should return quickly. It's shared
+/// by clones of the repository, and by the threads of [`Repository::save_targets`].
+///
+/// [`Repository::save_targets`]: crate::Repository::save_targets
+pub trait Observer: Debug + Send + Sync {
+    /// Called for each event.
+    fn event(&self, event: &Event<'_>);
+}
+
+/// Something a repository fetched or verified, as passed to an [`Observer`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// The metadata of a role was fetched, or read from the datastore, and verified.
+    MetadataVerified {
+        /// The type of the role.
+        role: RoleType,
+        /// The name of the role: `root`, `timestamp`, `snapshot`, `targets`, or the name of a
+        /// delegated role.
+        name: &'a str,
+        /// The version of the verified metadata.
+        version: NonZeroU64,
+    },
+
+    /// A target is being fetched.
+    TargetStarted {
+        /// The name of the target.
+        name: &'a str,
+        /// The length of the target listed in its targets metadata.
+        length: u64,
+    },
+
+    /// More of a target was read. The target is not verified until [`Event::TargetFinished`].
+    TargetProgress {
+        /// The name of the target.
+        name: &'a str,
+        /// The number of bytes read so far.
+        bytes: u64,
+        /// The length of the target listed in its targets metadata.
+        length: u64,
+    },
+
+    /// A target was read to the end and verified.
+    TargetFinished {
+        /// The name of the target.
+        name: &'a str,
+        /// The number of bytes read.
+        bytes: u64,
+    },
+
+    /// Fetching or reading a target failed, such as when it couldn't be fetched or didn't match its
+    /// hash. The target must not be used.
+    TargetFailed {
+        /// The name of the target.
+        name: &'a str,
+        /// The error the read failed with.
+        error: &'a io::Error,
+    },
+}
+
+/// Reports the progress of reading the target `name` to an observer. Wraps the verifying reader,
+/// so that the end of file is only reported once the target is verified.
+pub(crate) struct ObservingReader<R> {
+    reader: R,
+    observer: Arc<dyn Observer>,
+    name: String,
+    length: u64,
+    bytes: u64,
+    done: bool,
+}
+
+impl<R> ObservingReader<R> {
+    /// Returns a reader that reports the progress of the target `name`, which the caller has
+    /// already reported as started.
+    pub(crate) fn new(reader: R, observer: Arc<dyn Observer>, name: &str, length: u64) -> Self {
+        Self {
+            reader,
+            observer,
+            name: name.to_owned(),
+            length,
+            bytes: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: Read> Read for ObservingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.reader.read(buf);
+        if self.done {
+            return result;
+        }
+        let name = self.name.as_str();
+        match &result {
+            Ok(0) if buf.is_empty() => {}
+            Ok(0) => {
+                self.done = true;
+                self.observer.event(&Event::TargetFinished {
+                    name,
+                    bytes: self.bytes,
+                });
+            }
+            Ok(size) => {
+                self.bytes += *size as u64;
+                self.observer.event(&Event::TargetProgress {
+                    name,
+                    bytes: self.bytes,
+                    length: self.length,
+                });
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => {
+                self.done = true;
+                self.observer.event(&Event::TargetFailed { name, error });
+            }
+        }
+        result
+    }
+}
@@ -32,6 +32,23 @@ impl<T> Decoded<T> {
     pub fn into_vec(self) -> Vec<u8> {
         self.bytes
     }
+
+    /// Returns the original encoded form of the bytes, such as a hex string or PEM document.
+    pub(crate) fn original(&self) -> &str {
+        &self.original
+    }
+}
+
+impl<T: Encode> Decoded<T> {
+    /// Returns a copy of this object whose original form is encoded afresh from the bytes, such
+    /// as lowercase hex for [`Hex`]. Copies normalized this way serialize identically if and only
+    /// if they're equal.
+    ///
+    /// The original form of a `Decoded` in signed metadata must be kept as it is, since it's part
+    /// of what was signed.
+    pub fn normalized(&self) -> Self {
+        Self::from(self.bytes.clone())
+    }
 }
 
 impl<T: Encode> From<Vec<u8>> for Decoded<T> {
@@ -92,13 +109,13 @@ pub struct RsaPem;
 
 impl Decode for RsaPem {
     fn decode(s: &str) -> Result<Vec<u8>, Error> {
-        spki::decode(spki::OID_RSA_ENCRYPTION, None, s)
+        spki::decode(spki::OID_RSA_ENCRYPTION, spki::Parameters::Null, s)
     }
 }
 
 impl Encode for RsaPem {
     fn encode(b: &[u8]) -> String {
-        spki::encode(spki::OID_RSA_ENCRYPTION, None, b)
+        spki::encode(spki::OID_RSA_ENCRYPTION, spki::Parameters::Null, b)
     }
 }
 
@@ -110,7 +127,7 @@ impl Decode for EcdsaPem {
     fn decode(s: &str) -> Result<Vec<u8>, Error> {
         spki::decode(
             spki::OID_EC_PUBLIC_KEY,
-            Some(spki::OID_EC_PARAM_SECP256R1),
+            spki::Parameters::Oid(spki::OID_EC_PARAM_SECP256R1),
             s,
         )
     }
@@ -120,12 +137,29 @@ impl Encode for EcdsaPem {
     fn encode(b: &[u8]) -> String {
         spki::encode(
             spki::OID_EC_PUBLIC_KEY,
-            Some(spki::OID_EC_PARAM_SECP256R1),
+            spki::Parameters::Oid(spki::OID_EC_PARAM_SECP256R1),
             b,
         )
     }
 }
 
+/// [`Decode`]/[`Encode`] implementation for PEM-encoded Ed25519 public keys. TUF metadata lists
+/// Ed25519 keys in hex, but PEM is convenient for sharing them with other tools.
+#[derive(Debug, Clone, Copy)]
+pub struct Ed25519Pem;
+
+impl Decode for Ed25519Pem {
+    fn decode(s: &str) -> Result<Vec<u8>, Error> {
+        spki::decode(spki::OID_ED25519, spki::Parameters::Absent, s)
+    }
+}
+
+impl Encode for Ed25519Pem {
+    fn encode(b: &[u8]) -> String {
+        spki::encode(spki::OID_ED25519, spki::Parameters::Absent, b)
+    }
+}
+
 // =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=
 
 impl<'de, T: Decode> Deserialize<'de> for Decoded<T> {
@@ -71,6 +71,18 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    /// A role was not signed by a threshold of keys in any of the candidate roots.
+    #[snafu(display(
+        "Role {} is not signed by a threshold of keys in any of {} trusted roots",
+        role,
+        roots
+    ))]
+    NoTrustedRoot {
+        role: RoleType,
+        roots: usize,
+        backtrace: Backtrace,
+    },
+
     /// Failed to decode a PEM-encoded key.
     #[snafu(display("Invalid PEM string: {}", source))]
     PemDecode {
@@ -80,15 +92,32 @@ pub enum Error {
 
     /// A signature threshold specified in root.json was not met when verifying a signature.
     #[snafu(display(
-        "Signature threshold of {} not met for role {} ({} valid signatures)",
+        "Signature threshold of {} not met for role {} ({} valid signatures){}",
         threshold,
         role,
         valid,
+        unreachable_threshold(*threshold, *authorized),
     ))]
     SignatureThreshold {
         role: RoleType,
         threshold: u64,
         valid: u64,
+        /// The number of keys authorized to sign for the role.
+        authorized: u64,
+        backtrace: Backtrace,
+    },
+
+    /// A `spec_version` is not of the form `MAJOR.MINOR` or `MAJOR.MINOR.PATCH`.
+    #[snafu(display("Invalid spec version '{}'", version))]
+    SpecVersionParse {
+        version: String,
+        backtrace: Backtrace,
+    },
+
+    /// The timestamp's meta doesn't describe exactly one file, snapshot.json.
+    #[snafu(display("Timestamp meta must describe only snapshot.json, found {:?}", files))]
+    TimestampMeta {
+        files: Vec<String>,
         backtrace: Backtrace,
     },
 
@@ -100,6 +129,16 @@ pub enum Error {
     #[snafu(display("TUF targets must be files, given: '{}'", path.display()))]
     TargetNotAFile { path: PathBuf, backtrace: Backtrace },
 
+    /// A target's storage path is not a relative path within the targets directory.
+    #[snafu(display(
+        "Storage path {} must be a relative path string without '..' components",
+        storage_path
+    ))]
+    InvalidStoragePath {
+        storage_path: String,
+        backtrace: Backtrace,
+    },
+
     /// Target doesn't have proper permissions from parent delegations
     #[snafu(display("Invalid file permissions from parent delegation: {}", child))]
     UnmatchedPath { child: String },
@@ -118,6 +157,19 @@ pub enum Error {
     RoleNotFound { name: String },
 }
 
+/// Explains a threshold that can never be met because fewer keys than the threshold are authorized,
+/// such as a placeholder threshold that was never changed.
+fn unreachable_threshold(threshold: u64, authorized: u64) -> String {
+    if threshold > authorized {
+        format!(
+            "; threshold {} exceeds {} authorized keys",
+            threshold, authorized
+        )
+    } else {
+        String::new()
+    }
+}
+
 /// Wrapper for error types that don't impl [`std::error::Error`].
 ///
 /// This should not have to exist, and yet...
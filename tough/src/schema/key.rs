@@ -2,7 +2,7 @@
 
 //! Handles cryptographic keys and their serialization in TUF metadata files.
 
-use crate::schema::decoded::{Decoded, EcdsaPem, Hex, RsaPem};
+use crate::schema::decoded::{Decoded, EcdsaPem, Ed25519Pem, Encode, Hex, RsaPem};
 use crate::schema::error::{self, Result};
 use olpc_cjson::CanonicalFormatter;
 use ring::digest::{digest, SHA256};
@@ -33,6 +33,9 @@ use std::str::FromStr;
 ///  * `Rsa`: PUBLIC is in PEM format and a string. All RSA keys must be at least 2048 bits.
 ///  * `Ed25519`: PUBLIC is a 64-byte hex encoded string.
 ///  * `Ecdsa`: PUBLIC is in PEM format and a string.
+///
+/// Keys of any other type are read as [`Key::Unknown`], so that metadata listing a key type added
+/// after this library can still be loaded.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "keytype")]
@@ -67,6 +70,31 @@ pub enum Key {
         #[serde(flatten)]
         _extra: HashMap<String, Value>,
     },
+    /// A key of a type this library doesn't recognize. It can't verify signatures, so its
+    /// signatures never count toward a threshold, but its fields are kept as read so that its key
+    /// ID can still be calculated.
+    #[serde(untagged)]
+    Unknown {
+        /// The key type, which is none of the types above.
+        #[serde(deserialize_with = "de_unknown_keytype")]
+        keytype: String,
+        /// The remaining fields of the key, such as `keyval` and `scheme`.
+        #[serde(flatten)]
+        _extra: HashMap<String, Value>,
+    },
+}
+
+/// Reads the key type of a [`Key::Unknown`], rejecting the known types so that a malformed key of
+/// a known type is an error rather than an unknown key.
+fn de_unknown_keytype<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let keytype = String::deserialize(deserializer)?;
+    if ["rsa", "ed25519", "ecdsa"].contains(&keytype.as_str()) {
+        return Err(serde::de::Error::custom(format!("invalid {} key", keytype)));
+    }
+    Ok(keytype)
 }
 
 /// Used to identify the RSA signature scheme in use.
@@ -75,6 +103,8 @@ pub enum Key {
 pub enum RsaScheme {
     /// `rsassa-pss-sha256`: RSA Probabilistic signature scheme with appendix.
     RsassaPssSha256,
+    /// `rsassa-pss-sha512`: RSA Probabilistic signature scheme with appendix, using SHA-512.
+    RsassaPssSha512,
 }
 
 /// Represents a deserialized (decoded) RSA public key.
@@ -128,17 +158,54 @@ pub struct EcdsaKey {
 }
 
 impl Key {
-    /// Calculate the key ID for this key.
+    /// Calculate the key ID for this key, as defined by the TUF specification: the hex-encoded
+    /// SHA-256 digest of the key's canonical JSON form.
+    ///
+    /// Every field of the key is part of that form, including `keyid_hash_algorithms` if the key
+    /// lists it, so a key read from metadata that lists the field has a different ID than the same
+    /// key parsed from PEM. The field doesn't change the digest used, which is always SHA-256, as
+    /// in python-tuf.
     pub fn key_id(&self) -> Result<Decoded<Hex>> {
         let mut buf = Vec::new();
-        let mut ser = serde_json::Serializer::with_formatter(&mut buf, CanonicalFormatter::new());
+        let mut ser = serde_json::Serializer::with_formatter(
+            &mut buf,
+            CanonicalFormatter::without_normalization(),
+        );
         self.serialize(&mut ser).context(error::JsonSerialization {
             what: "key".to_owned(),
         })?;
         Ok(digest(&SHA256, &buf).as_ref().to_vec().into())
     }
 
-    /// Verify a signature of an object made with this key.
+    /// Returns the public key as a PEM-encoded `SubjectPublicKeyInfo` document, which can be
+    /// parsed back into the same key with [`FromStr`]. RSA and ECDSA keys are listed as PEM in
+    /// metadata already, and are returned exactly as listed. Returns `None` for a
+    /// [`Key::Unknown`].
+    pub fn to_pem(&self) -> Option<String> {
+        match self {
+            Key::Rsa { keyval, .. } => Some(keyval.public.original().to_owned()),
+            Key::Ed25519 { keyval, .. } => Some(Ed25519Pem::encode(&keyval.public)),
+            Key::Ecdsa { keyval, .. } => Some(keyval.public.original().to_owned()),
+            Key::Unknown { .. } => None,
+        }
+    }
+
+    /// Returns true if `other` is the same type of key with the same public key bytes, regardless
+    /// of the signature scheme each declares. Such keys have different key IDs, but are signed for
+    /// by the same private key.
+    pub fn same_public_material(&self, other: &Key) -> bool {
+        match (self, other) {
+            (Key::Rsa { keyval: a, .. }, Key::Rsa { keyval: b, .. }) => a.public == b.public,
+            (Key::Ed25519 { keyval: a, .. }, Key::Ed25519 { keyval: b, .. }) => {
+                a.public == b.public
+            }
+            (Key::Ecdsa { keyval: a, .. }, Key::Ecdsa { keyval: b, .. }) => a.public == b.public,
+            _ => false,
+        }
+    }
+
+    /// Verify a signature of an object made with this key. Signatures made with a
+    /// [`Key::Unknown`] never verify.
     pub(super) fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
         let (alg, public_key): (&dyn VerificationAlgorithm, untrusted::Input<'_>) = match self {
             Key::Ecdsa {
@@ -165,6 +232,15 @@ impl Key {
                 &ring::signature::RSA_PSS_2048_8192_SHA256,
                 untrusted::Input::from(&keyval.public),
             ),
+            Key::Rsa {
+                scheme: RsaScheme::RsassaPssSha512,
+                keyval,
+                ..
+            } => (
+                &ring::signature::RSA_PSS_2048_8192_SHA512,
+                untrusted::Input::from(&keyval.public),
+            ),
+            Key::Unknown { .. } => return false,
         };
 
         alg.verify(
@@ -189,6 +265,15 @@ impl FromStr for Key {
                 scheme: RsaScheme::RsassaPssSha256,
                 _extra: HashMap::new(),
             })
+        } else if let Ok(public) = serde_plain::from_str::<Decoded<Ed25519Pem>>(s) {
+            Ok(Key::Ed25519 {
+                keyval: Ed25519Key {
+                    public: public.into_vec().into(),
+                    _extra: HashMap::new(),
+                },
+                scheme: Ed25519Scheme::Ed25519,
+                _extra: HashMap::new(),
+            })
         } else if let Ok(public) = serde_plain::from_str::<Decoded<Hex>>(s) {
             if public.len() == ring::signature::ED25519_PUBLIC_KEY_LEN {
                 Ok(Key::Ed25519 {
@@ -228,3 +313,50 @@ impl fmt::Display for KeyParseError {
 }
 
 impl std::error::Error for KeyParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::Key;
+    use std::collections::HashMap;
+
+    // The key IDs in the reference implementation's root.json were calculated by python-tuf
+    #[test]
+    fn reference_key_ids() {
+        let root: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/data/tuf-reference-impl/metadata/root.json"
+        ))
+        .unwrap();
+        let keys: HashMap<String, Key> =
+            serde_json::from_value(root["signed"]["keys"].clone()).unwrap();
+        assert!(keys.values().any(|key| matches!(key, Key::Rsa { .. })));
+        assert!(keys.values().any(|key| matches!(key, Key::Ed25519 { .. })));
+        for (key_id, key) in &keys {
+            assert_eq!(&hex::encode(key.key_id().unwrap()), key_id);
+        }
+    }
+
+    // `keyid_hash_algorithms` is part of the key, so it changes the key ID
+    #[test]
+    fn key_id_includes_hash_algorithms() {
+        let root: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/data/tuf-reference-impl/metadata/root.json"
+        ))
+        .unwrap();
+        let key_id = "59a4df8af818e9ed7abe0764c0b47b4240952aa0d179b5b78346c470ac30278d";
+        let mut value = root["signed"]["keys"][key_id].clone();
+        assert!(value["keyid_hash_algorithms"].is_array());
+        let key: Key = serde_json::from_value(value.clone()).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("keyid_hash_algorithms");
+        let bare: Key = serde_json::from_value(value).unwrap();
+
+        assert_eq!(hex::encode(key.key_id().unwrap()), key_id);
+        assert_ne!(key.key_id().unwrap(), bare.key_id().unwrap());
+        assert!(key.same_public_material(&bare));
+        // Parsing the public key alone gives the key without the field
+        let parsed: Key = key.to_pem().unwrap().parse().unwrap();
+        assert_eq!(parsed.key_id().unwrap(), bare.key_id().unwrap());
+    }
+}
@@ -7,13 +7,17 @@ pub mod decoded;
 mod error;
 mod iter;
 pub mod key;
-mod spki;
+mod spec_version;
+pub(crate) mod spki;
 mod verify;
 
+use crate::error::TargetResolutionError;
+use crate::hash::HashAlgorithm;
 use crate::schema::decoded::{Decoded, Hex};
 pub use crate::schema::error::{Error, Result};
 use crate::schema::iter::KeysIter;
 use crate::schema::key::Key;
+pub use crate::schema::spec_version::SpecVersion;
 use crate::sign::Sign;
 pub use crate::transport::{FilesystemTransport, Transport};
 use chrono::{DateTime, Utc};
@@ -23,13 +27,13 @@ use ring::digest::{digest, Context, SHA256};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_plain::{forward_display_to_serde, forward_from_str_to_serde};
-use snafu::ResultExt;
-use std::collections::HashMap;
+use snafu::{ensure, OptionExt, ResultExt};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Read;
 use std::num::NonZeroU64;
 use std::ops::{Deref, DerefMut};
-use std::path::Path;
+use std::path::{Component, Path};
 
 /// The type of metadata role.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
@@ -54,6 +58,27 @@ pub enum RoleType {
 forward_display_to_serde!(RoleType);
 forward_from_str_to_serde!(RoleType);
 
+impl RoleType {
+    /// The top-level roles, each of which root.json lists the keys and threshold for. Delegated
+    /// targets roles are listed by the role that delegates to them instead, so
+    /// [`RoleType::DelegatedTargets`] isn't included.
+    pub const TOP_LEVEL: &'static [RoleType] = &[
+        RoleType::Root,
+        RoleType::Snapshot,
+        RoleType::Targets,
+        RoleType::Timestamp,
+    ];
+
+    /// Returns whether this is one of the roles in [`RoleType::TOP_LEVEL`].
+    pub fn is_top_level(self) -> bool {
+        // Deliberately exhaustive, so that a new role has to be classified here
+        match self {
+            RoleType::Root | RoleType::Snapshot | RoleType::Targets | RoleType::Timestamp => true,
+            RoleType::DelegatedTargets => false,
+        }
+    }
+}
+
 /// A role identifier
 #[derive(Debug, Clone)]
 pub enum RoleId {
@@ -87,7 +112,10 @@ pub trait Role: Serialize {
     /// [More info on canonical JSON](http://wiki.laptop.org/go/Canonical_JSON)
     fn canonical_form(&self) -> Result<Vec<u8>> {
         let mut data = Vec::new();
-        let mut ser = serde_json::Serializer::with_formatter(&mut data, CanonicalFormatter::new());
+        let mut ser = serde_json::Serializer::with_formatter(
+            &mut data,
+            CanonicalFormatter::without_normalization(),
+        );
         self.serialize(&mut ser)
             .context(error::JsonSerialization { what: "role" })?;
         Ok(data)
@@ -103,6 +131,40 @@ pub struct Signed<T> {
     pub signatures: Vec<Signature>,
 }
 
+impl<T> Signed<T> {
+    /// Replaces the signed role with `signed`, returning the previous role. Any existing
+    /// signatures no longer match the new content, so they are cleared.
+    pub fn replace_signed(&mut self, signed: T) -> T {
+        self.signatures.clear();
+        std::mem::replace(&mut self.signed, signed)
+    }
+
+    /// Modifies the signed role in place using `f`. Any existing signatures no longer match the
+    /// modified content, so they are cleared.
+    pub fn update_signed<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        f(&mut self.signed);
+        self.signatures.clear();
+    }
+
+    /// Removes signatures that are identical to an earlier one in the list, keeping the first of
+    /// each, and returns how many were removed. The signed role is not changed, so the remaining
+    /// signatures stay valid.
+    pub fn dedup_signatures(&mut self) -> usize {
+        let before = self.signatures.len();
+        let mut kept: Vec<Signature> = Vec::with_capacity(before);
+        for signature in self.signatures.drain(..) {
+            if !kept.contains(&signature) {
+                kept.push(signature);
+            }
+        }
+        self.signatures = kept;
+        before - self.signatures.len()
+    }
+}
+
 /// A signature and the key ID that made it.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct Signature {
@@ -110,6 +172,59 @@ pub struct Signature {
     pub keyid: Decoded<Hex>,
     /// A hex-encoded signature of the canonical JSON form of a role.
     pub sig: Decoded<Hex>,
+    /// When the signature was made, if the signer chose to record it. This is an extension to the
+    /// specification that other clients ignore. It is not covered by any signature, so it is only
+    /// informational and must not be relied on for verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+}
+
+impl Signature {
+    /// Encodes `keyid` and `sig` as lowercase hex, however they were encoded when parsed, so that
+    /// the same signature is always written the same way. Signatures aren't covered by the
+    /// signatures they accompany, so this doesn't affect verification.
+    pub fn normalize(&mut self) {
+        self.keyid = self.keyid.normalized();
+        self.sig = self.sig.normalized();
+    }
+}
+
+/// The outcome of checking one signature on a role, as returned by
+/// [`Root::signature_statuses`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureStatus {
+    /// The key ID that the signature claims to be made by.
+    pub keyid: Decoded<Hex>,
+    /// Whether the key ID is listed for the role in root.json.
+    pub authorized: bool,
+    /// Whether the signature was made over the role by the key with this key ID in root.json.
+    pub valid: bool,
+}
+
+/// How many valid signatures a role has compared to its threshold, as returned by
+/// [`Root::verify_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThresholdStatus {
+    /// The number of valid signatures by distinct authorized keys the role requires.
+    pub required: u64,
+    /// The number of valid signatures by distinct authorized keys the role has.
+    pub valid: u64,
+    /// Whether `valid` meets `required`, in which case the role verifies.
+    pub satisfied: bool,
+    /// How many more valid signatures the role has than it requires. These are harmless, but may
+    /// point to signers that no longer need to sign the role.
+    pub surplus: u64,
+}
+
+/// The signatures a role still needs to meet its threshold, as returned by
+/// [`Root::missing_signatures`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingSignatures {
+    /// How many more authorized keys must sign the role. This is zero if the threshold is met.
+    pub needed: u64,
+    /// The key IDs listed for the role that have not made a valid signature, in the order they are
+    /// listed. Any `needed` of them signing would meet the threshold.
+    pub unsigned_keyids: Vec<Decoded<Hex>>,
 }
 
 /// A `KeyHolder` is metadata that is responsible for verifying the signatures of a role.
@@ -208,12 +323,47 @@ impl Root {
     /// key ID from Root
     pub fn key_id(&self, key_pair: &dyn Sign) -> Option<Decoded<Hex>> {
         for (key_id, key) in &self.keys {
-            if key_pair.tuf_key() == *key {
+            if key_pair.is_listed_as(key) {
                 return Some(key_id.clone());
             }
         }
         None
     }
+
+    /// Returns the key IDs listed by any role.
+    pub fn all_referenced_keyids(&self) -> HashSet<&Decoded<Hex>> {
+        self.roles
+            .values()
+            .flat_map(|role_keys| role_keys.keyids.iter())
+            .collect()
+    }
+
+    /// Returns the key IDs present in `keys` but not listed by any role. These are often left
+    /// behind when a key is removed from its last role.
+    pub fn orphaned_keyids(&self) -> HashSet<&Decoded<Hex>> {
+        let referenced = self.all_referenced_keyids();
+        self.keys
+            .keys()
+            .filter(|key_id| !referenced.contains(key_id))
+            .collect()
+    }
+
+    /// Calculates the fingerprint of this root: the SHA-256 digest of its canonical JSON form.
+    /// Clients can be given this fingerprint instead of a full copy of root.json; see
+    /// [`RepositoryLoader::from_root_fingerprint`].
+    ///
+    /// [`RepositoryLoader::from_root_fingerprint`]: crate::RepositoryLoader::from_root_fingerprint
+    pub fn fingerprint(&self) -> Result<Decoded<Hex>> {
+        let mut buf = Vec::new();
+        let mut ser = serde_json::Serializer::with_formatter(
+            &mut buf,
+            CanonicalFormatter::without_normalization(),
+        );
+        self.serialize(&mut ser).context(error::JsonSerialization {
+            what: "root".to_owned(),
+        })?;
+        Ok(digest(&SHA256, &buf).as_ref().to_vec().into())
+    }
 }
 
 impl Role for Root {
@@ -331,6 +481,17 @@ pub struct Hashes {
     pub _extra: HashMap<String, Value>,
 }
 
+impl Hashes {
+    /// Returns the digest listed for `algorithm`, such as "sha256" or "sha512". Returns `None` if
+    /// there is no digest for `algorithm`, or it isn't a hex string.
+    pub fn get(&self, algorithm: &str) -> Option<Vec<u8>> {
+        if algorithm == "sha256" {
+            return Some(self.sha256.to_vec());
+        }
+        hex::decode(self._extra.get(algorithm)?.as_str()?).ok()
+    }
+}
+
 impl Snapshot {
     /// Create a new `Snapshot` object.
     pub fn new(spec_version: String, version: NonZeroU64, expires: DateTime<Utc>) -> Self {
@@ -342,6 +503,18 @@ impl Snapshot {
             _extra: HashMap::new(),
         }
     }
+
+    /// Returns whether this snapshot must list `root.json` in its `meta`, which depends on the
+    /// version of the specification it follows. Drafts before 1.0 had snapshot list every
+    /// metadata file other than timestamp.json, root.json included. From 1.0, repositories may
+    /// leave root.json out, since clients track root versions through the chain of roots.
+    ///
+    /// A `spec_version` that can't be parsed is treated as 1.0 or later.
+    pub fn requires_root_meta(&self) -> bool {
+        self.spec_version
+            .parse::<SpecVersion>()
+            .map_or(false, |version| version.major == 0)
+    }
 }
 impl Role for Snapshot {
     const TYPE: RoleType = RoleType::Snapshot;
@@ -450,8 +623,59 @@ pub struct Target {
 }
 
 impl Target {
+    /// A conventional key for the `custom` field that gives where a target is stored, relative to
+    /// the repository's targets directory. When used, this location replaces the one derived from
+    /// the target's name, including under consistent snapshots. Storage paths are only used if
+    /// the key is passed to [`SignedRepository::storage_path_custom_key`] when writing targets,
+    /// and to [`RepositoryLoader::storage_path_custom_key`] when fetching them.
+    ///
+    /// [`SignedRepository::storage_path_custom_key`]: crate::editor::signed::SignedRepository::storage_path_custom_key
+    /// [`RepositoryLoader::storage_path_custom_key`]: crate::RepositoryLoader::storage_path_custom_key
+    pub const STORAGE_PATH_FIELD: &'static str = "storage_path";
+
+    /// Returns the storage path recorded under `key` in the target's `custom` field, if any. Fails
+    /// if the storage path is not a relative path string that stays within the targets directory.
+    pub fn storage_path(&self, key: &str) -> Result<Option<&str>> {
+        let value = match self.custom.get(key) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let storage_path = value.as_str().context(error::InvalidStoragePath {
+            storage_path: value.to_string(),
+        })?;
+        ensure!(
+            !storage_path.is_empty()
+                && Path::new(storage_path)
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_))),
+            error::InvalidStoragePath {
+                storage_path: value.to_string(),
+            }
+        );
+        Ok(Some(storage_path))
+    }
+
+    /// Returns the file name of the target named `name` in a repository that uses consistent
+    /// snapshots; see [`consistent_target_name`](crate::consistent_target_name). The prefix is
+    /// always the target's SHA-256 digest, even if other hashes, such as `sha512`, are recorded.
+    pub fn consistent_name(&self, name: &str) -> String {
+        crate::consistent_target_name(&self.hashes.sha256, name)
+    }
+
     /// Given a path, returns a Target struct
     pub fn from_path<P>(path: P) -> Result<Target>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_path_with_hash_algorithms(path, &[])
+    }
+
+    /// Given a path, returns a Target struct whose `hashes` list a digest for each of
+    /// `hash_algorithms` as well as SHA-256, which every target lists. The file is only read once.
+    pub fn from_path_with_hash_algorithms<P>(
+        path: P,
+        hash_algorithms: &[Box<dyn HashAlgorithm>],
+    ) -> Result<Target>
     where
         P: AsRef<Path>,
     {
@@ -461,9 +685,14 @@ impl Target {
             return error::TargetNotAFile { path }.fail();
         }
 
-        // Get the sha256 and length of the target
+        // Get the digests and length of the target
         let mut file = File::open(path).context(error::FileOpen { path })?;
         let mut digest = Context::new(&SHA256);
+        let mut extra_digests: Vec<_> = hash_algorithms
+            .iter()
+            .filter(|algorithm| algorithm.name() != "sha256")
+            .map(|algorithm| (algorithm.name(), algorithm.start()))
+            .collect();
         let mut buf = [0; 8 * 1024];
         let mut length = 0;
         loop {
@@ -471,6 +700,9 @@ impl Target {
                 0 => break,
                 n => {
                     digest.update(&buf[..n]);
+                    for (_, extra_digest) in &mut extra_digests {
+                        extra_digest.update(&buf[..n]);
+                    }
                     length += n as u64;
                 }
             }
@@ -480,7 +712,15 @@ impl Target {
             length,
             hashes: Hashes {
                 sha256: Decoded::from(digest.finish().as_ref().to_vec()),
-                _extra: HashMap::new(),
+                _extra: extra_digests
+                    .into_iter()
+                    .map(|(name, extra_digest)| {
+                        (
+                            name.to_owned(),
+                            Value::String(hex::encode(extra_digest.finish())),
+                        )
+                    })
+                    .collect(),
             },
             custom: HashMap::new(),
             _extra: HashMap::new(),
@@ -520,6 +760,98 @@ impl Targets {
         })
     }
 
+    /// Finds a target as `find_target` does, also returning the name of the delegated role that
+    /// lists it, or `None` if this role lists it directly.
+    pub(crate) fn find_target_and_role(
+        &self,
+        target_name: &str,
+    ) -> Result<(Option<&str>, &Target)> {
+        if let Some(target) = self.targets.get(target_name) {
+            return Ok((None, target));
+        }
+        if let Some(delegations) = &self.delegations {
+            for role in &delegations.roles {
+                if let Some(targets) = &role.targets {
+                    if let Ok((name, target)) = targets.signed.find_target_and_role(target_name) {
+                        return Ok((Some(name.unwrap_or(&role.name)), target));
+                    }
+                }
+            }
+        }
+        Err(Error::TargetNotFound {
+            target_file: target_name.to_string(),
+        })
+    }
+
+    /// Resolves a target as a client following the TUF specification does, returning the name of
+    /// the delegated role that lists it, or `None` if this role lists it directly. Unlike
+    /// `find_target_and_role`, only delegated roles whose paths match the target are consulted,
+    /// and the search ends at the first terminating delegation or unloaded role that matches it.
+    pub(crate) fn resolve_target(
+        &self,
+        target_name: &str,
+    ) -> std::result::Result<(Option<&str>, &Target), TargetResolutionError> {
+        let search = self.search_target(target_name, true);
+        if let Some(TargetSearch::Found(role, target)) = search {
+            return Ok((role, target));
+        }
+        let target = target_name.to_owned();
+        match search {
+            Some(TargetSearch::Unloaded(role)) => {
+                return Err(TargetResolutionError::Unreachable {
+                    target,
+                    role: role.to_owned(),
+                })
+            }
+            Some(TargetSearch::Terminated(role)) => {
+                if let Some(TargetSearch::Found(listed_by, _)) =
+                    self.search_target(target_name, false)
+                {
+                    return Err(TargetResolutionError::ExcludedByTerminating {
+                        target,
+                        role: role.to_owned(),
+                        listed_by: listed_by.unwrap_or("targets").to_owned(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        Err(match self.find_target_and_role(target_name) {
+            Ok((role, _)) => TargetResolutionError::OutsideDelegatedPaths {
+                target,
+                role: role.unwrap_or("targets").to_owned(),
+            },
+            Err(_) => TargetResolutionError::NotListed { target },
+        })
+    }
+
+    /// Searches this role, then the delegated roles whose paths match `target_name`, for the
+    /// target. Returns `None` if the search should continue with the next role.
+    fn search_target(&self, target_name: &str, terminate: bool) -> Option<TargetSearch<'_>> {
+        if let Some(target) = self.targets.get(target_name) {
+            return Some(TargetSearch::Found(None, target));
+        }
+        let roles = self.delegations.iter().flat_map(|d| &d.roles);
+        for role in roles.filter(|role| role.paths.matched_target(target_name)) {
+            let targets = match &role.targets {
+                Some(targets) => targets,
+                None if terminate => return Some(TargetSearch::Unloaded(&role.name)),
+                None => continue,
+            };
+            match targets.signed.search_target(target_name, terminate) {
+                Some(TargetSearch::Found(None, target)) => {
+                    return Some(TargetSearch::Found(Some(&role.name), target))
+                }
+                Some(search) => return Some(search),
+                None if terminate && role.terminating => {
+                    return Some(TargetSearch::Terminated(&role.name))
+                }
+                None => {}
+            }
+        }
+        None
+    }
+
     /// Returns a hashmap of all targets and all delegated targets recursively
     pub fn targets_map(&self) -> HashMap<String, &Target> {
         let mut targets_map = HashMap::new();
@@ -537,6 +869,47 @@ impl Targets {
         targets_map
     }
 
+    /// Returns an iterator over all targets, including those of delegated roles, yielding each
+    /// target name once. Where more than one role lists the same target, the role that a client
+    /// would consult first takes priority: this role's own targets, then its delegated roles in
+    /// order, depth first. Once a terminating role, or a role whose targets weren't loaded, has
+    /// been visited, later roles are not consulted for the paths it was delegated.
+    pub fn all_targets(&self) -> impl Iterator<Item = (&String, &Target)> + '_ {
+        let mut all = Vec::new();
+        self.collect_targets(&mut HashSet::new(), &mut Vec::new(), &mut all);
+        all.into_iter()
+    }
+
+    /// Returns the names of all targets, including those of delegated roles. This is empty, not an
+    /// error, for a repository that has no targets.
+    pub fn target_names(&self) -> HashSet<&String> {
+        self.all_targets().map(|(name, _)| name).collect()
+    }
+
+    fn collect_targets<'a>(
+        &'a self,
+        seen: &mut HashSet<&'a str>,
+        terminated: &mut Vec<&'a PathSet>,
+        all: &mut Vec<(&'a String, &'a Target)>,
+    ) {
+        for (name, target) in &self.targets {
+            if !terminated.iter().any(|paths| paths.matched_target(name)) && seen.insert(name) {
+                all.push((name, target));
+            }
+        }
+        if let Some(delegations) = &self.delegations {
+            for role in &delegations.roles {
+                if let Some(targets) = &role.targets {
+                    targets.signed.collect_targets(seen, terminated, all);
+                }
+                // A role without targets couldn't be loaded, and may have taken priority
+                if role.terminating || role.targets.is_none() {
+                    terminated.push(&role.paths);
+                }
+            }
+        }
+    }
+
     /// Returns an iterator of all targets delegated
     pub fn targets_iter(&self) -> impl Iterator + '_ {
         self.targets_map().into_iter()
@@ -645,6 +1018,32 @@ impl Targets {
         roles
     }
 
+    /// Returns the names of the delegated roles whose metadata was loaded, walking delegations
+    /// depth first in the order a client consults them. A role reached more than once, such as one
+    /// delegated to by several roles, is listed only the first time.
+    pub fn loaded_role_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_loaded_role_names(&mut HashSet::new(), &mut names);
+        names
+    }
+
+    fn collect_loaded_role_names<'a>(
+        &'a self,
+        seen: &mut HashSet<&'a str>,
+        names: &mut Vec<&'a str>,
+    ) {
+        if let Some(delegations) = &self.delegations {
+            for role in &delegations.roles {
+                if let Some(targets) = &role.targets {
+                    if seen.insert(&role.name) {
+                        names.push(&role.name);
+                        targets.signed.collect_loaded_role_names(seen, names);
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns a reference to the parent delegation of `name`
     pub fn parent_of(&self, name: &str) -> Result<&Delegations> {
         if let Some(delegations) = &self.delegations {
@@ -700,6 +1099,16 @@ impl Targets {
     }
 }
 
+/// Where a search for a target by `Targets::search_target` ended.
+enum TargetSearch<'a> {
+    /// The target was found, listed by the named delegated role or by the role searched.
+    Found(Option<&'a str>, &'a Target),
+    /// The named terminating delegation matched the target but didn't list it.
+    Terminated(&'a str),
+    /// The named delegated role matched the target, but its metadata wasn't loaded.
+    Unloaded(&'a str),
+}
+
 impl Role for Targets {
     const TYPE: RoleType = RoleType::Targets;
 
@@ -825,6 +1234,14 @@ pub struct Delegations {
 
     /// The list of delegated roles.
     pub roles: Vec<DelegatedRole>,
+
+    /// Extra arguments found during deserialization.
+    ///
+    /// We must store these to correctly verify signatures for this object.
+    ///
+    /// If you're instantiating this struct, you should make this `HashMap::empty()`.
+    #[serde(flatten)]
+    pub _extra: HashMap<String, Value>,
 }
 
 /// Each role delegated in a targets file is considered a delegated role
@@ -849,6 +1266,14 @@ pub struct DelegatedRole {
     /// The targets that are signed by this role.
     #[serde(skip)]
     pub targets: Option<Signed<Targets>>,
+
+    /// Extra arguments found during deserialization.
+    ///
+    /// We must store these to correctly verify signatures for this object.
+    ///
+    /// If you're instantiating this struct, you should make this `HashMap::empty()`.
+    #[serde(flatten)]
+    pub _extra: HashMap<String, Value>,
 }
 
 /// Specifies the target paths that a delegated role controls.
@@ -933,6 +1358,7 @@ impl Delegations {
         Delegations {
             keys: HashMap::new(),
             roles: Vec::new(),
+            _extra: HashMap::new(),
         }
     }
 
@@ -967,7 +1393,7 @@ impl Delegations {
     /// key ID from Delegation
     pub fn key_id(&self, key_pair: &dyn Sign) -> Option<Decoded<Hex>> {
         for (key_id, key) in &self.keys {
-            if key_pair.tuf_key() == *key {
+            if key_pair.is_listed_as(key) {
                 return Some(key_id.clone());
             }
         }
@@ -1070,6 +1496,42 @@ impl Timestamp {
             _extra: HashMap::new(),
         }
     }
+
+    /// Creates a new `Timestamp` object that describes `snapshot`, recording the version of the
+    /// snapshot along with the `length` and `hashes` of the file it's written to. The timestamp
+    /// follows the same `spec_version` as the snapshot.
+    pub fn from_snapshot(
+        snapshot: &Snapshot,
+        length: u64,
+        hashes: Hashes,
+        version: NonZeroU64,
+        expires: DateTime<Utc>,
+    ) -> Self {
+        let mut timestamp = Timestamp::new(snapshot.spec_version.clone(), version, expires);
+        timestamp.meta.insert(
+            "snapshot.json".to_owned(),
+            TimestampMeta {
+                length,
+                hashes,
+                version: snapshot.version,
+                _extra: HashMap::new(),
+            },
+        );
+        timestamp
+    }
+
+    /// Returns the description of snapshot.json. Fails unless `meta` describes exactly one file,
+    /// snapshot.json, as the spec requires.
+    pub fn snapshot_meta(&self) -> Result<&TimestampMeta> {
+        match self.meta.get("snapshot.json") {
+            Some(meta) if self.meta.len() == 1 => Ok(meta),
+            _ => {
+                let mut files = self.meta.keys().cloned().collect::<Vec<_>>();
+                files.sort();
+                error::TimestampMeta { files }.fail()
+            }
+        }
+    }
 }
 
 impl Role for Timestamp {
@@ -1087,3 +1549,38 @@ impl Role for Timestamp {
         "timestamp.json".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RoleType;
+
+    #[test]
+    fn top_level_roles() {
+        // Every variant; `RoleType::is_top_level` won't compile once a variant is added until it's
+        // classified, and this list should be extended along with it.
+        let variants = [
+            RoleType::Root,
+            RoleType::Snapshot,
+            RoleType::Targets,
+            RoleType::Timestamp,
+            RoleType::DelegatedTargets,
+        ];
+        for role in &variants {
+            assert_eq!(
+                RoleType::TOP_LEVEL.contains(role),
+                role.is_top_level(),
+                "{}",
+                role
+            );
+        }
+        assert_eq!(
+            RoleType::TOP_LEVEL,
+            [
+                RoleType::Root,
+                RoleType::Snapshot,
+                RoleType::Targets,
+                RoleType::Timestamp
+            ]
+        );
+    }
+}
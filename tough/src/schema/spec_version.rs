@@ -0,0 +1,106 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides a typed form of the `spec_version` field of roles.
+
+use crate::schema::error::{self, Error};
+use snafu::OptionExt;
+use std::fmt;
+use std::str::FromStr;
+
+/// The major and minor version of the TUF specification that metadata follows, parsed from a
+/// role's `spec_version`. The patch version is optional in the string and is not kept, since it
+/// doesn't affect the format of metadata.
+///
+/// Versions order by major version, then minor version, so `1.10` is newer than `1.9`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpecVersion {
+    /// The major version. Metadata with a different major version is not compatible.
+    pub major: u64,
+    /// The minor version. Newer minor versions may add fields that older readers don't know.
+    pub minor: u64,
+}
+
+impl SpecVersion {
+    /// Returns whether metadata following `other` can be read by an implementation of this
+    /// version: the major versions must match, and `other` must not be a newer minor version.
+    pub fn supports(self, other: SpecVersion) -> bool {
+        self.major == other.major && other.minor <= self.minor
+    }
+}
+
+impl FromStr for SpecVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let context = || error::SpecVersionParse { version: s };
+        let mut parts = s.split('.').map(|part| {
+            // `u64::from_str` accepts a leading `+`, which isn't valid in a version
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                None
+            } else {
+                part.parse::<u64>().ok()
+            }
+        });
+        let major = parts.next().flatten().context(context())?;
+        let minor = parts.next().flatten().context(context())?;
+        // An optional patch version, and nothing after it
+        if let Some(patch) = parts.next() {
+            patch.context(context())?;
+        }
+        if parts.next().is_some() {
+            return context().fail();
+        }
+        Ok(Self { major, minor })
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpecVersion;
+
+    fn parse(s: &str) -> SpecVersion {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_versions() {
+        assert_eq!(parse("1.0"), SpecVersion { major: 1, minor: 0 });
+        assert_eq!(parse("1.0.0"), SpecVersion { major: 1, minor: 0 });
+        assert_eq!(parse("1.0.31"), SpecVersion { major: 1, minor: 0 });
+        assert_eq!(
+            parse("1.10"),
+            SpecVersion {
+                major: 1,
+                minor: 10
+            }
+        );
+        assert!(parse("1.10") > parse("1.9"));
+        assert_eq!(parse("1.10.0").to_string(), "1.10");
+    }
+
+    #[test]
+    fn reject_malformed_versions() {
+        for bad in &[
+            "x.y", "1", "", "1.", ".1", "1.0.", "1.0.0.0", "1.x", "+1.0", "1.0-rc1",
+        ] {
+            assert!(bad.parse::<SpecVersion>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn supports() {
+        let supported = parse("1.1.0");
+        assert!(supported.supports(parse("1.0")));
+        assert!(supported.supports(parse("1.1.5")));
+        assert!(!supported.supports(parse("1.2.0")));
+        assert!(!supported.supports(parse("2.0.0")));
+        assert!(!supported.supports(parse("0.9.0")));
+    }
+}
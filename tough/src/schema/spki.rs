@@ -24,13 +24,26 @@ use snafu::{OptionExt, ResultExt};
 pub(super) static OID_RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113_549, 1, 1, 1];
 pub(super) static OID_EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10_045, 2, 1];
 pub(super) static OID_EC_PARAM_SECP256R1: &[u64] = &[1, 2, 840, 10_045, 3, 1, 7];
+pub(super) static OID_ED25519: &[u64] = &[1, 3, 101, 112];
+
+/// The parameters of the algorithm identifier in a `SubjectPublicKeyInfo` document.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Parameters {
+    /// An ASN.1 NULL, as used for RSA.
+    Null,
+    /// An object identifier, such as the curve of an EC key.
+    Oid(&'static [u64]),
+    /// No parameters at all, as used for Ed25519 (RFC 8410).
+    Absent,
+}
 
 /// Wrap a bit string in a `SubjectPublicKeyInfo` document.
-pub(super) fn encode(algorithm_oid: &[u64], parameters_oid: Option<&[u64]>, b: &[u8]) -> String {
+pub(super) fn encode(algorithm_oid: &[u64], parameters: Parameters, b: &[u8]) -> String {
     let mut alg_ident = asn1_tag(der::Tag::OID, asn1_encode_oid(algorithm_oid));
-    alg_ident.extend(match parameters_oid {
-        Some(oid) => asn1_tag(der::Tag::OID, asn1_encode_oid(oid)),
-        None => asn1_tag(der::Tag::Null, Vec::new()),
+    alg_ident.extend(match parameters {
+        Parameters::Oid(oid) => asn1_tag(der::Tag::OID, asn1_encode_oid(oid)),
+        Parameters::Null => asn1_tag(der::Tag::Null, Vec::new()),
+        Parameters::Absent => Vec::new(),
     });
     let alg_ident = asn1_tag(der::Tag::Sequence, alg_ident);
 
@@ -59,7 +72,7 @@ pub(super) fn encode(algorithm_oid: &[u64], parameters_oid: Option<&[u64]>, b: &
 /// Extract the bit string from a PEM-encoded `SubjectPublicKeyInfo` document.
 pub(super) fn decode(
     algorithm_oid: &[u64],
-    parameters_oid: Option<&[u64]>,
+    parameters: Parameters,
     input: &str,
 ) -> Result<Vec<u8>> {
     let pem = pem::parse(input)
@@ -77,14 +90,20 @@ pub(super) fn decode(
                                 {
                                     return Err(ring::error::Unspecified);
                                 }
-                                if let Some(parameters_oid) = parameters_oid {
-                                    if der::expect_tag_and_get_value(input, der::Tag::OID)?
-                                        != untrusted::Input::from(&asn1_encode_oid(parameters_oid))
-                                    {
-                                        return Err(ring::error::Unspecified);
+                                match parameters {
+                                    Parameters::Oid(parameters_oid) => {
+                                        if der::expect_tag_and_get_value(input, der::Tag::OID)?
+                                            != untrusted::Input::from(&asn1_encode_oid(
+                                                parameters_oid,
+                                            ))
+                                        {
+                                            return Err(ring::error::Unspecified);
+                                        }
+                                    }
+                                    Parameters::Null => {
+                                        der::expect_tag_and_get_value(input, der::Tag::Null)?;
                                     }
-                                } else {
-                                    der::expect_tag_and_get_value(input, der::Tag::Null)?;
+                                    Parameters::Absent => {}
                                 }
                                 Ok(())
                             })
@@ -100,6 +119,22 @@ pub(super) fn decode(
         .to_owned())
 }
 
+/// Wrap a DER-encoded SEC1 `ECPrivateKey` for the P-256 curve in a PKCS#8 `PrivateKeyInfo`
+/// document, which is the only form of EC private key that `ring` reads. This is what OpenSSL
+/// writes as "EC PRIVATE KEY" and "PRIVATE KEY" PEM respectively.
+pub(crate) fn ec_private_key_to_pkcs8(sec1: &[u8]) -> Vec<u8> {
+    let mut alg_ident = asn1_tag(der::Tag::OID, asn1_encode_oid(OID_EC_PUBLIC_KEY));
+    alg_ident.extend(asn1_tag(
+        der::Tag::OID,
+        asn1_encode_oid(OID_EC_PARAM_SECP256R1),
+    ));
+
+    let mut sequence = asn1_tag(der::Tag::Integer, vec![0]);
+    sequence.extend(asn1_tag(der::Tag::Sequence, alg_ident));
+    sequence.extend(asn1_tag(der::Tag::OctetString, sec1.to_vec()));
+    asn1_tag(der::Tag::Sequence, sequence)
+}
+
 fn asn1_tag(tag: der::Tag, data: Vec<u8>) -> Vec<u8> {
     let mut v = vec![tag as u8];
     v.extend(asn1_encode_len(data.len()));
@@ -154,7 +189,10 @@ fn to_vlq(n: u64) -> Vec<u8> {
 
 #[cfg(test)]
 mod tests {
-    use super::{asn1_encode_len, asn1_encode_oid, to_vlq, OID_RSA_ENCRYPTION};
+    use super::{
+        asn1_encode_len, asn1_encode_oid, decode, encode, to_vlq, Parameters, OID_ED25519,
+        OID_RSA_ENCRYPTION,
+    };
 
     #[test]
     fn test_asn1_encode_len() {
@@ -187,4 +225,16 @@ mod tests {
         assert_eq!(to_vlq(0x0800_0000), [0xc0, 0x80, 0x80, 0x00]);
         assert_eq!(to_vlq(0x0fff_ffff), [0xff, 0xff, 0xff, 0x7f]);
     }
+
+    #[test]
+    fn test_ed25519_round_trip() {
+        let public = [0x42; 32];
+        let pem = encode(OID_ED25519, Parameters::Absent, &public);
+        // The base64 of the DER prefix every Ed25519 SubjectPublicKeyInfo shares
+        assert!(pem.contains("MCowBQYDK2VwAyEA"), "{}", pem);
+        assert_eq!(
+            decode(OID_ED25519, Parameters::Absent, &pem).unwrap(),
+            public
+        );
+    }
 }
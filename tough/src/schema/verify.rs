@@ -1,33 +1,105 @@
+use super::decoded::{Decoded, Hex};
 use super::error::{self, Result};
-use super::{Delegations, Role, RoleType, Root, Signed, Targets};
+use super::key::Key;
+use super::{
+    Delegations, MissingSignatures, Role, RoleType, Root, Signature, SignatureStatus, Signed,
+    Targets, ThresholdStatus,
+};
 use olpc_cjson::CanonicalFormatter;
 use serde::Serialize;
 use snafu::{ensure, OptionExt, ResultExt};
 use std::collections::HashSet;
 
+/// Serializes a role to canonical form, normalizing strings as NFC if `normalize` is set.
+fn canonical_form<T: Serialize>(signed: &T, what: String, normalize: bool) -> Result<Vec<u8>> {
+    let formatter = if normalize {
+        CanonicalFormatter::new()
+    } else {
+        CanonicalFormatter::without_normalization()
+    };
+    let mut data = Vec::new();
+    let mut ser = serde_json::Serializer::with_formatter(&mut data, formatter);
+    signed
+        .serialize(&mut ser)
+        .context(error::JsonSerialization { what })?;
+    Ok(data)
+}
+
+/// The canonical forms that a role's signatures may have been made over.
+///
+/// Signatures are made over the canonical form with strings written as given, as the TUF
+/// reference implementation signs. Earlier releases of tough normalized strings as NFC before
+/// signing, so a signature that doesn't match is also checked against that form, when it differs.
+struct CanonicalForms {
+    data: Vec<u8>,
+    normalized: Option<Vec<u8>>,
+}
+
+impl CanonicalForms {
+    fn new<T: Serialize>(signed: &T, what: String) -> Result<Self> {
+        let data = canonical_form(signed, what.clone(), false)?;
+        let normalized = canonical_form(signed, what, true)?;
+        Ok(Self {
+            normalized: if normalized == data {
+                None
+            } else {
+                Some(normalized)
+            },
+            data,
+        })
+    }
+
+    /// Returns whether `key` made `sig` over either canonical form.
+    fn verify(&self, key: &Key, sig: &[u8]) -> bool {
+        key.verify(&self.data, sig)
+            || self
+                .normalized
+                .as_ref()
+                .map_or(false, |normalized| key.verify(normalized, sig))
+    }
+}
+
 impl Root {
     /// Checks that the given metadata role is valid based on a threshold of key signatures.
     pub fn verify_role<T: Role + Serialize>(&self, role: &Signed<T>) -> Result<()> {
+        let status = self.verify_status(role)?;
+        ensure!(
+            status.satisfied,
+            error::SignatureThreshold {
+                role: T::TYPE,
+                threshold: status.required,
+                valid: status.valid,
+                authorized: self.authorized_key_count(T::TYPE),
+            }
+        );
+        Ok(())
+    }
+
+    /// Returns the number of distinct keys authorized to sign for `role`.
+    fn authorized_key_count(&self, role: RoleType) -> u64 {
+        self.roles.get(&role).map_or(0, |role_keys| {
+            role_keys.keyids.iter().collect::<HashSet<_>>().len() as u64
+        })
+    }
+
+    /// Counts the valid signatures on the given metadata role against the role's threshold. Unlike
+    /// [`Root::verify_role`], this succeeds whether or not the threshold is met, so the result can
+    /// be shown as progress, such as "2 of 3".
+    pub fn verify_status<T: Role + Serialize>(&self, role: &Signed<T>) -> Result<ThresholdStatus> {
         let role_keys = self
             .roles
             .get(&T::TYPE)
             .context(error::MissingRole { role: T::TYPE })?;
         let mut valid = 0;
 
-        let mut data = Vec::new();
-        let mut ser = serde_json::Serializer::with_formatter(&mut data, CanonicalFormatter::new());
-        role.signed
-            .serialize(&mut ser)
-            .context(error::JsonSerialization {
-                what: format!("{} role", T::TYPE),
-            })?;
+        let data = CanonicalForms::new(&role.signed, format!("{} role", T::TYPE))?;
 
         let mut valid_keyids = HashSet::new();
 
         for signature in &role.signatures {
             if role_keys.keyids.contains(&signature.keyid) {
                 if let Some(key) = self.keys.get(&signature.keyid) {
-                    if key.verify(&data, &signature.sig) {
+                    if data.verify(key, &signature.sig) {
                         // Ignore duplicate keyids.
                         if valid_keyids.insert(&signature.keyid) {
                             valid += 1;
@@ -37,15 +109,122 @@ impl Root {
             }
         }
 
-        ensure!(
-            valid >= u64::from(role_keys.threshold),
-            error::SignatureThreshold {
-                role: T::TYPE,
-                threshold: role_keys.threshold,
-                valid,
+        let required = u64::from(role_keys.threshold);
+        Ok(ThresholdStatus {
+            required,
+            valid,
+            satisfied: valid >= required,
+            surplus: valid.saturating_sub(required),
+        })
+    }
+
+    /// Checks the given metadata role against each of `roots` in order of preference, such as the
+    /// old and new roots during a key migration window. Returns the index of the first root that
+    /// the role is valid for, or [`Error::NoTrustedRoot`](super::Error::NoTrustedRoot) if it isn't
+    /// valid for any of them.
+    pub fn verify_role_with_any<'a, T, I>(roots: I, role: &Signed<T>) -> Result<usize>
+    where
+        T: Role + Serialize,
+        I: IntoIterator<Item = &'a Root>,
+    {
+        let mut count: usize = 0;
+        for (index, root) in roots.into_iter().enumerate() {
+            if root.verify_role(role).is_ok() {
+                return Ok(index);
             }
-        );
-        Ok(())
+            count += 1;
+        }
+        error::NoTrustedRoot {
+            role: T::TYPE,
+            roots: count,
+        }
+        .fail()
+    }
+
+    /// Checks every signature on the given metadata role, without regard to the role's threshold.
+    /// Returns one [`SignatureStatus`] per signature, in the order they appear in the role.
+    pub fn signature_statuses<T: Role + Serialize>(
+        &self,
+        role: &Signed<T>,
+    ) -> Result<Vec<SignatureStatus>> {
+        let role_keyids = self
+            .roles
+            .get(&T::TYPE)
+            .context(error::MissingRole { role: T::TYPE })?
+            .keyids
+            .as_slice();
+        let data = CanonicalForms::new(&role.signed, format!("{} role", T::TYPE))?;
+
+        Ok(role
+            .signatures
+            .iter()
+            .map(|signature| SignatureStatus {
+                keyid: signature.keyid.clone(),
+                authorized: role_keyids.contains(&signature.keyid),
+                valid: match self.keys.get(&signature.keyid) {
+                    Some(key) => data.verify(key, &signature.sig),
+                    None => false,
+                },
+            })
+            .collect())
+    }
+
+    /// Returns the IDs of every key listed in this root that `signature` is authentic for, whatever
+    /// key ID it claims and whichever roles the keys may sign for. See [`Key::verify_signature`].
+    pub fn authentic_signers<T: Role + Serialize>(
+        &self,
+        role: &T,
+        signature: &Signature,
+    ) -> Result<Vec<&Decoded<Hex>>> {
+        let data = CanonicalForms::new(role, format!("{} role", T::TYPE))?;
+        Ok(self
+            .keys
+            .iter()
+            .filter(|(_, key)| data.verify(key, &signature.sig))
+            .map(|(key_id, _)| key_id)
+            .collect())
+    }
+
+    /// Reports which authorized keys have yet to sign the given metadata role, and how many of
+    /// them must sign for the role to meet its threshold.
+    pub fn missing_signatures<T: Role + Serialize>(
+        &self,
+        role: &Signed<T>,
+    ) -> Result<MissingSignatures> {
+        let role_keys = self
+            .roles
+            .get(&T::TYPE)
+            .context(error::MissingRole { role: T::TYPE })?;
+        let signed = self
+            .signature_statuses(role)?
+            .into_iter()
+            .filter(|status| status.authorized && status.valid)
+            .map(|status| status.keyid)
+            .collect::<HashSet<_>>();
+
+        Ok(MissingSignatures {
+            needed: u64::from(role_keys.threshold).saturating_sub(signed.len() as u64),
+            unsigned_keyids: role_keys
+                .keyids
+                .iter()
+                .filter(|keyid| !signed.contains(*keyid))
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+impl Key {
+    /// Checks only whether `signature` is authentic: that this key made it over the canonical form
+    /// of `role`. The key ID the signature claims, whether this key may sign for the role, and the
+    /// role's threshold are all ignored; [`Root::verify_role`] checks those.
+    pub fn verify_signature<T: Role + Serialize>(
+        &self,
+        role: &T,
+        signature: &Signature,
+    ) -> Result<bool> {
+        let data = CanonicalForms::new(role, format!("{} role", T::TYPE))?;
+        Ok(data.verify(self, &signature.sig))
     }
 }
 
@@ -62,17 +241,11 @@ impl Delegations {
         let mut valid = 0;
 
         // serialize the role to verify the key ID by using the JSON representation
-        let mut data = Vec::new();
-        let mut ser = serde_json::Serializer::with_formatter(&mut data, CanonicalFormatter::new());
-        role.signed
-            .serialize(&mut ser)
-            .context(error::JsonSerialization {
-                what: format!("{} role", name.to_string()),
-            })?;
+        let data = CanonicalForms::new(&role.signed, format!("{} role", name.to_string()))?;
         for signature in &role.signatures {
             if role_keys.keyids.contains(&signature.keyid) {
                 if let Some(key) = self.keys.get(&signature.keyid) {
-                    if key.verify(&data, &signature.sig) {
+                    if data.verify(key, &signature.sig) {
                         valid += 1;
                     }
                 }
@@ -85,6 +258,7 @@ impl Delegations {
                 role: RoleType::Targets,
                 threshold: role_keys.threshold,
                 valid,
+                authorized: role_keys.keyids.iter().collect::<HashSet<_>>().len() as u64,
             }
         );
         Ok(())
@@ -93,7 +267,9 @@ impl Delegations {
 
 #[cfg(test)]
 mod tests {
-    use super::{Root, Signed};
+    use super::{RoleType, Root, Signed};
+    use crate::schema::decoded::{Decoded, Hex};
+    use crate::schema::Signature;
 
     #[test]
     fn simple_rsa() {
@@ -102,6 +278,466 @@ mod tests {
         root.signed.verify_role(&root).unwrap();
     }
 
+    #[test]
+    fn same_public_material() {
+        use crate::schema::key::{Key, RsaScheme};
+
+        let root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/rsa-pss-sha512/root.json"))
+                .unwrap();
+        let key = root.signed.keys.values().next().unwrap();
+        assert!(matches!(
+            key,
+            Key::Rsa {
+                scheme: RsaScheme::RsassaPssSha512,
+                ..
+            }
+        ));
+
+        // The same key bytes under another scheme are a different key, with a different ID
+        let mut other_scheme = key.clone();
+        if let Key::Rsa { scheme, .. } = &mut other_scheme {
+            *scheme = RsaScheme::RsassaPssSha256;
+        }
+        assert_ne!(key, &other_scheme);
+        assert_ne!(key.key_id().unwrap(), other_scheme.key_id().unwrap());
+        assert!(key.same_public_material(&other_scheme));
+        assert!(other_scheme.same_public_material(key));
+
+        // Neither another RSA key nor a key of another type matches
+        let other: Signed<Root> = serde_json::from_str(include_str!(
+            "../../tests/data/tuf-reference-impl/metadata/1.root.json"
+        ))
+        .unwrap();
+        assert!(other
+            .signed
+            .keys
+            .values()
+            .any(|k| matches!(k, Key::Rsa { .. })));
+        assert!(other
+            .signed
+            .keys
+            .values()
+            .any(|k| matches!(k, Key::Ed25519 { .. })));
+        for other_key in other.signed.keys.values() {
+            assert!(!key.same_public_material(other_key));
+        }
+    }
+
+    #[test]
+    fn rsa_pss_sha512() {
+        use crate::editor::signed::SignedRole;
+        use crate::key_source::{KeySource, LocalKeySource};
+        use crate::schema::KeyHolder;
+        use ring::rand::SystemRandom;
+
+        let root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/rsa-pss-sha512/root.json"))
+                .unwrap();
+        root.signed.verify_role(&root).unwrap();
+
+        // Signing with a key listed under `rsassa-pss-sha512` uses that scheme
+        let keys: Vec<Box<dyn KeySource>> = vec![Box::new(LocalKeySource {
+            path: std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("data")
+                .join("snakeoil.pem"),
+        })];
+        let mut signed = root.signed.clone();
+        signed.version = std::num::NonZeroU64::new(2).unwrap();
+        let signed = SignedRole::new(
+            signed,
+            &KeyHolder::Root(root.signed.clone()),
+            &keys,
+            &SystemRandom::new(),
+        )
+        .unwrap();
+        root.signed.verify_role(signed.signed()).unwrap();
+    }
+
+    #[test]
+    fn fractional_expires() {
+        use crate::editor::signed::SignedRole;
+        use crate::key_source::{KeySource, LocalKeySource};
+        use crate::schema::{KeyHolder, Timestamp};
+        use chrono::{DateTime, Timelike, Utc};
+        use ring::rand::SystemRandom;
+
+        let root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        let keys: Vec<Box<dyn KeySource>> = vec![Box::new(LocalKeySource {
+            path: std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("data")
+                .join("snakeoil.pem"),
+        })];
+        let expires = "2030-01-01T00:00:00.123456789Z"
+            .parse::<DateTime<Utc>>()
+            .unwrap();
+        let signed = SignedRole::new(
+            Timestamp::new(
+                "1.0.0".to_owned(),
+                std::num::NonZeroU64::new(1).unwrap(),
+                expires,
+            ),
+            &KeyHolder::Root(root.signed.clone()),
+            &keys,
+            &SystemRandom::new(),
+        )
+        .unwrap();
+
+        // Subsecond `expires` written by another tool are kept as received, so that the
+        // signatures over them verify
+        let loaded: Signed<Timestamp> = serde_json::from_slice(signed.buffer()).unwrap();
+        assert_eq!(loaded.signed.expires, expires);
+        assert_eq!(loaded.signed.expires.nanosecond(), 123_456_789);
+        root.signed.verify_role(&loaded).unwrap();
+    }
+
+    #[test]
+    fn missing_signatures() {
+        let root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        let signer = root.signatures[0].keyid.clone();
+
+        // A 3-of-5 root role, of which only `signer` has signed
+        let others: Vec<Decoded<Hex>> = (1..5).map(|i| vec![i; 32].into()).collect();
+        let mut trusted = root.signed.clone();
+        let role_keys = trusted.roles.get_mut(&RoleType::Root).unwrap();
+        role_keys.keyids.extend(others.iter().cloned());
+        role_keys.threshold = std::num::NonZeroU64::new(3).unwrap();
+
+        let missing = trusted.missing_signatures(&root).unwrap();
+        assert_eq!(missing.needed, 2);
+        assert_eq!(missing.unsigned_keyids, others);
+
+        // An invalid signature doesn't count towards the threshold
+        let mut bad = root.clone();
+        bad.signatures[0].sig = vec![0; 4].into();
+        let missing = trusted.missing_signatures(&bad).unwrap();
+        assert_eq!(missing.needed, 3);
+        assert!(missing.unsigned_keyids.contains(&signer));
+
+        // Once the threshold is met, no more signatures are needed
+        let missing = root.signed.missing_signatures(&root).unwrap();
+        assert_eq!(missing.needed, 0);
+        assert!(missing.unsigned_keyids.is_empty());
+    }
+
+    #[test]
+    fn update_signed_clears_signatures() {
+        let mut root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        assert!(!root.signatures.is_empty());
+        let trusted = root.signed.clone();
+        root.update_signed(|signed| {
+            signed.version = std::num::NonZeroU64::new(signed.version.get() + 1).unwrap();
+        });
+        assert!(root.signatures.is_empty());
+        trusted
+            .verify_role(&root)
+            .expect_err("edited root should not verify");
+    }
+
+    #[test]
+    fn verify_role_with_any() {
+        use crate::editor::signed::SignedRole;
+        use crate::key_source::{KeySource, LocalKeySource};
+        use crate::schema::KeyHolder;
+        use ring::rand::SystemRandom;
+
+        let data = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("data");
+        let old: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        let sign = |root: &Root, key: &str| {
+            let keys: Vec<Box<dyn KeySource>> = vec![Box::new(LocalKeySource {
+                path: data.join(key),
+            })];
+            let mut signed = old.signed.clone();
+            signed.version = std::num::NonZeroU64::new(2).unwrap();
+            SignedRole::new(
+                signed,
+                &KeyHolder::Root(root.clone()),
+                &keys,
+                &SystemRandom::new(),
+            )
+            .unwrap()
+            .signed()
+            .clone()
+        };
+
+        // A new root that trusts only `snakeoil_2.pem` for every role
+        let new_key = LocalKeySource {
+            path: data.join("snakeoil_2.pem"),
+        }
+        .as_sign()
+        .unwrap()
+        .tuf_key();
+        let new_keyid = new_key.key_id().unwrap();
+        let mut new = old.signed.clone();
+        new.keys.clear();
+        new.keys.insert(new_keyid.clone(), new_key);
+        for role_keys in new.roles.values_mut() {
+            role_keys.keyids = vec![new_keyid.clone()];
+        }
+        let roots = [&old.signed, &new];
+
+        let signed_by_old = sign(&old.signed, "snakeoil.pem");
+        assert_eq!(
+            Root::verify_role_with_any(roots, &signed_by_old).unwrap(),
+            0
+        );
+        assert_eq!(
+            Root::verify_role_with_any([&new, &old.signed], &signed_by_old).unwrap(),
+            1
+        );
+        let signed_by_new = sign(&new, "snakeoil_2.pem");
+        assert_eq!(
+            Root::verify_role_with_any(roots, &signed_by_new).unwrap(),
+            1
+        );
+
+        // Signed by neither root's keys
+        let mut signed_by_neither = signed_by_new;
+        signed_by_neither.signatures.clear();
+        let err = Root::verify_role_with_any(roots, &signed_by_neither).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::schema::Error::NoTrustedRoot {
+                role: RoleType::Root,
+                roots: 2,
+                ..
+            }
+        ));
+    }
+
+    /// Returns simple-rsa's root.json with a root role that lists each of the named keys in
+    /// tests/data, signed by all of them.
+    fn signed_by_all(names: &[&str]) -> (Root, Signed<Root>) {
+        use crate::editor::signed::SignedRole;
+        use crate::key_source::{KeySource, LocalKeySource};
+        use crate::schema::KeyHolder;
+        use ring::rand::SystemRandom;
+
+        let root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        let keys: Vec<Box<dyn KeySource>> = names
+            .iter()
+            .map(|name| {
+                Box::new(LocalKeySource {
+                    path: std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                        .join("tests")
+                        .join("data")
+                        .join(name),
+                }) as Box<dyn KeySource>
+            })
+            .collect();
+
+        // Only the named keys are listed, so that each signs under the key ID given here
+        let mut trusted = root.signed.clone();
+        trusted.keys.clear();
+        let role_keys = trusted.roles.get_mut(&RoleType::Root).unwrap();
+        role_keys.keyids.clear();
+        for key in &keys {
+            let key = key.as_sign().unwrap().tuf_key();
+            let keyid = key.key_id().unwrap();
+            role_keys.keyids.push(keyid.clone());
+            trusted.keys.insert(keyid, key);
+        }
+        let signed = SignedRole::new(
+            root.signed,
+            &KeyHolder::Root(trusted.clone()),
+            &keys,
+            &SystemRandom::new(),
+        )
+        .unwrap()
+        .signed()
+        .clone();
+        (trusted, signed)
+    }
+
+    fn set_threshold(root: &mut Root, threshold: u64) {
+        root.roles.get_mut(&RoleType::Root).unwrap().threshold =
+            std::num::NonZeroU64::new(threshold).unwrap();
+    }
+
+    #[test]
+    fn verify_status() {
+        use super::ThresholdStatus;
+
+        // A root role with two keys, signed by both of them
+        let (mut trusted, signed) = signed_by_all(&["snakeoil.pem", "snakeoil_2.pem"]);
+        let mut status_at = |threshold| {
+            set_threshold(&mut trusted, threshold);
+            (
+                trusted.verify_status(&signed).unwrap(),
+                trusted.verify_role(&signed).is_ok(),
+            )
+        };
+        // Under, at, and over the threshold
+        assert_eq!(
+            status_at(3),
+            (
+                ThresholdStatus {
+                    required: 3,
+                    valid: 2,
+                    satisfied: false,
+                    surplus: 0,
+                },
+                false
+            )
+        );
+        assert_eq!(
+            status_at(2),
+            (
+                ThresholdStatus {
+                    required: 2,
+                    valid: 2,
+                    satisfied: true,
+                    surplus: 0,
+                },
+                true
+            )
+        );
+        assert_eq!(
+            status_at(1),
+            (
+                ThresholdStatus {
+                    required: 1,
+                    valid: 2,
+                    satisfied: true,
+                    surplus: 1,
+                },
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn unreachable_threshold_message() {
+        // A root role with two keys, signed by only one of them
+        let (trusted, _) = signed_by_all(&["snakeoil.pem", "snakeoil_2.pem"]);
+        let (_, signed) = signed_by_all(&["snakeoil.pem"]);
+        let message_at = |threshold| {
+            let mut trusted = trusted.clone();
+            set_threshold(&mut trusted, threshold);
+            trusted.verify_role(&signed).unwrap_err().to_string()
+        };
+
+        // A threshold the authorized keys could meet isn't remarked on
+        assert_eq!(
+            message_at(2),
+            "Signature threshold of 2 not met for role root (1 valid signatures)"
+        );
+        // A placeholder threshold is
+        assert_eq!(
+            message_at(1507),
+            "Signature threshold of 1507 not met for role root (1 valid signatures); \
+             threshold 1507 exceeds 2 authorized keys"
+        );
+    }
+
+    #[test]
+    fn surplus_signatures() {
+        // A 2-of-3 root role signed by all three keys
+        let (mut trusted, signed) =
+            signed_by_all(&["snakeoil.pem", "snakeoil_2.pem", "targetskey"]);
+        set_threshold(&mut trusted, 2);
+        let status = trusted.verify_status(&signed).unwrap();
+        assert_eq!(status.valid, 3);
+        assert!(status.satisfied);
+        assert_eq!(status.surplus, 1);
+
+        // An invalid extra signature isn't surplus
+        let mut tampered = signed;
+        tampered.signatures[2].sig = vec![0; 4].into();
+        let status = trusted.verify_status(&tampered).unwrap();
+        assert_eq!(status.valid, 2);
+        assert_eq!(status.surplus, 0);
+    }
+
+    #[test]
+    fn dedup_signatures() {
+        let mut root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        let original = root.clone();
+        let duplicate = root.signatures[0].clone();
+        root.signatures.push(duplicate.clone());
+        root.signatures.push(duplicate);
+
+        assert_eq!(root.dedup_signatures(), 2);
+        assert_eq!(root, original);
+        root.signed.verify_role(&root).unwrap();
+        assert_eq!(root.dedup_signatures(), 0);
+    }
+
+    #[test]
+    fn signature_statuses() {
+        let root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        let keyid = root.signatures[0].keyid.clone();
+
+        let mut role = root.clone();
+        // An authorized signature that doesn't match the content
+        let mut bad_sig = role.signatures[0].sig.to_vec();
+        bad_sig[0] ^= 0xff;
+        role.signatures.push(Signature {
+            keyid: keyid.clone(),
+            sig: bad_sig.into(),
+            created: None,
+        });
+        // A signature by a key that root.json doesn't know about
+        let unknown: Decoded<Hex> = vec![0xab; 32].into();
+        role.signatures.push(Signature {
+            keyid: unknown.clone(),
+            sig: role.signatures[0].sig.clone(),
+            created: None,
+        });
+
+        let statuses = root.signed.signature_statuses(&role).unwrap();
+        let statuses: Vec<_> = statuses
+            .iter()
+            .map(|status| (&status.keyid, status.authorized, status.valid))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (&keyid, true, true),
+                (&keyid, true, false),
+                (&unknown, false, false),
+            ]
+        );
+
+        // A valid signature by a key that is no longer authorized for the role
+        let mut trusted = root.signed.clone();
+        trusted
+            .roles
+            .get_mut(&RoleType::Root)
+            .unwrap()
+            .keyids
+            .clear();
+        let statuses = trusted.signature_statuses(&root).unwrap();
+        assert!(!statuses[0].authorized);
+        assert!(statuses[0].valid);
+    }
+
+    #[test]
+    fn signing_time_round_trips_and_is_not_verified() {
+        let mut root: Signed<Root> =
+            serde_json::from_str(include_str!("../../tests/data/simple-rsa/root.json")).unwrap();
+        assert!(root.signatures[0].created.is_none());
+        let created = "2021-02-03T04:05:06Z".parse().unwrap();
+        root.signatures[0].created = Some(created);
+
+        let json = serde_json::to_string(&root).unwrap();
+        assert!(json.contains(r#""created":"2021-02-03T04:05:06Z""#));
+        let root: Signed<Root> = serde_json::from_str(&json).unwrap();
+        assert_eq!(root.signatures[0].created, Some(created));
+        root.signed.verify_role(&root).unwrap();
+    }
+
     #[test]
     fn no_root_json_signatures_is_err() {
         let root: Signed<Root> = serde_json::from_str(include_str!(
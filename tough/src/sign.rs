@@ -5,6 +5,7 @@
 
 use crate::error::{self, Result};
 use crate::schema::key::Key;
+use crate::schema::spki::ec_private_key_to_pkcs8;
 use crate::sign::SignKeyPair::ECDSA;
 use crate::sign::SignKeyPair::ED25519;
 use crate::sign::SignKeyPair::RSA;
@@ -25,6 +26,24 @@ pub trait Sign: Sync + Send {
         msg: &[u8],
         rng: &dyn SecureRandom,
     ) -> std::result::Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+    /// Returns whether `key`, as listed in role metadata, is the public key of this key pair.
+    /// Key pairs that can sign under more than one scheme accept `key` listed under any of them.
+    fn is_listed_as(&self, key: &Key) -> bool {
+        self.tuf_key() == *key
+    }
+
+    /// Signs the supplied message under the scheme of `key`, the key this key pair is listed as in
+    /// role metadata. By default the scheme reported by `tuf_key` is used.
+    fn sign_as(
+        &self,
+        key: &Key,
+        msg: &[u8],
+        rng: &dyn SecureRandom,
+    ) -> std::result::Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let _ = key;
+        self.sign(msg, rng)
+    }
 }
 
 /// Implements the Sign trait for ED25519
@@ -77,6 +96,34 @@ impl Sign for RsaKeyPair {
             .context(error::Sign)?;
         Ok(signature)
     }
+
+    fn is_listed_as(&self, key: &Key) -> bool {
+        match key {
+            Key::Rsa { keyval, .. } => *keyval.public == *self.public_key().as_ref(),
+            _ => false,
+        }
+    }
+
+    fn sign_as(
+        &self,
+        key: &Key,
+        msg: &[u8],
+        rng: &dyn SecureRandom,
+    ) -> std::result::Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        use crate::schema::key::RsaScheme;
+
+        let padding = match key {
+            Key::Rsa {
+                scheme: RsaScheme::RsassaPssSha512,
+                ..
+            } => &ring::signature::RSA_PSS_SHA512,
+            _ => &ring::signature::RSA_PSS_SHA256,
+        };
+        let mut signature = vec![0; self.public_modulus_len()];
+        self.sign(padding, rng, msg, &mut signature)
+            .context(error::Sign)?;
+        Ok(signature)
+    }
 }
 
 /// Implements the Sign trait for ECDSA keypairs
@@ -135,11 +182,36 @@ impl Sign for SignKeyPair {
             ECDSA(key) => (key as &dyn Sign).sign(msg, rng),
         }
     }
+
+    fn is_listed_as(&self, key: &Key) -> bool {
+        match self {
+            RSA(key_pair) => key_pair.is_listed_as(key),
+            ED25519(key_pair) => key_pair.is_listed_as(key),
+            ECDSA(key_pair) => key_pair.is_listed_as(key),
+        }
+    }
+
+    fn sign_as(
+        &self,
+        key: &Key,
+        msg: &[u8],
+        rng: &dyn SecureRandom,
+    ) -> std::result::Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        match self {
+            RSA(key_pair) => key_pair.sign_as(key, msg, rng),
+            ED25519(key_pair) => key_pair.sign_as(key, msg, rng),
+            ECDSA(key_pair) => key_pair.sign_as(key, msg, rng),
+        }
+    }
 }
 
 /// Parses a supplied keypair and if it is recognized, returns an object that
 /// implements the Sign trait
-/// Accepted Keys: ED25519 pkcs8, Ecdsa pkcs8, RSA
+/// Accepted Keys: ED25519 pkcs8 (DER or PEM), ED25519 raw hex, Ecdsa pkcs8 (DER or PEM), Ecdsa
+/// SEC1 PEM, RSA
+///
+/// A raw hex ED25519 key is the 32-byte seed, as python-tuf stores it, optionally followed by the
+/// 32-byte public key.
 pub fn parse_keypair(key: &[u8]) -> Result<impl Sign> {
     if let Ok(ed25519_key_pair) = Ed25519KeyPair::from_pkcs8(key) {
         Ok(SignKeyPair::ED25519(ed25519_key_pair))
@@ -152,15 +224,45 @@ pub fn parse_keypair(key: &[u8]) -> Result<impl Sign> {
             "PRIVATE KEY" => {
                 if let Ok(rsa_key_pair) = RsaKeyPair::from_pkcs8(&pem.contents) {
                     Ok(SignKeyPair::RSA(rsa_key_pair))
+                } else if let Ok(ed25519_key_pair) =
+                    Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pem.contents)
+                {
+                    Ok(SignKeyPair::ED25519(ed25519_key_pair))
+                } else if let Ok(ecdsa_key_pair) = EcdsaKeyPair::from_pkcs8(
+                    &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+                    &pem.contents,
+                ) {
+                    Ok(SignKeyPair::ECDSA(ecdsa_key_pair))
                 } else {
                     error::KeyUnrecognized.fail()
                 }
             }
+            "EC PRIVATE KEY" => Ok(SignKeyPair::ECDSA(
+                EcdsaKeyPair::from_pkcs8(
+                    &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+                    &ec_private_key_to_pkcs8(&pem.contents),
+                )
+                .context(error::KeyRejected)?,
+            )),
             "RSA PRIVATE KEY" => Ok(SignKeyPair::RSA(
                 RsaKeyPair::from_der(&pem.contents).context(error::KeyRejected)?,
             )),
             _ => error::KeyUnrecognized.fail(),
         }
+    } else if let Some(seed) = std::str::from_utf8(key)
+        .ok()
+        .and_then(|hex| hex::decode(hex.trim()).ok())
+    {
+        match seed.len() {
+            32 => Ok(SignKeyPair::ED25519(
+                Ed25519KeyPair::from_seed_unchecked(&seed).context(error::KeyRejected)?,
+            )),
+            64 => Ok(SignKeyPair::ED25519(
+                Ed25519KeyPair::from_seed_and_public_key(&seed[..32], &seed[32..])
+                    .context(error::KeyRejected)?,
+            )),
+            _ => error::KeyUnrecognized.fail(),
+        }
     } else {
         error::KeyUnrecognized.fail()
     }
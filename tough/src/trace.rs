@@ -0,0 +1,96 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides a trace of the files a repository fetches and the signatures it checks, to help tell
+//! transport problems (the wrong bytes were fetched) from cryptographic ones (the expected bytes
+//! were fetched, but aren't properly signed).
+//!
+//! The trace is logged at the `debug` level to the `tough::trace` target, so it's enabled by
+//! configuring the logger, such as with `RUST_LOG=tough::trace=debug` for `env_logger`. When the
+//! target is disabled, nothing extra is computed.
+
+use ring::digest::{Context, SHA256};
+use std::fmt::Display;
+use std::io::{self, Read};
+use url::Url;
+
+const TARGET: &str = "tough::trace";
+
+fn enabled() -> bool {
+    log::log_enabled!(target: TARGET, log::Level::Debug)
+}
+
+/// Wraps `reader` so that, once it has been read to the end, the length and sha256 digest of the
+/// bytes read from `url` are logged alongside the length and digest that were expected.
+pub(crate) fn fetched(
+    reader: Box<dyn Read + Send>,
+    url: &Url,
+    max_size: u64,
+    specifier: &'static str,
+    sha256: Option<&[u8]>,
+) -> Box<dyn Read + Send> {
+    if !enabled() {
+        return reader;
+    }
+    Box::new(TraceAdapter {
+        reader,
+        url: url.clone(),
+        max_size,
+        specifier,
+        expected: sha256.map(<[u8]>::to_vec),
+        length: 0,
+        digest: Some(Context::new(&SHA256)),
+    })
+}
+
+/// Logs whether the signatures on `file` verified, and passes `result` on.
+pub(crate) fn verified<T, E: Display>(file: &str, result: Result<T, E>) -> Result<T, E> {
+    match &result {
+        Ok(_) => log::debug!(target: TARGET, "signatures on {}: verified", file),
+        Err(err) => log::debug!(target: TARGET, "signatures on {}: FAILED ({})", file, err),
+    }
+    result
+}
+
+struct TraceAdapter {
+    reader: Box<dyn Read + Send>,
+    url: Url,
+    max_size: u64,
+    specifier: &'static str,
+    expected: Option<Vec<u8>>,
+    length: u64,
+    digest: Option<Context>,
+}
+
+impl Read for TraceAdapter {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.reader.read(buf)?;
+        if let Some(digest) = &mut self.digest {
+            if size != 0 {
+                digest.update(&buf[..size]);
+                self.length += size as u64;
+            } else if !buf.is_empty() {
+                let calculated = self.digest.take().unwrap().finish();
+                let calculated = calculated.as_ref();
+                let expected = match &self.expected {
+                    Some(expected) if expected.as_slice() == calculated => {
+                        format!("{} (match)", hex::encode(expected))
+                    }
+                    Some(expected) => format!("{} (MISMATCH)", hex::encode(expected)),
+                    None => "unknown".to_owned(),
+                };
+                log::debug!(
+                    target: TARGET,
+                    "fetched {}: {} bytes with sha256 {}; expected sha256 {}, at most {} bytes ({})",
+                    self.url,
+                    self.length,
+                    hex::encode(calculated),
+                    expected,
+                    self.max_size,
+                    self.specifier
+                );
+            }
+        }
+        Ok(size)
+    }
+}
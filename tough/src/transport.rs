@@ -12,8 +12,9 @@ use url::Url;
 /// `Box<dyn Read + Send>` and by requiring concrete type [`TransportError`] as the error type.
 ///
 /// Inclusion of the `DynClone` trait means that you will need to implement `Clone` when
-/// implementing a `Transport`.
-pub trait Transport: Debug + DynClone {
+/// implementing a `Transport`. A `Transport` must also be `Send` and `Sync`, so that a
+/// [`Repository`](crate::Repository) can be shared between threads.
+pub trait Transport: Debug + DynClone + Send + Sync {
     /// Opens a `Read` object for the file specified by `url`.
     fn fetch(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError>;
 }
@@ -173,11 +174,13 @@ impl Transport for FilesystemTransport {
 
 /// A Transport that provides support for both local files and, if the `http` feature is enabled,
 /// HTTP-transported files.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct DefaultTransport {
     file: FilesystemTransport,
     #[cfg(feature = "http")]
     http: HttpTransport,
+    #[cfg(not(feature = "http"))]
+    http: NoHttpTransport,
 }
 
 impl Default for DefaultTransport {
@@ -186,6 +189,8 @@ impl Default for DefaultTransport {
             file: FilesystemTransport,
             #[cfg(feature = "http")]
             http: HttpTransport::default(),
+            #[cfg(not(feature = "http"))]
+            http: NoHttpTransport,
         }
     }
 }
@@ -212,7 +217,7 @@ impl Transport for DefaultTransport {
     fn fetch(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError> {
         match url.scheme() {
             "file" => self.file.fetch(url),
-            "http" | "https" => self.handle_http(url),
+            "http" | "https" => self.http.fetch(url),
             _ => Err(TransportError::new(
                 TransportErrorKind::UnsupportedUrlScheme,
                 url,
@@ -221,19 +226,18 @@ impl Transport for DefaultTransport {
     }
 }
 
-impl DefaultTransport {
-    #[cfg(not(feature = "http"))]
-    #[allow(clippy::trivially_copy_pass_by_ref, clippy::unused_self)]
-    fn handle_http(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError> {
+/// Stands in for the `HttpTransport` of a `DefaultTransport` when the `http` feature is disabled.
+#[cfg(not(feature = "http"))]
+#[derive(Debug, Clone, Default)]
+struct NoHttpTransport;
+
+#[cfg(not(feature = "http"))]
+impl Transport for NoHttpTransport {
+    fn fetch(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError> {
         Err(TransportError::new_with_cause(
             TransportErrorKind::UnsupportedUrlScheme,
             url,
             "The library was not compiled with the http feature enabled.",
         ))
     }
-
-    #[cfg(feature = "http")]
-    fn handle_http(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError> {
-        self.http.fetch(url)
-    }
 }
@@ -0,0 +1,107 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::HashMap;
+use std::fs::File;
+use test_utils::{dir_url, test_data};
+use tough::schema::{PathSet, Target, Targets};
+use tough::{Repository, RepositoryLoader};
+
+mod test_utils;
+
+fn load_tuf_reference_impl() -> Repository {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+    .load()
+    .unwrap()
+}
+
+/// Returns the target `all_targets` yields for each name, checking that no name is yielded twice.
+fn all_targets(targets: &Targets) -> HashMap<String, Target> {
+    let mut all = HashMap::new();
+    for (name, target) in targets.all_targets() {
+        assert!(
+            all.insert(name.clone(), target.clone()).is_none(),
+            "{} yielded more than once",
+            name
+        );
+    }
+    all
+}
+
+/// Returns a copy of `target` with a different length, so that it can be told apart.
+fn shadow(target: &Target, length: u64) -> Target {
+    let mut target = target.clone();
+    target.length = length;
+    target
+}
+
+/// Test that every target, including delegated targets, is yielded.
+#[test]
+fn all_targets_includes_delegated() {
+    let repo = load_tuf_reference_impl();
+    let mut names = repo
+        .all_targets()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["file1.txt", "file2.txt", "file3.txt"]);
+}
+
+/// Test that a target listed by more than one role is yielded once, from the role a client would
+/// consult first.
+#[test]
+fn all_targets_priority() {
+    let repo = load_tuf_reference_impl();
+    let mut targets = repo.targets().signed.clone();
+    let delegations = targets.delegations.as_mut().unwrap();
+    let file3 = delegations.roles[0]
+        .targets
+        .as_ref()
+        .unwrap()
+        .signed
+        .targets["file3.txt"]
+        .clone();
+
+    // A later sibling of `role1` lists a different `file3.txt`, which `role1` shadows
+    let mut sibling = delegations.roles[0].clone();
+    sibling.name = "sibling".to_owned();
+    sibling.paths = PathSet::Paths(vec!["*".to_owned()]);
+    let sibling_targets = &mut sibling.targets.as_mut().unwrap().signed;
+    sibling_targets.delegations = None;
+    sibling_targets
+        .targets
+        .insert("file3.txt".to_owned(), shadow(&file3, 1));
+    sibling_targets
+        .targets
+        .insert("file4.txt".to_owned(), shadow(&file3, 2));
+    sibling_targets
+        .targets
+        .insert("file5.txt".to_owned(), shadow(&file3, 3));
+    delegations.roles.push(sibling);
+
+    let all = all_targets(&targets);
+    assert_eq!(all["file3.txt"], file3);
+    assert_eq!(all["file4.txt"].length, 2);
+    assert_eq!(all["file5.txt"].length, 3);
+
+    // A target listed by the top-level role takes priority over every delegated role
+    targets
+        .targets
+        .insert("file5.txt".to_owned(), shadow(&file3, 5));
+    assert_eq!(all_targets(&targets)["file5.txt"].length, 5);
+
+    // Once `role1` is terminating, later roles aren't consulted for the paths it was delegated
+    let delegations = targets.delegations.as_mut().unwrap();
+    delegations.roles[0].paths =
+        PathSet::Paths(vec!["file3.txt".to_owned(), "file4.txt".to_owned()]);
+    delegations.roles[0].terminating = true;
+    let all = all_targets(&targets);
+    assert_eq!(all["file3.txt"], file3);
+    assert!(!all.contains_key("file4.txt"));
+    assert_eq!(all.len(), 4);
+}
@@ -0,0 +1,83 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(feature = "async")]
+
+use futures_util::StreamExt;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use test_utils::{dir_url, test_data};
+use tough::asynchronous::TargetStream;
+use tough::{Repository, RepositoryLoader};
+
+mod test_utils;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+async fn load(targets_dir: &Path) -> Repository {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(targets_dir),
+    )
+    .load_async()
+    .await
+    .unwrap()
+}
+
+/// Collects the chunks of `stream`, stopping at the first error.
+async fn collect(mut stream: TargetStream) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        buf.extend_from_slice(&chunk?);
+    }
+    Ok(buf)
+}
+
+/// Test that a repository can be loaded and its targets read without blocking the runtime.
+#[test]
+fn read_target_stream() {
+    runtime().block_on(async {
+        let targets_dir = test_data().join("tuf-reference-impl").join("targets");
+        let repo = Arc::new(load(&targets_dir).await);
+
+        let stream = Arc::clone(&repo)
+            .read_target_stream("file1.txt")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            collect(stream).await.unwrap(),
+            std::fs::read(targets_dir.join("file1.txt")).unwrap()
+        );
+        assert!(repo
+            .read_target_stream("no-such-target")
+            .await
+            .unwrap()
+            .is_none());
+    });
+}
+
+/// Test that a target that doesn't match its metadata ends the stream with an error.
+#[test]
+fn read_target_stream_tampered() {
+    runtime().block_on(async {
+        let targets_dir = TempDir::new().unwrap();
+        std::fs::write(
+            targets_dir.path().join("file1.txt"),
+            "This is not the file you want.\n",
+        )
+        .unwrap();
+        let repo = Arc::new(load(targets_dir.path()).await);
+
+        let stream = repo.read_target_stream("file1.txt").await.unwrap().unwrap();
+        assert!(collect(stream).await.is_err());
+    });
+}
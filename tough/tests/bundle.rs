@@ -0,0 +1,71 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use test_utils::{dir_url, test_data};
+use tough::bundle::MetadataBundle;
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+/// Builds a bundle by embedding each metadata file from the reference implementation repository
+/// verbatim. `snapshot_suffix` is appended to the snapshot member to simulate tampering.
+fn reference_impl_bundle(snapshot_suffix: &str) -> String {
+    let metadata = test_data().join("tuf-reference-impl").join("metadata");
+    let read = |name: &str| std::fs::read_to_string(metadata.join(name)).unwrap();
+    format!(
+        r#"{{"root":{},"timestamp":{},"snapshot":{}{},"targets":{},"role1":{},"role2":{}}}"#,
+        read("root.json"),
+        read("timestamp.json"),
+        read("snapshot.json"),
+        snapshot_suffix,
+        read("targets.json"),
+        read("role1.json"),
+        read("role2.json"),
+    )
+}
+
+/// Test that a repository can be loaded and verified entirely from a metadata bundle.
+#[test]
+fn load_bundle() {
+    let base = test_data().join("tuf-reference-impl");
+    let bundle = MetadataBundle::from_string(reference_impl_bundle("")).unwrap();
+
+    let repo = RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+    .transport(bundle)
+    .load()
+    .unwrap();
+
+    assert!(repo.targets().signed.targets.contains_key("file1.txt"));
+    assert!(repo.targets().signed.targets.contains_key("file2.txt"));
+    assert!(repo
+        .delegated_role("role1")
+        .unwrap()
+        .targets
+        .as_ref()
+        .unwrap()
+        .signed
+        .targets
+        .contains_key("file3.txt"));
+}
+
+/// Test that the bundle's members are hashed as embedded, so altering a member's bytes fails
+/// verification.
+#[test]
+fn load_bundle_tampered_snapshot() {
+    let base = test_data().join("tuf-reference-impl");
+    let bundle = MetadataBundle::from_string(reference_impl_bundle(" ")).unwrap();
+
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+    .transport(bundle)
+    .load()
+    .expect_err("tampered snapshot should not verify");
+}
@@ -0,0 +1,85 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use olpc_cjson::CanonicalFormatter;
+use ring::rand::SystemRandom;
+use serde::Serialize;
+use std::fs::File;
+use std::path::PathBuf;
+use test_utils::{root_path, snakeoil_keys, test_data};
+use tough::schema::{Role, RoleType, Root, Signature, Signed, Targets};
+
+mod test_utils;
+
+fn data() -> PathBuf {
+    test_data().join("canonical-interop")
+}
+
+/// Targets metadata whose target names and custom values hold decomposed and precomposed Unicode
+/// characters and ASCII control characters.
+fn targets() -> Targets {
+    serde_json::from_reader(File::open(data().join("targets.json")).unwrap()).unwrap()
+}
+
+/// The canonical form of `targets()` written by securesystemslib's `encode_canonical`.
+fn reference_canonical() -> Vec<u8> {
+    std::fs::read(data().join("targets.canonical")).unwrap()
+}
+
+fn root() -> Signed<Root> {
+    serde_json::from_reader(File::open(root_path()).unwrap()).unwrap()
+}
+
+/// Signs `data`, the canonical form of `targets`, with snakeoil.pem, as the targets role of
+/// `root()`.
+fn sign(targets: Targets, data: &[u8]) -> Signed<Targets> {
+    let sig = snakeoil_keys()[0]
+        .as_sign()
+        .unwrap()
+        .sign(data, &SystemRandom::new())
+        .unwrap();
+    Signed {
+        signed: targets,
+        signatures: vec![Signature {
+            keyid: root().signed.roles[&RoleType::Targets].keyids[0].clone(),
+            sig: sig.into(),
+            created: None,
+        }],
+    }
+}
+
+/// Test that the canonical form tough signs and verifies matches the reference implementation's,
+/// byte for byte, for strings that Unicode normalization or JSON escaping would change.
+#[test]
+fn canonical_form_matches_reference() {
+    let targets = targets();
+    assert_eq!(targets.targets.len(), 2);
+    assert_eq!(targets.canonical_form().unwrap(), reference_canonical());
+}
+
+/// Test that a signature made over the reference implementation's canonical form verifies.
+#[test]
+fn reference_signature_verifies() {
+    root()
+        .signed
+        .verify_role(&sign(targets(), &reference_canonical()))
+        .unwrap();
+}
+
+/// Test that a signature made over the NFC-normalized canonical form, as earlier releases of tough
+/// signed, still verifies.
+#[test]
+fn normalized_signature_verifies() {
+    // Drop the precomposed name, which would collide with the decomposed one once normalized
+    let mut targets = targets();
+    targets.targets.remove("caf\u{e9}.txt").unwrap();
+    let mut normalized = Vec::new();
+    let mut ser =
+        serde_json::Serializer::with_formatter(&mut normalized, CanonicalFormatter::new());
+    targets.serialize(&mut ser).unwrap();
+    assert_ne!(normalized, targets.canonical_form().unwrap());
+    root()
+        .signed
+        .verify_role(&sign(targets, &normalized))
+        .unwrap();
+}
@@ -0,0 +1,123 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(feature = "gzip")]
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{dir_url, read_to_end, root_path, snakeoil_keys, test_data, RepoBuilder};
+use tough::{compressed_metadata_name, RepositoryLoader};
+
+mod test_utils;
+
+fn targets_path() -> PathBuf {
+    test_data().join("tuf-reference-impl").join("targets")
+}
+
+/// Writes metadata, with compressed copies of the targets metadata, for a repository that
+/// delegates `file1.txt` to `role1`.
+fn write_repo(outdir: &Path) {
+    let editor = RepoBuilder::new().delegating_editor();
+    editor
+        .sign(&snakeoil_keys())
+        .unwrap()
+        .write_compressed(outdir)
+        .unwrap();
+}
+
+fn loader(metadata: &Path) -> RepositoryLoader<File> {
+    RepositoryLoader::new(
+        File::open(root_path()).unwrap(),
+        dir_url(metadata),
+        dir_url(targets_path()),
+    )
+    .compressed_metadata(true)
+}
+
+fn gunzip(path: &Path) -> Vec<u8> {
+    read_to_end(flate2::read::GzDecoder::new(File::open(path).unwrap()))
+}
+
+/// Test that the version prefix of a consistent snapshot comes before the compression extension.
+#[test]
+fn compressed_names() {
+    assert_eq!(compressed_metadata_name("targets.json"), "targets.json.gz");
+    assert_eq!(
+        compressed_metadata_name("42.targets.json"),
+        "42.targets.json.gz"
+    );
+    assert_eq!(compressed_metadata_name("7.role1.json"), "7.role1.json.gz");
+}
+
+/// Test that compressed targets metadata written by the editor is found and verified by the
+/// loader, using only the compressed files.
+#[test]
+fn compressed_round_trip() {
+    let metadata = TempDir::new().unwrap();
+    write_repo(metadata.path());
+
+    // The compressed files hold exactly the uncompressed metadata
+    for name in &["1.targets.json", "1.role1.json"] {
+        let path = metadata.path().join(name);
+        assert_eq!(
+            gunzip(&metadata.path().join(compressed_metadata_name(name))),
+            std::fs::read(&path).unwrap()
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+    // Only targets metadata is compressed
+    assert!(!metadata.path().join("1.snapshot.json.gz").exists());
+
+    let repo = loader(metadata.path()).load().unwrap();
+    assert_eq!(repo.targets().signed.version.get(), 1);
+    let role1 = repo.delegated_role("role1").unwrap();
+    assert!(role1
+        .targets
+        .as_ref()
+        .unwrap()
+        .signed
+        .targets
+        .contains_key("file1.txt"));
+
+    // Without compression, the removed files are needed
+    RepositoryLoader::new(
+        File::open(root_path()).unwrap(),
+        dir_url(metadata.path()),
+        dir_url(targets_path()),
+    )
+    .load()
+    .unwrap_err();
+}
+
+/// Test that the length listed in snapshot.json limits the decompressed metadata.
+#[test]
+fn compressed_over_length() {
+    let metadata = TempDir::new().unwrap();
+    write_repo(metadata.path());
+    loader(metadata.path()).load().unwrap();
+
+    // Trailing whitespace doesn't change the parsed role, only its length
+    let mut role = std::fs::read(metadata.path().join("1.role1.json")).unwrap();
+    role.extend_from_slice(&[b' '; 64]);
+    let mut encoder = flate2::write::GzEncoder::new(
+        File::create(metadata.path().join("1.role1.json.gz")).unwrap(),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(&role).unwrap();
+    encoder.finish().unwrap();
+
+    let err = loader(metadata.path()).load().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            tough::error::Error::MaxSizeExceeded {
+                specifier: "snapshot.json",
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,53 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use ring::digest::{digest, SHA512};
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, test_data, RepoBuilder};
+use tough::editor::signed::PathExists;
+use tough::schema::Target;
+
+mod test_utils;
+
+/// Test that a target listing both SHA-256 and SHA-512 digests is written to, and fetched from,
+/// the path prefixed with its SHA-256 digest.
+#[test]
+fn sha256_prefix_with_sha512() {
+    let input = test_data()
+        .join("tuf-reference-impl")
+        .join("targets")
+        .join("file1.txt");
+    let contents = std::fs::read(&input).unwrap();
+    let sha512 = hex::encode(digest(&SHA512, &contents));
+    let mut target = Target::from_path(&input).unwrap();
+    target
+        .hashes
+        ._extra
+        .insert("sha512".to_owned(), sha512.clone().into());
+    let sha256 = hex::encode(&target.hashes.sha256);
+    assert_eq!(
+        target.consistent_name("file1.txt"),
+        format!("{}.file1.txt", sha256)
+    );
+
+    let builder = RepoBuilder::new();
+    let mut editor = builder.editor();
+    editor.add_target("file1.txt", target).unwrap();
+
+    let repo_dir = TempDir::new().unwrap();
+    let signed = builder.write_editor(editor, repo_dir.path());
+    let targets_dir = repo_dir.path().join("targets");
+    signed
+        .copy_target(&input, &targets_dir, PathExists::Skip, None)
+        .unwrap();
+    assert!(targets_dir.join(format!("{}.file1.txt", sha256)).is_file());
+    assert!(!targets_dir.join(format!("{}.file1.txt", sha512)).exists());
+
+    let repo = repo_loader(repo_dir.path()).load().unwrap();
+    let listed = &repo.targets().signed.targets["file1.txt"];
+    assert_eq!(listed.hashes._extra["sha512"], sha512.as_str());
+    assert_eq!(
+        read_to_end(repo.read_target("file1.txt").unwrap().unwrap()),
+        contents
+    );
+}
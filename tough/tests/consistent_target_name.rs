@@ -0,0 +1,44 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, test_data, RepoBuilder};
+use tough::consistent_target_name;
+use tough::editor::signed::PathExists;
+use tough::schema::Target;
+
+mod test_utils;
+
+/// Test that the editor writes, and the client fetches, targets whose names contain directories
+/// at the same consistent snapshot path.
+#[test]
+fn consistent_target_name_with_directories() {
+    let repo_dir = TempDir::new().unwrap();
+    let input = test_data()
+        .join("tuf-reference-impl")
+        .join("targets")
+        .join("file1.txt");
+    let name = "dir/sub/file1.txt";
+    let target = Target::from_path(&input).unwrap();
+    let path = consistent_target_name(&target.hashes.sha256, name);
+    assert_eq!(
+        path,
+        format!("dir/sub/{}.file1.txt", hex::encode(&target.hashes.sha256))
+    );
+
+    let builder = RepoBuilder::new();
+    let mut editor = builder.editor();
+    editor.add_target(name, target).unwrap();
+    let signed = builder.write_editor(editor, repo_dir.path());
+    let targets_dir = repo_dir.path().join("targets");
+    signed
+        .copy_target(&input, &targets_dir, PathExists::Skip, Some(name))
+        .unwrap();
+    assert!(targets_dir.join(&path).is_file());
+
+    let repo = repo_loader(repo_dir.path()).load().unwrap();
+    assert_eq!(
+        read_to_end(repo.read_target(name).unwrap().unwrap()),
+        std::fs::read(&input).unwrap()
+    );
+}
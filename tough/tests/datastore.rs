@@ -0,0 +1,98 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use tempfile::TempDir;
+use test_utils::{dir_url, test_data};
+use tough::error::Error;
+use tough::schema::{Signed, Snapshot};
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+fn loader() -> RepositoryLoader<File> {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+}
+
+/// Test that files in the datastore are created with the requested mode.
+#[cfg(unix)]
+#[test]
+fn datastore_file_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let datastore = TempDir::new().unwrap();
+    loader()
+        .datastore(datastore.path())
+        .datastore_file_mode(0o600)
+        .load()
+        .unwrap();
+    for file in &["timestamp.json", "snapshot.json", "targets.json"] {
+        let metadata = std::fs::metadata(datastore.path().join(file)).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600, "{}", file);
+    }
+}
+
+/// Test that a world-writable datastore file mode is rejected.
+#[test]
+fn datastore_file_mode_world_writable() {
+    let datastore = TempDir::new().unwrap();
+    let err = loader()
+        .datastore(datastore.path())
+        .datastore_file_mode(0o666)
+        .load()
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::DatastoreFileMode { .. }),
+        "unexpected error: {}",
+        err
+    );
+}
+
+/// Test that a corrupt snapshot in the datastore is treated as absent, so that the snapshot is
+/// fetched and verified again and replaces it.
+#[test]
+fn corrupt_cached_snapshot() {
+    let datastore = TempDir::new().unwrap();
+    loader().datastore(datastore.path()).load().unwrap();
+    let cached = datastore.path().join("snapshot.json");
+    let contents = std::fs::read(&cached).unwrap();
+    std::fs::write(&cached, &contents[..contents.len() / 2]).unwrap();
+
+    let repo = loader().datastore(datastore.path()).load().unwrap();
+    let recached: Signed<Snapshot> = serde_json::from_reader(File::open(&cached).unwrap()).unwrap();
+    assert_eq!(&recached, repo.snapshot());
+}
+
+/// Test that a corrupt snapshot fetched from the repository is an error, unlike a corrupt one in
+/// the datastore. The snapshot's hash is listed in the timestamp, so the corruption is reported as
+/// a hash mismatch.
+#[test]
+fn corrupt_fetched_snapshot() {
+    let metadata = TempDir::new().unwrap();
+    let base = test_data().join("tuf-reference-impl");
+    for entry in std::fs::read_dir(base.join("metadata")).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, metadata.path().join(path.file_name().unwrap())).unwrap();
+    }
+    let snapshot = metadata.path().join("snapshot.json");
+    let contents = std::fs::read(&snapshot).unwrap();
+    std::fs::write(&snapshot, &contents[..contents.len() / 2]).unwrap();
+
+    let err = RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(metadata.path()),
+        dir_url(base.join("targets")),
+    )
+    .load()
+    .unwrap_err();
+    assert!(
+        matches!(err, Error::HashMismatch { .. }),
+        "unexpected error: {}",
+        err
+    );
+}
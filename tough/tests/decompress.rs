@@ -0,0 +1,125 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(any(feature = "gzip", feature = "zstd"))]
+
+use ring::digest::{digest, SHA256};
+use std::io::Read;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, RepoBuilder};
+use tough::Repository;
+
+mod test_utils;
+
+const CONTENTS: &[u8] = b"This target is stored compressed.\n";
+
+/// Creates a repository in `repo_dir` whose only target, `blob`, has the contents `stored`.
+fn create_repo(repo_dir: &Path, stored: &[u8]) -> Repository {
+    let input = repo_dir.join("input");
+    std::fs::create_dir_all(&input).unwrap();
+    std::fs::write(input.join("blob"), stored).unwrap();
+
+    RepoBuilder::new()
+        .target_path(input.join("blob"))
+        .write(repo_dir);
+    repo_loader(repo_dir).load().unwrap()
+}
+
+/// Replaces the stored target in `repo_dir` with `stored`.
+fn replace_target(repo: &Repository, repo_dir: &Path, stored: &[u8]) {
+    let sha256 = hex::encode(&repo.targets().signed.targets["blob"].hashes.sha256);
+    let path = repo_dir.join("targets").join(format!("{}.blob", sha256));
+    std::fs::remove_file(&path).unwrap();
+    std::fs::write(&path, stored).unwrap();
+}
+
+#[cfg(feature = "gzip")]
+fn gzip(data: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// Test that the target's hash is over the compressed bytes, which `read_target` returns as
+/// stored, and that decompression is applied on top of the verified stream.
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_target_hash_over_compressed_bytes() {
+    let repo_dir = TempDir::new().unwrap();
+    let compressed = gzip(CONTENTS);
+    let repo = create_repo(repo_dir.path(), &compressed);
+
+    let target = &repo.targets().signed.targets["blob"];
+    assert_eq!(target.length, compressed.len() as u64);
+    assert_eq!(
+        target.hashes.sha256.as_ref(),
+        digest(&SHA256, &compressed).as_ref()
+    );
+    assert_eq!(
+        read_to_end(repo.read_target("blob").unwrap().unwrap()),
+        compressed
+    );
+    assert_eq!(
+        read_to_end(tough::decompress::gzip(
+            repo.read_target("blob").unwrap().unwrap()
+        )),
+        CONTENTS
+    );
+}
+
+/// Test that a tampered compressed target fails verification when read through the decompressor,
+/// even if the tampering is in bytes the decompressor doesn't need.
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_target_tampered() {
+    let repo_dir = TempDir::new().unwrap();
+    let mut compressed = gzip(CONTENTS);
+    compressed.extend_from_slice(b"trailer");
+    let repo = create_repo(repo_dir.path(), &compressed);
+    assert_eq!(
+        read_to_end(tough::decompress::gzip(
+            repo.read_target("blob").unwrap().unwrap()
+        )),
+        CONTENTS
+    );
+
+    let mut tampered = gzip(CONTENTS);
+    tampered.extend_from_slice(b"TRAILER");
+    replace_target(&repo, repo_dir.path(), &tampered);
+    let mut decompressed = Vec::new();
+    tough::decompress::gzip(repo.read_target("blob").unwrap().unwrap())
+        .read_to_end(&mut decompressed)
+        .expect_err("tampered target should not verify");
+}
+
+/// Test that a zstd-compressed target is verified as stored and can be decompressed.
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_target_hash_over_compressed_bytes() {
+    let repo_dir = TempDir::new().unwrap();
+    let compressed = zstd::encode_all(CONTENTS, 0).unwrap();
+    let repo = create_repo(repo_dir.path(), &compressed);
+
+    assert_eq!(
+        repo.targets().signed.targets["blob"].hashes.sha256.as_ref(),
+        digest(&SHA256, &compressed).as_ref()
+    );
+    assert_eq!(
+        read_to_end(tough::decompress::zstd(repo.read_target("blob").unwrap().unwrap()).unwrap()),
+        CONTENTS
+    );
+
+    replace_target(
+        &repo,
+        repo_dir.path(),
+        &zstd::encode_all(&b"Tampered"[..], 0).unwrap(),
+    );
+    let mut decompressed = Vec::new();
+    tough::decompress::zstd(repo.read_target("blob").unwrap().unwrap())
+        .unwrap()
+        .read_to_end(&mut decompressed)
+        .expect_err("tampered target should not verify");
+}
@@ -0,0 +1,66 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use test_utils::{dir_url, test_data};
+use tough::{Repository, RepositoryLoader};
+
+mod test_utils;
+
+fn load_tuf_reference_impl() -> Repository {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+    .load()
+    .unwrap()
+}
+
+/// Test that every level of delegation is listed, in the order a client consults it.
+#[test]
+fn delegated_roles_multi_level() {
+    // targets delegates to role1, which delegates to role2
+    let repo = load_tuf_reference_impl();
+    assert_eq!(repo.delegated_roles(), ["role1", "role2"]);
+}
+
+/// Test that a role reached more than once is listed once, and that roles whose metadata wasn't
+/// loaded aren't listed.
+#[test]
+fn delegated_roles_repeated() {
+    let repo = load_tuf_reference_impl();
+    let mut targets = repo.targets().signed.clone();
+    let delegations = targets.delegations.as_mut().unwrap();
+    let role1 = delegations.roles[0].clone();
+
+    // role2 delegates back to role1, as a cycle in the metadata would
+    let role2 = &mut delegations.roles[0]
+        .targets
+        .as_mut()
+        .unwrap()
+        .signed
+        .delegations
+        .as_mut()
+        .unwrap()
+        .roles[0];
+    role2
+        .targets
+        .as_mut()
+        .unwrap()
+        .signed
+        .delegations
+        .get_or_insert_with(Default::default)
+        .roles
+        .push(role1.clone());
+
+    // A later sibling delegates to role1 too, and another sibling wasn't loaded
+    let mut unloaded = role1.clone();
+    unloaded.name = "unloaded".to_owned();
+    unloaded.targets = None;
+    delegations.roles.push(role1);
+    delegations.roles.push(unloaded);
+
+    assert_eq!(targets.loaded_role_names(), ["role1", "role2"]);
+}
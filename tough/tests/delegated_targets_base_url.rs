@@ -0,0 +1,70 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{dir_url, read_to_end, repo_loader, test_data, RepoBuilder};
+
+mod test_utils;
+
+fn targets_path() -> PathBuf {
+    test_data().join("tuf-reference-impl").join("targets")
+}
+
+/// Writes a repository that lists `file3.txt` itself, under the default targets directory, and
+/// delegates `file1.txt` to `role1`.
+fn write_repo(repo_dir: &Path) {
+    let builder = RepoBuilder::new().target_path(targets_path().join("file3.txt"));
+    let editor = builder.delegating_editor();
+    builder.write_editor(editor, repo_dir);
+}
+
+/// Copies the reference target `name` into `dir` under its consistent snapshot filename.
+fn copy_target(dir: &Path, name: &str) {
+    let contents = std::fs::read(targets_path().join(name)).unwrap();
+    let digest = ring::digest::digest(&ring::digest::SHA256, &contents);
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(
+        dir.join(format!("{}.{}", hex::encode(digest), name)),
+        contents,
+    )
+    .unwrap();
+}
+
+/// Test that the targets of a delegated role are fetched from that role's targets base URL, while
+/// other targets are fetched from the default targets base URL.
+#[test]
+fn delegated_targets_base_url() {
+    let repo_dir = TempDir::new().unwrap();
+    let role1_targets = repo_dir.path().join("role1").join("targets");
+    write_repo(repo_dir.path());
+    copy_target(&role1_targets, "file1.txt");
+    let loader = || repo_loader(repo_dir.path());
+
+    // Without an override, role1's target is looked for under the default targets base URL
+    let repo = loader().load().unwrap();
+    assert!(repo.read_target("file1.txt").is_err());
+
+    let repo = loader()
+        .delegated_targets_base_url("role1", dir_url(&role1_targets))
+        .load()
+        .unwrap();
+    assert_eq!(
+        read_to_end(repo.read_target("file1.txt").unwrap().unwrap()),
+        std::fs::read(targets_path().join("file1.txt")).unwrap()
+    );
+    assert_eq!(
+        read_to_end(repo.read_target("file3.txt").unwrap().unwrap()),
+        std::fs::read(targets_path().join("file3.txt")).unwrap()
+    );
+
+    // Caching fetches each target from the same base URL
+    let cache = TempDir::new().unwrap();
+    repo.cache(
+        cache.path().join("metadata"),
+        cache.path().join("targets"),
+        None::<&[&str]>,
+        false,
+    )
+    .unwrap();
+}
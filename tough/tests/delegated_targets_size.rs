@@ -0,0 +1,46 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, RepoBuilder};
+
+mod test_utils;
+
+/// Writes a repository that delegates `file1.txt` to `role1`.
+fn write_repo(repo_dir: &Path) {
+    let builder = RepoBuilder::new();
+    let editor = builder.delegating_editor();
+    builder.write_editor(editor, repo_dir);
+}
+
+/// Test that delegated targets metadata longer than the length listed in snapshot.json is
+/// rejected, even though its signatures are still valid.
+#[test]
+fn delegated_targets_over_length() {
+    let repo_dir = TempDir::new().unwrap();
+    write_repo(repo_dir.path());
+    repo_loader(repo_dir.path()).load().unwrap();
+
+    // Trailing whitespace doesn't change the parsed role, only its length
+    OpenOptions::new()
+        .append(true)
+        .open(repo_dir.path().join("metadata").join("1.role1.json"))
+        .unwrap()
+        .write_all(&[b' '; 64])
+        .unwrap();
+    let err = repo_loader(repo_dir.path()).load().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            tough::error::Error::MaxSizeExceeded {
+                specifier: "snapshot.json",
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+}
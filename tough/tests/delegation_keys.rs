@@ -0,0 +1,74 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, resign_snapshot_and_timestamp, sign, RepoBuilder};
+use tough::error::Error;
+use tough::schema::{Signed, Targets};
+
+mod test_utils;
+
+const UNDECLARED: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+/// Writes a repository into `dir` whose targets delegate to `role1`, which lists a key that the
+/// delegation doesn't declare.
+fn create_repo(dir: &Path) {
+    RepoBuilder::new().write(dir);
+    let metadata = &dir.join("metadata");
+
+    // Add the delegation to targets, and point snapshot and timestamp at the result
+    let mut targets: Signed<Targets> =
+        serde_json::from_reader(File::open(metadata.join("1.targets.json")).unwrap()).unwrap();
+    targets.signed.delegations = Some(
+        serde_json::from_value(serde_json::json!({
+            "keys": {},
+            "roles": [{
+                "name": "role1",
+                "keyids": [UNDECLARED],
+                "threshold": 1,
+                "paths": ["*"],
+                "terminating": false
+            }]
+        }))
+        .unwrap(),
+    );
+    let targets = sign(targets.signed);
+    targets.write(metadata, true).unwrap();
+
+    resign_snapshot_and_timestamp(metadata, |snapshot| {
+        let meta = snapshot.meta.get_mut("targets.json").unwrap();
+        meta.length = Some(*targets.length());
+        meta.hashes.as_mut().unwrap().sha256 = targets.sha256().to_vec().into();
+        let role1_meta = meta.clone();
+        snapshot.meta.insert("role1.json".to_owned(), role1_meta);
+    });
+}
+
+/// Test that a delegated role listing a key its delegator doesn't declare is an error.
+#[test]
+fn undeclared_delegation_key() {
+    let dir = TempDir::new().unwrap();
+    create_repo(dir.path());
+
+    match repo_loader(dir.path()).load().unwrap_err() {
+        Error::MissingDelegationKey { role, keyid } => {
+            assert_eq!(role, "role1");
+            assert_eq!(keyid, UNDECLARED);
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    // The role is unreachable when unreachable delegations are skipped
+    let repo = repo_loader(dir.path())
+        .skip_unreachable_delegations(true)
+        .load()
+        .unwrap();
+    let unreachable = repo.unreachable_delegations();
+    assert_eq!(unreachable.len(), 1);
+    assert!(matches!(
+        &*unreachable[0].error,
+        Error::MissingDelegationKey { role, .. } if role == "role1"
+    ));
+}
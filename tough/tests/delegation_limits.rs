@@ -0,0 +1,119 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{
+    dir_url, repo_loader, resign_snapshot_and_timestamp, root_path, sign, test_data, RepoBuilder,
+};
+use tough::error::Error;
+use tough::schema::{Delegations, RoleType, Root, Signed, Targets};
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+fn root() -> Signed<Root> {
+    serde_json::from_reader(File::open(root_path()).unwrap()).unwrap()
+}
+
+/// Returns delegations of every path to `name`, which is signed with the same key as targets.
+fn delegate_to(name: &str) -> Delegations {
+    let root = root().signed;
+    let keyid = &root.roles[&RoleType::Targets].keyids[0];
+    serde_json::from_value(serde_json::json!({
+        "keys": { hex::encode(keyid): root.keys[keyid] },
+        "roles": [{
+            "name": name,
+            "keyids": [keyid],
+            "threshold": 1,
+            "paths": ["*"],
+            "terminating": false
+        }]
+    }))
+    .unwrap()
+}
+
+/// Writes a repository into `dir` whose targets delegate to `role1`, which delegates back to
+/// itself.
+fn create_cyclic_repo(dir: &Path) {
+    RepoBuilder::new().write(dir);
+    let metadata = &dir.join("metadata");
+
+    let mut targets: Signed<Targets> =
+        serde_json::from_reader(File::open(metadata.join("1.targets.json")).unwrap()).unwrap();
+    let mut role1 = targets.signed.clone();
+    targets.signed.delegations = Some(delegate_to("role1"));
+    let targets = sign(targets.signed);
+    targets.write(metadata, true).unwrap();
+    role1.delegations = Some(delegate_to("role1"));
+    let role1 = sign(role1);
+    std::fs::write(metadata.join("1.role1.json"), role1.buffer()).unwrap();
+
+    // Point snapshot at both, and timestamp at the result
+    resign_snapshot_and_timestamp(metadata, |snapshot| {
+        let mut meta = snapshot.meta["targets.json"].clone();
+        for (name, role) in &[("targets.json", &targets), ("role1.json", &role1)] {
+            meta.length = Some(*role.length());
+            meta.hashes.as_mut().unwrap().sha256 = role.sha256().to_vec().into();
+            snapshot.meta.insert((*name).to_owned(), meta.clone());
+        }
+    });
+}
+
+/// Test that a role that delegates to itself is an error rather than loaded endlessly, and that
+/// it's unreachable when unreachable delegations are skipped.
+#[test]
+fn delegation_cycle() {
+    let dir = TempDir::new().unwrap();
+    create_cyclic_repo(dir.path());
+    let loader = || repo_loader(dir.path());
+
+    match loader().load().unwrap_err() {
+        Error::DelegationCycle { name, chain } => {
+            assert_eq!(name, "role1");
+            assert_eq!(chain, "targets -> role1");
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    let repo = loader().skip_unreachable_delegations(true).load().unwrap();
+    assert_eq!(repo.delegated_roles(), ["role1"]);
+    let unreachable = repo.unreachable_delegations();
+    assert_eq!(unreachable.len(), 1);
+    assert!(matches!(
+        &*unreachable[0].error,
+        Error::DelegationCycle { name, .. } if name == "role1"
+    ));
+}
+
+/// Test that a role delegated to more than `max_delegation_depth` delegations from the top-level
+/// targets isn't loaded. In the reference implementation's repository, targets delegates to
+/// `role1`, which delegates to `role2`.
+#[test]
+fn delegation_depth() {
+    let base = test_data().join("tuf-reference-impl");
+    let load = |max_delegation_depth| {
+        RepositoryLoader::new(
+            File::open(base.join("metadata").join("1.root.json")).unwrap(),
+            dir_url(base.join("metadata")),
+            dir_url(base.join("targets")),
+        )
+        .max_delegation_depth(max_delegation_depth)
+        .load()
+    };
+
+    assert_eq!(load(2).unwrap().delegated_roles(), ["role1", "role2"]);
+    match load(1).unwrap_err() {
+        Error::DelegationDepth {
+            name,
+            max_depth,
+            chain,
+        } => {
+            assert_eq!(name, "role2");
+            assert_eq!(max_depth, 1);
+            assert_eq!(chain, "targets -> role1");
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use tempfile::TempDir;
+use test_utils::{repo_loader, root_path, RepoBuilder};
+use tough::editor::signed::PathExists;
+use tough::editor::RepositoryEditor;
+
+mod test_utils;
+
+/// Test that a repository with no targets can be created, published, and verified, and that the
+/// targets accessors all report an empty repository rather than an error.
+#[test]
+fn empty_targets() {
+    let repo_dir = TempDir::new().unwrap();
+    let input = repo_dir.path().join("input");
+    let targets_dir = repo_dir.path().join("targets");
+    std::fs::create_dir_all(&input).unwrap();
+
+    let signed = RepoBuilder::new().write(repo_dir.path());
+    signed
+        .copy_targets(&input, &targets_dir, PathExists::Skip)
+        .unwrap();
+    signed.check_targets(&targets_dir).unwrap();
+
+    let repo = repo_loader(repo_dir.path()).load().unwrap();
+    assert!(repo.targets().signed.targets.is_empty());
+    assert!(repo.targets().signed.targets_map().is_empty());
+    assert_eq!(repo.all_targets().count(), 0);
+    assert!(repo.target_names().is_empty());
+    assert!(repo.read_target("file1.txt").unwrap().is_none());
+
+    // The empty repository can be cached and edited like any other
+    let cache = TempDir::new().unwrap();
+    repo.cache(
+        cache.path().join("metadata"),
+        cache.path().join("targets"),
+        None::<&[&str]>,
+        false,
+    )
+    .unwrap();
+    RepositoryEditor::from_repo(root_path(), repo).unwrap();
+}
@@ -0,0 +1,120 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{dir_url, read_to_end, root_path, snakeoil_keys, test_data};
+use tough::editor::signed::{PathExists, SignedRole};
+use tough::editor::RepositoryEditor;
+use tough::error::{Error, Result};
+use tough::schema::{KeyHolder, RoleType, Root, Signed};
+use tough::{Repository, RepositoryLoader};
+
+mod test_utils;
+
+fn target_path() -> PathBuf {
+    test_data()
+        .join("tuf-reference-impl")
+        .join("targets")
+        .join("file1.txt")
+}
+
+/// Writes a copy of the root.json that corresponds with snakeoil.pem to `path`, expiring at
+/// `expires`.
+fn write_root(path: &Path, expires: DateTime<Utc>) {
+    let mut root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    root.signed.expires = expires;
+    let signed = SignedRole::new(
+        root.signed.clone(),
+        &KeyHolder::Root(root.signed),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap();
+    std::fs::write(path, signed.buffer()).unwrap();
+}
+
+/// Writes a repository with one target to `dir`, trusting `root` and with a timestamp that
+/// expired an hour ago.
+fn write_repo(dir: &Path, root: &Path) {
+    let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+    let expired = Utc::now().checked_sub_signed(Duration::hours(1)).unwrap();
+    let mut editor = RepositoryEditor::new(root).unwrap();
+    editor
+        .targets_version(NonZeroU64::new(1).unwrap())
+        .unwrap()
+        .targets_expires(expires)
+        .unwrap()
+        .snapshot_version(NonZeroU64::new(1).unwrap())
+        .snapshot_expires(expires)
+        .timestamp_version(NonZeroU64::new(1).unwrap())
+        .timestamp_expires(expired)
+        .add_target_path(target_path())
+        .unwrap();
+    let signed = editor.sign(&snakeoil_keys()).unwrap();
+    let targets_dir = dir.join("targets");
+    std::fs::create_dir_all(&targets_dir).unwrap();
+    signed
+        .copy_target(&target_path(), &targets_dir, PathExists::Skip, None)
+        .unwrap();
+    signed.write(dir.join("metadata")).unwrap();
+}
+
+fn load(dir: &Path, root: &Path, timestamp_grace: Option<Duration>) -> Result<Repository> {
+    let mut loader = RepositoryLoader::new(
+        File::open(root).unwrap(),
+        dir_url(dir.join("metadata")),
+        dir_url(dir.join("targets")),
+    );
+    if let Some(grace) = timestamp_grace {
+        loader = loader.expiration_grace(RoleType::Timestamp, grace);
+    }
+    loader.load()
+}
+
+fn assert_expired(result: Result<Repository>, expected: RoleType) {
+    match result {
+        Err(Error::ExpiredMetadata { role, .. }) => assert_eq!(role, expected),
+        other => panic!("expected {} to be expired, got {:?}", expected, other.err()),
+    }
+}
+
+/// Test that a timestamp that expired within its grace window is accepted, and that targets can
+/// be read with it, while the default is to reject it.
+#[test]
+fn timestamp_grace_window() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("root.json");
+    write_root(&root, Utc::now() + Duration::days(365));
+    write_repo(dir.path(), &root);
+
+    assert_expired(load(dir.path(), &root, None), RoleType::Timestamp);
+    assert_expired(
+        load(dir.path(), &root, Some(Duration::minutes(30))),
+        RoleType::Timestamp,
+    );
+
+    let repo = load(dir.path(), &root, Some(Duration::days(1))).unwrap();
+    assert_eq!(
+        read_to_end(repo.read_target("file1.txt").unwrap().unwrap()),
+        std::fs::read(target_path()).unwrap()
+    );
+}
+
+/// Test that a grace window for the timestamp doesn't extend to an expired root.
+#[test]
+fn expired_root_without_grace() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("root.json");
+    write_root(&root, Utc::now() - Duration::hours(1));
+    write_repo(dir.path(), &root);
+
+    assert_expired(
+        load(dir.path(), &root, Some(Duration::days(1))),
+        RoleType::Root,
+    );
+}
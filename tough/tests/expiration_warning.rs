@@ -0,0 +1,46 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{Duration, Utc};
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, RepoBuilder};
+
+mod test_utils;
+
+/// Writes a repository whose timestamp expires in two days and whose other roles expire in 30
+/// days.
+fn write_repo(repo_dir: &Path) {
+    let now = Utc::now();
+    let builder = RepoBuilder::new().expires(now + Duration::days(30));
+    let mut editor = builder.editor();
+    editor.timestamp_expires(now + Duration::days(2));
+    builder.write_editor(editor, repo_dir);
+}
+
+/// Test that loading warns about roles that expire within the window, and only those.
+#[test]
+fn expiration_warning() {
+    let repo_dir = TempDir::new().unwrap();
+    write_repo(repo_dir.path());
+
+    let repo = repo_loader(repo_dir.path())
+        .expiration_warning(Duration::days(7))
+        .load()
+        .unwrap();
+    let warnings = repo.expiration_warnings();
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(warnings[0].role, "timestamp");
+    assert_eq!(warnings[0].expires, repo.timestamp().signed.expires);
+
+    // A shorter window doesn't include the timestamp
+    let repo = repo_loader(repo_dir.path())
+        .expiration_warning(Duration::days(1))
+        .load()
+        .unwrap();
+    assert!(repo.expiration_warnings().is_empty());
+
+    // Without a window, nothing is reported
+    let repo = repo_loader(repo_dir.path()).load().unwrap();
+    assert!(repo.expiration_warnings().is_empty());
+}
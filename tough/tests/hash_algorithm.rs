@@ -0,0 +1,141 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use ring::digest::{digest, Context, SHA256, SHA384, SHA512};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{repo_loader, test_data, RepoBuilder};
+use tough::hash::{HashAlgorithm, HashContext, Sha256, Sha512};
+use tough::schema::Target;
+use tough::Repository;
+
+mod test_utils;
+
+/// A hash algorithm that isn't built in.
+#[derive(Debug, Clone)]
+struct Sha384;
+
+struct Sha384Context(Context);
+
+impl HashContext for Sha384Context {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finish(self: Box<Self>) -> Vec<u8> {
+        self.0.finish().as_ref().to_vec()
+    }
+}
+
+impl HashAlgorithm for Sha384 {
+    fn name(&self) -> &str {
+        "sha384"
+    }
+
+    fn strength(&self) -> u32 {
+        192
+    }
+
+    fn start(&self) -> Box<dyn HashContext> {
+        Box::new(Sha384Context(Context::new(&SHA384)))
+    }
+}
+
+fn target_path() -> PathBuf {
+    test_data()
+        .join("tuf-reference-impl")
+        .join("targets")
+        .join("file1.txt")
+}
+
+fn contents() -> Vec<u8> {
+    std::fs::read(target_path()).unwrap()
+}
+
+/// Writes a repository into `dir` whose only target, `file1.txt`, lists `sha256` and `sha384`
+/// digests, which needn't be correct.
+fn write_repo(dir: &Path, sha256: &[u8], sha384: &[u8]) {
+    let mut target = Target::from_path(target_path()).unwrap();
+    target.hashes.sha256 = sha256.to_vec().into();
+    target
+        .hashes
+        ._extra
+        .insert("sha384".to_owned(), hex::encode(sha384).into());
+
+    let builder = RepoBuilder::new();
+    let mut editor = builder.editor();
+    editor.add_target("file1.txt", target.clone()).unwrap();
+    builder.write_editor(editor, dir);
+
+    std::fs::write(
+        dir.join("targets")
+            .join(target.consistent_name("file1.txt")),
+        contents(),
+    )
+    .unwrap();
+}
+
+fn load(dir: &Path, custom: bool) -> Repository {
+    let mut loader = repo_loader(dir);
+    if custom {
+        loader = loader.hash_algorithm(Sha384);
+    }
+    loader.load().unwrap()
+}
+
+fn read(repo: &Repository) -> std::io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    std::io::copy(
+        &mut repo.read_target("file1.txt").unwrap().unwrap(),
+        &mut contents,
+    )?;
+    Ok(contents)
+}
+
+/// Test that a target is verified with a registered algorithm when it's the strongest one listed,
+/// so that a target whose SHA-256 digest is wrong, but whose SHA-384 digest is right, is read.
+#[test]
+fn custom_algorithm_used() {
+    let dir = TempDir::new().unwrap();
+    let sha384 = digest(&SHA384, &contents());
+    write_repo(dir.path(), &[0; 32], sha384.as_ref());
+
+    read(&load(dir.path(), false)).unwrap_err();
+    assert_eq!(read(&load(dir.path(), true)).unwrap(), contents());
+}
+
+/// Test that a target whose digest for a registered algorithm is wrong isn't read, even though its
+/// SHA-256 digest is right.
+#[test]
+fn custom_algorithm_mismatch() {
+    let dir = TempDir::new().unwrap();
+    let sha256 = digest(&SHA256, &contents());
+    write_repo(dir.path(), sha256.as_ref(), &[0; 48]);
+
+    assert_eq!(read(&load(dir.path(), false)).unwrap(), contents());
+    read(&load(dir.path(), true)).unwrap_err();
+}
+
+/// Test that a target built from a path lists a digest for each algorithm it's given, alongside its
+/// SHA-256 digest, and nothing else.
+#[test]
+fn target_from_path_with_hash_algorithms() {
+    let algorithms: Vec<Box<dyn HashAlgorithm>> =
+        vec![Box::new(Sha256), Box::new(Sha512), Box::new(Sha384)];
+    let target = Target::from_path_with_hash_algorithms(target_path(), &algorithms).unwrap();
+
+    assert_eq!(target.length, contents().len() as u64);
+    assert_eq!(
+        target.hashes.sha256.as_ref(),
+        digest(&SHA256, &contents()).as_ref()
+    );
+    assert_eq!(
+        target.hashes.get("sha512").unwrap(),
+        digest(&SHA512, &contents()).as_ref()
+    );
+    assert_eq!(
+        target.hashes.get("sha384").unwrap(),
+        digest(&SHA384, &contents()).as_ref()
+    );
+    assert_eq!(target.hashes._extra.len(), 2);
+}
@@ -81,6 +81,37 @@ mod http_happy {
         mock_file1_txt.assert();
         mock_file2_txt.assert();
     }
+
+    /// Test that an `HttpTransport` and its clones, which share one client, can each fetch,
+    /// including from another thread.
+    #[test]
+    fn test_http_transport_shared_client() {
+        let mock = mock("GET", "/targets/file1.txt")
+            .with_status(200)
+            .with_body("This is an example target file.")
+            .expect(3)
+            .create();
+        let url = Url::from_str(mockito::server_url().as_str())
+            .unwrap()
+            .join("targets/file1.txt")
+            .unwrap();
+        let transport = HttpTransport::default();
+        let fetch = |transport: &HttpTransport| read_to_end(transport.fetch(url.clone()).unwrap());
+
+        assert_eq!(fetch(&transport), &b"This is an example target file."[..]);
+        assert_eq!(
+            fetch(&transport.clone()),
+            &b"This is an example target file."[..]
+        );
+        let clone = transport.clone();
+        let url_clone = url.clone();
+        let from_thread = std::thread::spawn(move || read_to_end(clone.fetch(url_clone).unwrap()))
+            .join()
+            .unwrap();
+        assert_eq!(from_thread, &b"This is an example target file."[..]);
+
+        mock.assert();
+    }
 }
 
 #[cfg(feature = "http")]
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use std::fs::File;
+use std::path::Path;
 use tempfile::TempDir;
 use test_utils::{dir_url, read_to_end, test_data};
 use tough::{FilesystemTransport, Limits, Repository, RepositoryLoader};
@@ -80,3 +81,22 @@ fn test_tuf_reference_impl_default_transport() {
     .unwrap();
     assert_tuf_reference_impl(&repo);
 }
+
+/// Test that `tough` can load a repository from local directories, given as relative or absolute
+/// paths, without building URLs for them.
+#[test]
+fn test_tuf_reference_impl_from_directories() {
+    // Tests run in the crate's directory
+    let relative = Path::new("tests").join("data").join("tuf-reference-impl");
+    for base in &[relative, test_data().join("tuf-reference-impl")] {
+        let repo = RepositoryLoader::from_directories(
+            File::open(base.join("metadata").join("1.root.json")).unwrap(),
+            base.join("metadata"),
+            base.join("targets"),
+        )
+        .unwrap()
+        .load()
+        .unwrap();
+        assert_tuf_reference_impl(&repo);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, RepoBuilder};
+use tough::error::{Error, Result};
+use tough::Repository;
+
+mod test_utils;
+
+/// Writes a repository into `dir`, whose snapshot and targets are listed with their lengths and
+/// hashes.
+fn write_repo(dir: &Path) {
+    RepoBuilder::new().write(dir);
+}
+
+fn load(dir: &Path) -> Result<Repository> {
+    repo_loader(dir).load()
+}
+
+/// Replaces the first `1` in the metadata file `name` with a `2`, keeping its length.
+fn alter(dir: &Path, name: &str) {
+    let path = dir.join("metadata").join(name);
+    let mut contents = std::fs::read(&path).unwrap();
+    let i = contents.iter().position(|&b| b == b'1').unwrap();
+    contents[i] = b'2';
+    std::fs::write(&path, contents).unwrap();
+}
+
+/// Appends a space to the metadata file `name`, which is still valid JSON.
+fn extend(dir: &Path, name: &str) {
+    let path = dir.join("metadata").join(name);
+    let mut contents = std::fs::read(&path).unwrap();
+    contents.push(b' ');
+    std::fs::write(&path, contents).unwrap();
+}
+
+/// Test that a snapshot whose hash doesn't match the one listed in the timestamp is refused with a
+/// hash mismatch, rather than a parse error.
+#[test]
+fn snapshot_hash_mismatch() {
+    let dir = TempDir::new().unwrap();
+    write_repo(dir.path());
+    alter(dir.path(), "1.snapshot.json");
+
+    match load(dir.path()).unwrap_err() {
+        Error::HashMismatch { context, .. } => assert!(context.ends_with("1.snapshot.json")),
+        other => panic!("unexpected error: {}", other),
+    }
+}
+
+/// Test that a snapshot longer than the length listed in the timestamp is refused.
+#[test]
+fn snapshot_length_mismatch() {
+    let dir = TempDir::new().unwrap();
+    write_repo(dir.path());
+    extend(dir.path(), "1.snapshot.json");
+
+    match load(dir.path()).unwrap_err() {
+        Error::MaxSizeExceeded { specifier, .. } => assert_eq!(specifier, "timestamp.json"),
+        other => panic!("unexpected error: {}", other),
+    }
+}
+
+/// Test that targets whose hash doesn't match the one listed in the snapshot are refused with a
+/// hash mismatch, rather than a parse error.
+#[test]
+fn targets_hash_mismatch() {
+    let dir = TempDir::new().unwrap();
+    write_repo(dir.path());
+    alter(dir.path(), "1.targets.json");
+
+    match load(dir.path()).unwrap_err() {
+        Error::HashMismatch { context, .. } => assert!(context.ends_with("1.targets.json")),
+        other => panic!("unexpected error: {}", other),
+    }
+}
+
+/// Test that targets longer than the length listed in the snapshot are refused.
+#[test]
+fn targets_length_mismatch() {
+    let dir = TempDir::new().unwrap();
+    write_repo(dir.path());
+    extend(dir.path(), "1.targets.json");
+
+    match load(dir.path()).unwrap_err() {
+        Error::MaxSizeExceeded { specifier, .. } => assert_eq!(specifier, "snapshot.json"),
+        other => panic!("unexpected error: {}", other),
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use test_utils::{dir_url, read_to_end, test_data};
+use tough::observer::{Event, Observer};
+use tough::{Repository, RepositoryLoader};
+
+mod test_utils;
+
+/// An observer that records a description of each event.
+#[derive(Debug, Clone, Default)]
+struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Recorder {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.events.lock().unwrap())
+    }
+}
+
+impl Observer for Recorder {
+    fn event(&self, event: &Event<'_>) {
+        let description = match event {
+            Event::MetadataVerified {
+                role,
+                name,
+                version,
+            } => format!("verified {} {} v{}", role, name, version),
+            Event::TargetStarted { name, length } => format!("started {} {}", name, length),
+            Event::TargetProgress {
+                name,
+                bytes,
+                length,
+            } => format!("progress {} {}/{}", name, bytes, length),
+            Event::TargetFinished { name, bytes } => format!("finished {} {}", name, bytes),
+            Event::TargetFailed { name, .. } => format!("failed {}", name),
+            _ => "unknown".to_owned(),
+        };
+        self.events.lock().unwrap().push(description);
+    }
+}
+
+/// Loads `tuf-reference-impl`, whose targets are read from `targets_dir`, with `recorder`.
+fn load(targets_dir: &Path, recorder: &Recorder) -> Repository {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(targets_dir),
+    )
+    .observer(recorder.clone())
+    .load()
+    .unwrap()
+}
+
+/// Test that the observer is told about each verified role, including delegated roles, and about
+/// the progress of each target that's read.
+#[test]
+fn observe_load_and_read() {
+    let recorder = Recorder::default();
+    let targets_dir = test_data().join("tuf-reference-impl").join("targets");
+    let repo = load(&targets_dir, &recorder);
+    assert_eq!(
+        recorder.take(),
+        [
+            "verified root root v1",
+            "verified timestamp timestamp v1",
+            "verified snapshot snapshot v1",
+            "verified targets targets v1",
+            "verified delegated-targets role1 v1",
+            "verified delegated-targets role2 v1",
+        ]
+    );
+
+    read_to_end(repo.read_target("file1.txt").unwrap().unwrap());
+    let events = recorder.take();
+    assert_eq!(events.first().unwrap(), "started file1.txt 31");
+    assert_eq!(events[events.len() - 2], "progress file1.txt 31/31");
+    assert_eq!(events.last().unwrap(), "finished file1.txt 31");
+
+    // Delegated targets are read the same way
+    let out_dir = TempDir::new().unwrap();
+    repo.save_target("file3.txt", out_dir.path().join("file3.txt"))
+        .unwrap();
+    let events = recorder.take();
+    assert_eq!(events.first().unwrap(), "started file3.txt 28");
+    assert_eq!(events.last().unwrap(), "finished file3.txt 28");
+}
+
+/// Test that the observer is told when a target fails verification, and not that it finished.
+#[test]
+fn observe_failed_target() {
+    let recorder = Recorder::default();
+    let targets_dir = TempDir::new().unwrap();
+    std::fs::write(
+        targets_dir.path().join("file1.txt"),
+        "This is not the file you want.\n",
+    )
+    .unwrap();
+    let repo = load(targets_dir.path(), &recorder);
+    recorder.take();
+
+    let mut reader = repo.read_target("file1.txt").unwrap().unwrap();
+    assert!(std::io::copy(&mut reader, &mut std::io::sink()).is_err());
+    let events = recorder.take();
+    assert_eq!(events.first().unwrap(), "started file1.txt 31");
+    assert_eq!(events.last().unwrap(), "failed file1.txt");
+    assert!(!events.iter().any(|event| event.starts_with("finished")));
+}
+
+/// Test that the observer is told when a target can't be fetched at all.
+#[test]
+fn observe_unfetchable_target() {
+    let recorder = Recorder::default();
+    let targets_dir = TempDir::new().unwrap();
+    let repo = load(targets_dir.path(), &recorder);
+    recorder.take();
+
+    assert!(repo.read_target("file1.txt").is_err());
+    assert_eq!(
+        recorder.take(),
+        ["started file1.txt 31", "failed file1.txt"]
+    );
+}
@@ -0,0 +1,112 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::io::Read;
+use std::path::PathBuf;
+use test_utils::test_data;
+use tough::error::Error;
+use tough::schema::{Root, Signed, Snapshot, Targets, Timestamp};
+use tough::{read_metadata, read_verified_metadata};
+
+mod test_utils;
+
+fn metadata_path(name: &str) -> PathBuf {
+    test_data()
+        .join("tuf-reference-impl")
+        .join("metadata")
+        .join(name)
+}
+
+fn root() -> Signed<Root> {
+    serde_json::from_slice(&std::fs::read(metadata_path("root.json")).unwrap()).unwrap()
+}
+
+/// A reader that returns at most `chunk` bytes on each read, like a pipe delivering data in
+/// small pieces.
+struct ChunkedReader {
+    data: Vec<u8>,
+    position: usize,
+    chunk: usize,
+}
+
+impl ChunkedReader {
+    fn new(name: &str, chunk: usize) -> Self {
+        Self {
+            data: std::fs::read(metadata_path(name)).unwrap(),
+            position: 0,
+            chunk,
+        }
+    }
+}
+
+impl Read for ChunkedReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let end = (self.position + self.chunk.min(buf.len())).min(self.data.len());
+        let size = end - self.position;
+        buf[..size].copy_from_slice(&self.data[self.position..end]);
+        self.position = end;
+        Ok(size)
+    }
+}
+
+/// Test that metadata read in chunks parses and verifies the same as metadata read all at once.
+#[test]
+fn chunked_metadata_matches_whole() {
+    let root = root();
+    for chunk in &[1, 7, 4096] {
+        let timestamp: Signed<Timestamp> = read_verified_metadata(
+            ChunkedReader::new("timestamp.json", *chunk),
+            4096,
+            &root.signed,
+        )
+        .unwrap();
+        let whole: Signed<Timestamp> =
+            serde_json::from_slice(&std::fs::read(metadata_path("timestamp.json")).unwrap())
+                .unwrap();
+        root.signed.verify_role(&whole).unwrap();
+        assert_eq!(timestamp, whole);
+
+        let snapshot: Signed<Snapshot> = read_verified_metadata(
+            ChunkedReader::new("snapshot.json", *chunk),
+            4096,
+            &root.signed,
+        )
+        .unwrap();
+        let targets: Signed<Targets> = read_verified_metadata(
+            ChunkedReader::new("targets.json", *chunk),
+            8192,
+            &root.signed,
+        )
+        .unwrap();
+        assert_eq!(snapshot.signed.version.get(), 1);
+        assert_eq!(targets.signed.version.get(), 1);
+    }
+}
+
+/// Test that metadata larger than the size cap is rejected.
+#[test]
+fn metadata_size_cap() {
+    let len = std::fs::read(metadata_path("timestamp.json"))
+        .unwrap()
+        .len() as u64;
+    read_metadata::<Timestamp, _>(ChunkedReader::new("timestamp.json", 7), len).unwrap();
+    let err = read_metadata::<Timestamp, _>(ChunkedReader::new("timestamp.json", 7), len - 1)
+        .unwrap_err();
+    assert!(matches!(err, Error::MaxSizeExceeded { .. }), "{}", err);
+}
+
+/// Test that metadata for the wrong role, or that isn't signed by the trusted root, is rejected.
+#[test]
+fn chunked_metadata_verification_fails() {
+    let root = root();
+    read_metadata::<Snapshot, _>(ChunkedReader::new("timestamp.json", 7), 4096).unwrap_err();
+
+    // role1.json is signed by a delegated key rather than the targets key listed by root
+    let err = read_verified_metadata::<Targets, _>(
+        ChunkedReader::new("role1.json", 7),
+        4096,
+        &root.signed,
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::VerifyMetadata { .. }), "{}", err);
+}
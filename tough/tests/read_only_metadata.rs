@@ -0,0 +1,75 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![cfg(unix)]
+
+use std::fs::{self, File, Permissions};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{dir_url, test_data};
+use tough::{ExpirationEnforcement, Repository, RepositoryLoader};
+
+mod test_utils;
+
+/// Copies the metadata of the reference implementation's repository into `dir`.
+fn copy_metadata(dir: &Path) {
+    let metadata = test_data().join("tuf-reference-impl").join("metadata");
+    for entry in fs::read_dir(metadata).unwrap() {
+        let entry = entry.unwrap();
+        fs::copy(entry.path(), dir.join(entry.file_name())).unwrap();
+    }
+}
+
+/// Sets the mode of `dir` and every file in it.
+fn set_modes(dir: &Path, dir_mode: u32, file_mode: u32) {
+    for entry in fs::read_dir(dir).unwrap() {
+        fs::set_permissions(entry.unwrap().path(), Permissions::from_mode(file_mode)).unwrap();
+    }
+    fs::set_permissions(dir, Permissions::from_mode(dir_mode)).unwrap();
+}
+
+fn load(metadata: &Path, check: bool) -> Repository {
+    RepositoryLoader::new(
+        File::open(metadata.join("1.root.json")).unwrap(),
+        dir_url(metadata),
+        dir_url(test_data().join("tuf-reference-impl").join("targets")),
+    )
+    .expiration_enforcement(ExpirationEnforcement::Unsafe)
+    .read_only_metadata(check)
+    .load()
+    .unwrap()
+}
+
+/// Test that metadata that can't be written to passes the check, and that anything writable is
+/// listed.
+#[test]
+fn read_only_metadata_check() {
+    let tmp = TempDir::new().unwrap();
+    let metadata = tmp.path().join("metadata");
+    fs::create_dir(&metadata).unwrap();
+    copy_metadata(&metadata);
+
+    set_modes(&metadata, 0o555, 0o444);
+    assert!(load(&metadata, true).writable_metadata().is_empty());
+
+    // A writable file, or a writable directory that files could be replaced in, is listed
+    fs::set_permissions(&metadata, Permissions::from_mode(0o755)).unwrap();
+    let writable = metadata.join("timestamp.json");
+    fs::set_permissions(&writable, Permissions::from_mode(0o644)).unwrap();
+    assert_eq!(
+        load(&metadata, true).writable_metadata(),
+        [metadata.clone(), writable]
+    );
+
+    set_modes(&metadata, 0o755, 0o644);
+}
+
+/// Test that writable metadata isn't checked by default.
+#[test]
+fn read_only_metadata_unchecked() {
+    let tmp = TempDir::new().unwrap();
+    copy_metadata(tmp.path());
+    set_modes(tmp.path(), 0o755, 0o644);
+    assert!(load(tmp.path(), false).writable_metadata().is_empty());
+}
@@ -0,0 +1,118 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, root_path, snakeoil_keys, test_data, RepoBuilder};
+use tough::editor::signed::SignedRole;
+use tough::schema::{KeyHolder, Root, Signed, Timestamp};
+use tough::RoleChanges;
+
+mod test_utils;
+
+/// Writes a repository listing `targets` to `dir`. Metadata written with the same arguments is
+/// identical.
+fn write_repo(
+    dir: &Path,
+    expires: DateTime<Utc>,
+    targets: &[&str],
+    targets_version: u64,
+    snapshot_version: u64,
+    timestamp_version: u64,
+) {
+    let mut builder = RepoBuilder::new().expires(expires);
+    for target in targets {
+        builder = builder.target_path(
+            test_data()
+                .join("tuf-reference-impl")
+                .join("targets")
+                .join(target),
+        );
+    }
+    let mut editor = builder.editor();
+    editor
+        .targets_version(NonZeroU64::new(targets_version).unwrap())
+        .unwrap()
+        .snapshot_version(NonZeroU64::new(snapshot_version).unwrap())
+        .timestamp_version(NonZeroU64::new(timestamp_version).unwrap());
+    builder.write_editor(editor, dir);
+}
+
+/// Re-signs the timestamp in `dir` with its version incremented, leaving the other roles as they
+/// are.
+fn bump_timestamp(dir: &Path) {
+    let metadata = dir.join("metadata");
+    let root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    let mut timestamp: Signed<Timestamp> =
+        serde_json::from_reader(File::open(metadata.join("timestamp.json")).unwrap()).unwrap();
+    timestamp.signed.version = NonZeroU64::new(timestamp.signed.version.get() + 1).unwrap();
+    SignedRole::new(
+        timestamp.signed,
+        &KeyHolder::Root(root.signed),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap()
+    .write(&metadata, true)
+    .unwrap();
+}
+
+/// Test that `refresh` reports only the roles whose metadata was updated.
+#[test]
+fn refresh_reports_changed_roles() {
+    let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+    let dir = TempDir::new().unwrap();
+    write_repo(dir.path(), expires, &["file1.txt"], 1, 1, 1);
+
+    let mut repo = repo_loader(dir.path()).load().unwrap();
+
+    // Nothing was published
+    let changes = repo.refresh().unwrap();
+    assert_eq!(changes, RoleChanges::default());
+    assert!(!changes.any());
+
+    // Only the timestamp was re-signed
+    bump_timestamp(dir.path());
+    let changes = repo.refresh().unwrap();
+    assert_eq!(
+        changes,
+        RoleChanges {
+            timestamp: true,
+            ..RoleChanges::default()
+        }
+    );
+    assert_eq!(repo.timestamp().signed.version.get(), 2);
+
+    // A target was added
+    write_repo(dir.path(), expires, &["file1.txt", "file2.txt"], 2, 2, 3);
+    let changes = repo.refresh().unwrap();
+    assert_eq!(
+        changes,
+        RoleChanges {
+            root: false,
+            timestamp: true,
+            snapshot: true,
+            targets: true,
+        }
+    );
+    assert!(repo.targets().signed.targets.contains_key("file2.txt"));
+}
+
+/// Test that a failed refresh leaves the repository's trusted metadata unchanged.
+#[test]
+fn refresh_failure_keeps_state() {
+    let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+    let dir = TempDir::new().unwrap();
+    write_repo(dir.path(), expires, &["file1.txt"], 1, 1, 2);
+
+    let mut repo = repo_loader(dir.path()).load().unwrap();
+
+    // Publishing an older timestamp is a rollback, which the refresh must reject
+    write_repo(dir.path(), expires, &["file1.txt"], 1, 1, 1);
+    repo.refresh().unwrap_err();
+    assert_eq!(repo.timestamp().signed.version.get(), 2);
+}
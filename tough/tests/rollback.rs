@@ -0,0 +1,135 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, test_data, RepoBuilder};
+use tough::error::Error;
+use tough::schema::RoleType;
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+/// Creates a repository in `outdir` containing the single target `target`.
+fn create_repo(outdir: &Path, target: &str, timestamp_version: u64, snapshot_version: u64) {
+    let builder = RepoBuilder::new().version(snapshot_version).target_path(
+        test_data()
+            .join("tuf-reference-impl")
+            .join("targets")
+            .join(target),
+    );
+    let mut editor = builder.editor();
+    editor.timestamp_version(NonZeroU64::new(timestamp_version).unwrap());
+    builder.write_editor(editor, outdir);
+}
+
+/// Creates a repository in `outdir` that delegates `file1.txt` to `role1`, with the top-level roles
+/// at `version` and `role1` at `role_version`.
+fn create_delegated_repo(outdir: &Path, version: u64, role_version: u64) {
+    // Sign `role1` at `role_version`, then move the top-level roles to `version`
+    let builder = RepoBuilder::new().version(role_version);
+    let mut editor = builder.delegating_editor();
+    let version = NonZeroU64::new(version).unwrap();
+    editor
+        .targets_version(version)
+        .unwrap()
+        .snapshot_version(version)
+        .timestamp_version(version);
+    builder.write_editor(editor, outdir);
+}
+
+fn loader(repo_dir: &Path, datastore: &Path) -> RepositoryLoader<File> {
+    repo_loader(repo_dir).datastore(datastore)
+}
+
+/// Test that a snapshot with the same version as the trusted snapshot, but different content, is
+/// rejected even though the timestamp version was bumped.
+#[test]
+fn snapshot_changed_without_version_bump() {
+    let datastore = TempDir::new().unwrap();
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+    create_repo(first.path(), "file1.txt", 1, 1);
+    create_repo(second.path(), "file2.txt", 2, 1);
+
+    loader(first.path(), datastore.path()).load().unwrap();
+    let err = loader(second.path(), datastore.path()).load().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::ContentChangedWithoutVersionBump {
+                role: RoleType::Snapshot,
+                ..
+            }
+        ),
+        "unexpected error: {}",
+        err
+    );
+}
+
+/// Test that reloading an unchanged repository with the same datastore is not an error.
+#[test]
+fn same_version_same_content() {
+    let datastore = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    create_repo(repo.path(), "file1.txt", 1, 1);
+
+    loader(repo.path(), datastore.path()).load().unwrap();
+    loader(repo.path(), datastore.path()).load().unwrap();
+}
+
+/// Test that a snapshot older than the trusted snapshot is rejected, even though the timestamp
+/// version was bumped.
+#[test]
+fn snapshot_rollback() {
+    let datastore = TempDir::new().unwrap();
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+    create_repo(first.path(), "file1.txt", 1, 2);
+    create_repo(second.path(), "file1.txt", 2, 1);
+
+    loader(first.path(), datastore.path()).load().unwrap();
+    let err = loader(second.path(), datastore.path()).load().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::OlderMetadata {
+                role: RoleType::Snapshot,
+                current_version: 2,
+                new_version: 1,
+                ..
+            }
+        ),
+        "unexpected error: {}",
+        err
+    );
+}
+
+/// Test that a snapshot listing an older version of a delegated role than the trusted snapshot is
+/// rejected, even though every top-level role's version was bumped.
+#[test]
+fn delegated_role_rollback() {
+    let datastore = TempDir::new().unwrap();
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+    create_delegated_repo(first.path(), 1, 2);
+    create_delegated_repo(second.path(), 2, 1);
+
+    loader(first.path(), datastore.path()).load().unwrap();
+    let err = loader(second.path(), datastore.path()).load().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::OlderMetadata {
+                role: RoleType::DelegatedTargets,
+                current_version: 2,
+                new_version: 1,
+                ..
+            }
+        ),
+        "unexpected error: {}",
+        err
+    );
+}
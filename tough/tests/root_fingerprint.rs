@@ -0,0 +1,53 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::num::NonZeroU64;
+use test_utils::{dir_url, test_data};
+use tough::error::Error;
+use tough::schema::{Root, Signed};
+use tough::{FilesystemTransport, RepositoryLoader};
+
+mod test_utils;
+
+fn loader(fingerprint: &[u8]) -> tough::error::Result<RepositoryLoader<std::io::Cursor<Vec<u8>>>> {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::from_root_fingerprint(
+        fingerprint,
+        NonZeroU64::new(1).unwrap(),
+        FilesystemTransport,
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+}
+
+/// Test that a repository can be loaded by trusting the root that matches a fingerprint.
+#[test]
+fn root_fingerprint_match() {
+    let root: Signed<Root> = serde_json::from_reader(
+        File::open(
+            test_data()
+                .join("tuf-reference-impl")
+                .join("metadata")
+                .join("1.root.json"),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let fingerprint = root.signed.fingerprint().unwrap();
+
+    let repo = loader(&fingerprint).unwrap().load().unwrap();
+    assert_eq!(repo.root().signed, root.signed);
+    assert!(repo.read_target("file1.txt").unwrap().is_some());
+}
+
+/// Test that a root that doesn't match the fingerprint is not trusted.
+#[test]
+fn root_fingerprint_mismatch() {
+    let err = loader(&[0; 32]).err().unwrap();
+    assert!(
+        matches!(err, Error::RootFingerprintMismatch { .. }),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,157 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{Duration, Utc};
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{key, repo_loader, root_path, snakeoil_keys};
+use tough::editor::signed::SignedRole;
+use tough::editor::RepositoryEditor;
+use tough::error::Error;
+use tough::schema::{KeyHolder, RoleType, Root, Signed};
+
+mod test_utils;
+
+fn trusted_root() -> Root {
+    let root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    root.signed
+}
+
+/// Returns the next version of `root`, with the root role's keys replaced by the key in `key_name`.
+fn rotate(root: &Root, key_name: &str) -> Root {
+    let key = key(key_name)[0].as_sign().unwrap().tuf_key();
+    let key_id = key.key_id().unwrap();
+    let mut new_root = root.clone();
+    new_root.version = NonZeroU64::new(root.version.get() + 1).unwrap();
+    new_root.keys.insert(key_id.clone(), key);
+    new_root.roles.get_mut(&RoleType::Root).unwrap().keyids = vec![key_id];
+    new_root
+}
+
+/// Signs `root` with the keys in `signers`, each of which must be listed for the root role in the
+/// root it's paired with.
+fn sign_root(root: &Root, signers: &[(&Root, &str)]) -> SignedRole<Root> {
+    let sign_with = |holder: &Root, key_name: &str| {
+        SignedRole::new(
+            root.clone(),
+            &KeyHolder::Root(holder.clone()),
+            &key(key_name),
+            &SystemRandom::new(),
+        )
+        .unwrap()
+    };
+    let (first, rest) = signers.split_first().unwrap();
+    let mut signed = sign_with(first.0, first.1);
+    for (holder, key_name) in rest {
+        let signatures = sign_with(holder, key_name).signed().signatures.clone();
+        signed = signed.add_old_signatures(signatures).unwrap();
+    }
+    signed
+}
+
+/// Writes the `roots` to `dir/metadata`, followed by the rest of a repository signed under the
+/// last of them.
+fn create_repo(dir: &Path, roots: &[SignedRole<Root>]) {
+    let metadata = dir.join("metadata");
+    std::fs::create_dir_all(&metadata).unwrap();
+    for root in roots {
+        root.write(&metadata, true).unwrap();
+    }
+    let last = roots.last().unwrap().signed().signed.version;
+    let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+    let mut editor = RepositoryEditor::new(metadata.join(format!("{}.root.json", last))).unwrap();
+    editor
+        .targets_version(NonZeroU64::new(1).unwrap())
+        .unwrap()
+        .targets_expires(expires)
+        .unwrap()
+        .snapshot_version(NonZeroU64::new(1).unwrap())
+        .snapshot_expires(expires)
+        .timestamp_version(NonZeroU64::new(1).unwrap())
+        .timestamp_expires(expires);
+    editor
+        .sign(&snakeoil_keys())
+        .unwrap()
+        .write(&metadata)
+        .unwrap();
+}
+
+fn load(dir: &Path) -> tough::error::Result<tough::Repository> {
+    repo_loader(dir).load()
+}
+
+/// Test that the loader follows a chain of root rotations to the latest root, where each new root
+/// is signed by the keys of the root before it and by its own.
+#[test]
+fn root_rotation_chain() {
+    let dir = TempDir::new().unwrap();
+    let root1 = trusted_root();
+    let root2 = rotate(&root1, "snakeoil_2.pem");
+    let root3 = rotate(&root2, "snakeoil_2.pem");
+    create_repo(
+        dir.path(),
+        &[
+            sign_root(
+                &root2,
+                &[(&root1, "snakeoil.pem"), (&root2, "snakeoil_2.pem")],
+            ),
+            sign_root(&root3, &[(&root3, "snakeoil_2.pem")]),
+        ],
+    );
+
+    let repo = load(dir.path()).unwrap();
+    assert_eq!(repo.root().signed.version.get(), 3);
+}
+
+/// Test that a new root that isn't signed by the keys of the trusted root is rejected.
+#[test]
+fn root_rotation_not_signed_by_trusted_root() {
+    let dir = TempDir::new().unwrap();
+    let root1 = trusted_root();
+    let root2 = rotate(&root1, "snakeoil_2.pem");
+    create_repo(
+        dir.path(),
+        &[sign_root(&root2, &[(&root2, "snakeoil_2.pem")])],
+    );
+
+    let err = load(dir.path()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::VerifyMetadata {
+                role: RoleType::Root,
+                ..
+            }
+        ),
+        "unexpected error: {}",
+        err
+    );
+}
+
+/// Test that a new root that isn't signed by its own keys is rejected.
+#[test]
+fn root_rotation_not_signed_by_new_root() {
+    let dir = TempDir::new().unwrap();
+    let root1 = trusted_root();
+    let root2 = rotate(&root1, "snakeoil_2.pem");
+    create_repo(
+        dir.path(),
+        &[sign_root(&root2, &[(&root1, "snakeoil.pem")])],
+    );
+
+    let err = load(dir.path()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::VerifyMetadata {
+                role: RoleType::Root,
+                ..
+            }
+        ),
+        "unexpected error: {}",
+        err
+    );
+}
@@ -0,0 +1,197 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::Mutex;
+use tempfile::TempDir;
+use test_utils::{dir_url, repo_loader, test_data, RepoBuilder};
+use tough::error::Error;
+use tough::{Repository, RepositoryLoader};
+
+mod test_utils;
+
+const CONTENTS: &[u8] = b"This target is saved to a file.\n";
+
+/// Creates a repository in `repo_dir` whose only target, `blob`, has the contents `CONTENTS`.
+fn create_repo(repo_dir: &Path) -> Repository {
+    let input = repo_dir.join("input");
+    std::fs::create_dir_all(&input).unwrap();
+    std::fs::write(input.join("blob"), CONTENTS).unwrap();
+    RepoBuilder::new()
+        .target_path(input.join("blob"))
+        .write(repo_dir);
+    repo_loader(repo_dir).load().unwrap()
+}
+
+/// Returns the names of the files in `dir`.
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Test that a verified target is saved to the destination, replacing any file already there.
+#[test]
+fn save_good_target() {
+    let repo_dir = TempDir::new().unwrap();
+    let repo = create_repo(repo_dir.path());
+    let out_dir = TempDir::new().unwrap();
+    let dest = out_dir.path().join("blob");
+
+    repo.save_target("blob", &dest).unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), CONTENTS);
+
+    std::fs::write(&dest, b"stale").unwrap();
+    repo.save_target("blob", &dest).unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), CONTENTS);
+    assert_eq!(file_names(out_dir.path()), ["blob"]);
+}
+
+/// Test that a target that fails verification leaves nothing behind, and doesn't replace an
+/// existing file.
+#[test]
+fn save_corrupted_target() {
+    let repo_dir = TempDir::new().unwrap();
+    let repo = create_repo(repo_dir.path());
+
+    // Corrupt the stored target without changing its length
+    let sha256 = hex::encode(&repo.targets().signed.targets["blob"].hashes.sha256);
+    let stored = repo_dir
+        .path()
+        .join("targets")
+        .join(format!("{}.blob", sha256));
+    let mut corrupted = CONTENTS.to_vec();
+    corrupted[0] ^= 0xff;
+    std::fs::remove_file(&stored).unwrap();
+    std::fs::write(&stored, &corrupted).unwrap();
+
+    let out_dir = TempDir::new().unwrap();
+    let dest = out_dir.path().join("blob");
+    let err = repo.save_target("blob", &dest).unwrap_err();
+    assert!(matches!(err, Error::SaveTarget { .. }), "{}", err);
+    assert!(file_names(out_dir.path()).is_empty());
+
+    std::fs::write(&dest, b"previous").unwrap();
+    repo.save_target("blob", &dest).unwrap_err();
+    assert_eq!(std::fs::read(&dest).unwrap(), b"previous");
+    assert_eq!(file_names(out_dir.path()), ["blob"]);
+}
+
+/// Test that saving a target that isn't listed is an error, and creates no file.
+#[test]
+fn save_missing_target() {
+    let repo_dir = TempDir::new().unwrap();
+    let repo = create_repo(repo_dir.path());
+    let out_dir = TempDir::new().unwrap();
+
+    let err = repo
+        .save_target("missing", out_dir.path().join("missing"))
+        .unwrap_err();
+    assert!(matches!(err, Error::SaveTargetMissing { .. }), "{}", err);
+    assert!(file_names(out_dir.path()).is_empty());
+}
+
+/// Loads `tuf-reference-impl`, whose targets are `file1.txt` and `file2.txt`, and `file3.txt`
+/// through a delegation, with its targets read from `targets_dir`.
+fn reference_repo(targets_dir: &Path) -> Repository {
+    let base = test_data().join("tuf-reference-impl");
+    RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(targets_dir),
+    )
+    .load()
+    .unwrap()
+}
+
+/// Test that several targets, including delegated ones, are saved concurrently, with progress
+/// reported for each.
+#[test]
+fn save_targets_in_parallel() {
+    let targets_dir = test_data().join("tuf-reference-impl").join("targets");
+    let repo = reference_repo(&targets_dir);
+    let out_dir = TempDir::new().unwrap();
+    let names = ["file1.txt", "file2.txt", "file3.txt"];
+
+    let saved = Mutex::new(Vec::new());
+    repo.save_targets(
+        &names,
+        out_dir.path(),
+        NonZeroUsize::new(2).unwrap(),
+        |name, result| {
+            assert!(result.is_ok());
+            saved.lock().unwrap().push(name.to_owned());
+        },
+    )
+    .unwrap();
+
+    let mut saved = saved.into_inner().unwrap();
+    saved.sort();
+    assert_eq!(saved, names);
+    assert_eq!(file_names(out_dir.path()), names);
+    for name in &names {
+        assert_eq!(
+            std::fs::read(out_dir.path().join(name)).unwrap(),
+            std::fs::read(targets_dir.join(name)).unwrap()
+        );
+    }
+}
+
+/// Test that a target that fails verification fails the whole call and isn't saved, and that no
+/// more targets are started after it.
+#[test]
+fn save_targets_corrupted() {
+    let targets_dir = TempDir::new().unwrap();
+    let source = test_data().join("tuf-reference-impl").join("targets");
+    for name in &["file1.txt", "file3.txt"] {
+        std::fs::copy(source.join(name), targets_dir.path().join(name)).unwrap();
+    }
+    std::fs::write(targets_dir.path().join("file2.txt"), "corrupted").unwrap();
+    let repo = reference_repo(targets_dir.path());
+    let out_dir = TempDir::new().unwrap();
+
+    let outcomes = Mutex::new(Vec::new());
+    let err = repo
+        .save_targets(
+            &["file1.txt", "file2.txt", "file3.txt"],
+            out_dir.path(),
+            NonZeroUsize::new(1).unwrap(),
+            |name, result| {
+                outcomes
+                    .lock()
+                    .unwrap()
+                    .push((name.to_owned(), result.is_ok()))
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, Error::SaveTarget { .. }), "{}", err);
+    assert_eq!(
+        outcomes.into_inner().unwrap(),
+        [
+            ("file1.txt".to_owned(), true),
+            ("file2.txt".to_owned(), false)
+        ]
+    );
+    assert_eq!(file_names(out_dir.path()), ["file1.txt"]);
+}
+
+/// Test that a target name that would be saved outside of the output directory is refused.
+#[test]
+fn save_targets_unsafe_name() {
+    let repo = reference_repo(&test_data().join("tuf-reference-impl").join("targets"));
+    let out_dir = TempDir::new().unwrap();
+    let dest = out_dir.path().join("dest");
+
+    for name in &["../file1.txt", "/etc/file1.txt", ""] {
+        let err = repo
+            .save_targets(&[name], &dest, NonZeroUsize::new(1).unwrap(), |_, _| {})
+            .unwrap_err();
+        assert!(matches!(err, Error::SaveTargetName { .. }), "{}", err);
+    }
+    assert!(file_names(out_dir.path()).is_empty());
+}
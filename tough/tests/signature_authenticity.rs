@@ -0,0 +1,116 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{Duration, Utc};
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::num::NonZeroU64;
+use test_utils::{root_path, test_data};
+use tough::key_source::{KeySource, LocalKeySource};
+use tough::schema::{Role, Root, Signature, Signed, Timestamp};
+use tough::sign::Sign;
+
+mod test_utils;
+
+// The root.json that corresponds with snakeoil.pem; snakeoil_2.pem isn't listed in it
+fn root() -> Root {
+    serde_json::from_reader::<_, Signed<Root>>(File::open(root_path()).unwrap())
+        .unwrap()
+        .signed
+}
+
+fn key(name: &str) -> Box<dyn Sign> {
+    LocalKeySource {
+        path: test_data().join(name),
+    }
+    .as_sign()
+    .unwrap()
+}
+
+fn timestamp(version: u64) -> Timestamp {
+    Timestamp::new(
+        "1.0.0".to_owned(),
+        NonZeroU64::new(version).unwrap(),
+        Utc::now().checked_add_signed(Duration::days(7)).unwrap(),
+    )
+}
+
+/// Signs `timestamp` with `signer`, claiming to be the key `claimed`.
+fn sign(timestamp: &Timestamp, signer: &dyn Sign, claimed: &dyn Sign) -> Signature {
+    let data = timestamp.canonical_form().unwrap();
+    Signature {
+        keyid: claimed.tuf_key().key_id().unwrap(),
+        sig: signer.sign(&data, &SystemRandom::new()).unwrap().into(),
+        created: None,
+    }
+}
+
+/// Test that a signature by a key that may not sign for the role is still authentic.
+#[test]
+fn authentic_unauthorized_signature() {
+    let root = root();
+    let unlisted = key("snakeoil_2.pem");
+    let timestamp = timestamp(1);
+    let signature = sign(&timestamp, unlisted.as_ref(), unlisted.as_ref());
+
+    assert!(unlisted
+        .tuf_key()
+        .verify_signature(&timestamp, &signature)
+        .unwrap());
+    assert!(root
+        .authentic_signers(&timestamp, &signature)
+        .unwrap()
+        .is_empty());
+    root.verify_role(&Signed {
+        signed: timestamp,
+        signatures: vec![signature],
+    })
+    .unwrap_err();
+}
+
+/// Test that a signature is checked against the key that made it, whatever key ID it claims.
+#[test]
+fn authentic_signer_misattributed() {
+    let root = root();
+    let listed = key("snakeoil.pem");
+    let timestamp = timestamp(1);
+    let signature = sign(&timestamp, listed.as_ref(), key("snakeoil_2.pem").as_ref());
+
+    let signers = root.authentic_signers(&timestamp, &signature).unwrap();
+    assert_eq!(signers.len(), 1);
+    assert_ne!(signers[0], &signature.keyid);
+    assert!(listed.is_listed_as(&root.keys[signers[0]]));
+}
+
+/// Test that signatures over other data, by another key, or that were altered aren't authentic.
+#[test]
+fn inauthentic_signatures() {
+    let listed = key("snakeoil.pem");
+    let timestamp = timestamp(1);
+    let signature = sign(&timestamp, listed.as_ref(), listed.as_ref());
+    assert!(listed
+        .tuf_key()
+        .verify_signature(&timestamp, &signature)
+        .unwrap());
+
+    assert!(!listed
+        .tuf_key()
+        .verify_signature(&self::timestamp(2), &signature)
+        .unwrap());
+    assert!(!key("snakeoil_2.pem")
+        .tuf_key()
+        .verify_signature(&timestamp, &signature)
+        .unwrap());
+    let mut altered = signature.clone();
+    let mut sig = altered.sig.to_vec();
+    sig[0] ^= 1;
+    altered.sig = sig.into();
+    assert!(!listed
+        .tuf_key()
+        .verify_signature(&timestamp, &altered)
+        .unwrap());
+    assert!(root()
+        .authentic_signers(&timestamp, &altered)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,87 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use ring::rand::SystemRandom;
+use std::fs::File;
+use test_utils::{root_path, snakeoil_keys};
+use tough::editor::signed::SignedRole;
+use tough::schema::{KeyHolder, Root, Signature, Signed};
+
+mod test_utils;
+
+fn root() -> Signed<Root> {
+    serde_json::from_reader(File::open(root_path()).unwrap()).unwrap()
+}
+
+/// Returns `signature` with its key ID and signature hex-encoded in uppercase.
+fn uppercase(signature: &Signature) -> Signature {
+    let mut value = serde_json::to_value(signature).unwrap();
+    for field in &["keyid", "sig"] {
+        let upper = value[field].as_str().unwrap().to_uppercase();
+        value[field] = upper.into();
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+/// Test that a key ID in uppercase hex matches the same key ID in lowercase, and that normalizing
+/// a signature writes it in lowercase.
+#[test]
+fn mixed_case_signature() {
+    let mut root = root();
+    let lowercase = root.signatures[0].clone();
+    let mut signature = uppercase(&lowercase);
+    assert_ne!(
+        serde_json::to_string(&signature).unwrap(),
+        serde_json::to_string(&lowercase).unwrap()
+    );
+    assert_eq!(signature, lowercase);
+    assert!(root.signed.keys.contains_key(&signature.keyid));
+
+    root.signatures = vec![signature.clone()];
+    root.signed.verify_role(&root).unwrap();
+
+    signature.normalize();
+    assert_eq!(
+        serde_json::to_string(&signature).unwrap(),
+        serde_json::to_string(&lowercase).unwrap()
+    );
+}
+
+/// Test that signatures added to a signed role are written in lowercase, and that one that differs
+/// from an existing signature only by case isn't added again.
+#[test]
+fn signed_role_normalizes_signatures() {
+    let root = root();
+    let signed = SignedRole::new(
+        root.signed.clone(),
+        &KeyHolder::Root(root.signed.clone()),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap();
+    let existing = signed.signed().signatures[0].clone();
+    let other: Signature = serde_json::from_value(serde_json::json!({
+        "keyid": "ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789",
+        "sig": "0A0B0C"
+    }))
+    .unwrap();
+
+    let signed = signed
+        .add_old_signatures(vec![uppercase(&existing), other])
+        .unwrap();
+    let written: Signed<Root> = serde_json::from_slice(signed.buffer()).unwrap();
+    let written = serde_json::to_value(&written.signatures).unwrap();
+    assert_eq!(
+        written,
+        serde_json::json!([
+            {
+                "keyid": serde_json::to_value(&existing.keyid).unwrap(),
+                "sig": serde_json::to_value(&existing.sig).unwrap()
+            },
+            {
+                "keyid": "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789",
+                "sig": "0a0b0c"
+            }
+        ])
+    );
+}
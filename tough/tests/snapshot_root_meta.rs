@@ -0,0 +1,66 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, resign_snapshot_and_timestamp, RepoBuilder};
+use tough::error::Error;
+use tough::schema::RoleType;
+use tough::Repository;
+
+mod test_utils;
+
+/// Writes a repository without targets into `dir`, with its snapshot following `spec_version`
+/// and listing root.json if `list_root` is true.
+fn create_repo(dir: &Path, spec_version: &str, list_root: bool) {
+    RepoBuilder::new().write(dir);
+    let metadata = dir.join("metadata");
+
+    // Sign snapshot again as the specification version requires, and point timestamp at it
+    resign_snapshot_and_timestamp(&metadata, |snapshot| {
+        snapshot.spec_version = spec_version.to_owned();
+        if list_root {
+            let mut root_meta = snapshot.meta["targets.json"].clone();
+            root_meta.hashes = None;
+            root_meta.length = None;
+            snapshot.meta.insert("root.json".to_owned(), root_meta);
+        }
+    });
+}
+
+fn load(dir: &Path) -> tough::error::Result<Repository> {
+    repo_loader(dir).load()
+}
+
+/// Test that a 1.0 snapshot loads whether or not it lists root.json.
+#[test]
+fn spec_1_0_root_optional() {
+    for &list_root in &[true, false] {
+        let dir = TempDir::new().unwrap();
+        create_repo(dir.path(), "1.0.0", list_root);
+        let repo = load(dir.path()).unwrap();
+        assert_eq!(
+            repo.snapshot().signed.meta.contains_key("root.json"),
+            list_root
+        );
+    }
+}
+
+/// Test that a snapshot following a draft from before 1.0 loads only if it lists root.json.
+#[test]
+fn pre_1_0_root_required() {
+    let dir = TempDir::new().unwrap();
+    create_repo(dir.path(), "0.9", true);
+    load(dir.path()).unwrap();
+
+    let dir = TempDir::new().unwrap();
+    create_repo(dir.path(), "0.9", false);
+    assert!(matches!(
+        load(dir.path()).unwrap_err(),
+        Error::MetaMissing {
+            file,
+            role: RoleType::Snapshot,
+            ..
+        } if file == "root.json"
+    ));
+}
@@ -0,0 +1,91 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{dir_url, read_to_end, repo_loader, test_data, RepoBuilder};
+use tough::error::Error;
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+fn targets_path() -> PathBuf {
+    test_data().join("tuf-reference-impl").join("targets")
+}
+
+/// Writes `version` of a consistent-snapshot repository containing the single target `target`
+/// into `repo_dir`, leaving any earlier versions in place.
+fn write_version(repo_dir: &Path, version: u64, target: &str) {
+    RepoBuilder::new()
+        .version(version)
+        .target_path(targets_path().join(target))
+        .write(repo_dir);
+}
+
+fn loader(repo_dir: &Path, datastore: &Path) -> RepositoryLoader<File> {
+    repo_loader(repo_dir).datastore(datastore)
+}
+
+/// Test that a client can pin to an older snapshot version, and that doing so doesn't interfere
+/// with later unpinned loads.
+#[test]
+fn pin_older_snapshot_version() {
+    let repo_dir = TempDir::new().unwrap();
+    let datastore = TempDir::new().unwrap();
+    write_version(repo_dir.path(), 1, "file1.txt");
+    write_version(repo_dir.path(), 2, "file2.txt");
+
+    let latest = loader(repo_dir.path(), datastore.path()).load().unwrap();
+    assert_eq!(latest.snapshot().signed.version.get(), 2);
+    assert!(latest.targets().signed.targets.contains_key("file2.txt"));
+
+    let pinned = loader(repo_dir.path(), datastore.path())
+        .snapshot_version(NonZeroU64::new(1).unwrap())
+        .load()
+        .unwrap();
+    assert_eq!(pinned.snapshot().signed.version.get(), 1);
+    assert_eq!(pinned.targets().signed.version.get(), 1);
+    assert!(pinned.targets().signed.targets.contains_key("file1.txt"));
+    assert!(!pinned.targets().signed.targets.contains_key("file2.txt"));
+    assert_eq!(
+        read_to_end(pinned.read_target("file1.txt").unwrap().unwrap()),
+        std::fs::read(targets_path().join("file1.txt")).unwrap()
+    );
+
+    let latest = loader(repo_dir.path(), datastore.path()).load().unwrap();
+    assert_eq!(latest.snapshot().signed.version.get(), 2);
+}
+
+/// Test that pinning to a snapshot version that was never published fails.
+#[test]
+fn pin_missing_snapshot_version() {
+    let repo_dir = TempDir::new().unwrap();
+    let datastore = TempDir::new().unwrap();
+    write_version(repo_dir.path(), 1, "file1.txt");
+
+    loader(repo_dir.path(), datastore.path())
+        .snapshot_version(NonZeroU64::new(3).unwrap())
+        .load()
+        .expect_err("snapshot version 3 does not exist");
+}
+
+/// Test that pinning a snapshot version requires consistent snapshots.
+#[test]
+fn pin_without_consistent_snapshot() {
+    let base = test_data().join("tuf-reference-impl");
+    let err = RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+    .snapshot_version(NonZeroU64::new(1).unwrap())
+    .load()
+    .unwrap_err();
+    assert!(
+        matches!(err, Error::SnapshotVersionRequiresConsistentSnapshot { .. }),
+        "unexpected error: {}",
+        err
+    );
+}
@@ -0,0 +1,134 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, test_data, RepoBuilder};
+use tough::editor::signed::PathExists;
+use tough::schema::Target;
+
+mod test_utils;
+
+fn targets_path() -> PathBuf {
+    test_data().join("tuf-reference-impl").join("targets")
+}
+
+/// Creates a repository in `outdir` whose target `file1.txt` has `storage_path` under
+/// `Target::STORAGE_PATH_FIELD`, and links the target into `outdir/targets`. The editor only uses
+/// the storage path if `use_storage_path` is true.
+fn link_repo(
+    outdir: &Path,
+    storage_path: &str,
+    use_storage_path: bool,
+) -> tough::error::Result<()> {
+    let mut target = Target::from_path(targets_path().join("file1.txt")).unwrap();
+    target.custom.insert(
+        Target::STORAGE_PATH_FIELD.to_owned(),
+        storage_path.to_owned().into(),
+    );
+
+    let builder = RepoBuilder::new();
+    let mut editor = builder.editor();
+    editor.add_target("file1.txt", target).unwrap();
+    let mut signed_repo = builder.write_editor(editor, outdir);
+    if use_storage_path {
+        signed_repo = signed_repo.storage_path_custom_key(Target::STORAGE_PATH_FIELD);
+    }
+    signed_repo.link_targets(targets_path(), outdir.join("targets"), PathExists::Skip)?;
+    Ok(())
+}
+
+/// Creates a repository in `outdir` whose target `file1.txt` is stored at `storage_path`.
+fn create_repo(outdir: &Path, storage_path: &str) -> tough::error::Result<()> {
+    link_repo(outdir, storage_path, true)
+}
+
+/// Test that a target is written to, and fetched from, its storage path rather than its name.
+#[test]
+fn read_target_from_storage_path() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path(), "blobs/one.bin").unwrap();
+    let targets_dir = repo_dir.path().join("targets");
+    assert!(targets_dir.join("blobs").join("one.bin").is_file());
+    assert!(!targets_dir.join("file1.txt").exists());
+
+    // Storage paths are only used if the loader is told which key they're under
+    let loader = || repo_loader(repo_dir.path());
+    let repo = loader().load().unwrap();
+    assert!(repo.read_target("file1.txt").is_err());
+
+    let repo = loader()
+        .storage_path_custom_key(Target::STORAGE_PATH_FIELD)
+        .load()
+        .unwrap();
+    assert_eq!(
+        read_to_end(repo.read_target("file1.txt").unwrap().unwrap()),
+        std::fs::read(targets_path().join("file1.txt")).unwrap()
+    );
+
+    // Caching the repository keeps the target at its storage path.
+    let cache_dir = TempDir::new().unwrap();
+    repo.cache(
+        cache_dir.path().join("metadata"),
+        cache_dir.path().join("targets"),
+        None::<&[&str]>,
+        false,
+    )
+    .unwrap();
+    assert!(cache_dir
+        .path()
+        .join("targets")
+        .join("blobs")
+        .join("one.bin")
+        .is_file());
+}
+
+/// Test that the editor ignores storage paths unless it's told which key they're under, so the
+/// target is written under its name and fetched by a default client.
+#[test]
+fn storage_path_not_used_without_key() {
+    let repo_dir = TempDir::new().unwrap();
+    link_repo(repo_dir.path(), "blobs/one.bin", false).unwrap();
+    let targets_dir = repo_dir.path().join("targets");
+    assert!(!targets_dir.join("blobs").exists());
+
+    let repo = repo_loader(repo_dir.path()).load().unwrap();
+    assert_eq!(
+        read_to_end(repo.read_target("file1.txt").unwrap().unwrap()),
+        std::fs::read(targets_path().join("file1.txt")).unwrap()
+    );
+}
+
+/// Test that the target's hash is still checked when it is fetched from its storage path.
+#[test]
+fn storage_path_hash_mismatch() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path(), "blobs/one.bin").unwrap();
+    let blob = repo_dir
+        .path()
+        .join("targets")
+        .join("blobs")
+        .join("one.bin");
+    std::fs::remove_file(&blob).unwrap();
+    std::fs::write(&blob, b"This is an example target file.\n").unwrap();
+
+    let repo = repo_loader(repo_dir.path())
+        .storage_path_custom_key(Target::STORAGE_PATH_FIELD)
+        .load()
+        .unwrap();
+    let mut reader = repo.read_target("file1.txt").unwrap().unwrap();
+    assert!(std::io::copy(&mut reader, &mut std::io::sink()).is_err());
+}
+
+/// Test that storage paths that would escape the targets directory are rejected.
+#[test]
+fn invalid_storage_path() {
+    for storage_path in &["../escape.bin", "/tmp/escape.bin", ""] {
+        let repo_dir = TempDir::new().unwrap();
+        assert!(
+            create_repo(repo_dir.path(), storage_path).is_err(),
+            "storage path '{}' should be rejected",
+            storage_path
+        );
+    }
+}
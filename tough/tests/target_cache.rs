@@ -0,0 +1,137 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, RepoBuilder};
+use tough::{FilesystemTransport, Repository, Transport, TransportError};
+use url::Url;
+
+mod test_utils;
+
+/// A transport that counts the targets it fetches.
+#[derive(Debug, Clone, Default)]
+struct CountingTransport {
+    targets_fetched: Arc<Mutex<usize>>,
+}
+
+impl CountingTransport {
+    fn targets_fetched(&self) -> usize {
+        *self.targets_fetched.lock().unwrap()
+    }
+}
+
+impl Transport for CountingTransport {
+    fn fetch(&self, url: Url) -> Result<Box<dyn Read + Send>, TransportError> {
+        if url.path().contains("/targets/") {
+            *self.targets_fetched.lock().unwrap() += 1;
+        }
+        FilesystemTransport.fetch(url)
+    }
+}
+
+/// Writes `version` of a repository in `dir` whose only target, `file.txt`, holds `contents`.
+fn write_repo(dir: &Path, version: u64, contents: &str) {
+    let input = TempDir::new().unwrap();
+    let target = input.path().join("file.txt");
+    fs::write(&target, contents).unwrap();
+    RepoBuilder::new()
+        .version(version)
+        .target_path(&target)
+        .write(dir);
+}
+
+fn load(dir: &Path, datastore: &Path, transport: &CountingTransport) -> Repository {
+    repo_loader(dir)
+        .transport(transport.clone())
+        .datastore(datastore)
+        .cache_targets(true)
+        .load()
+        .unwrap()
+}
+
+fn read(repo: &Repository) -> String {
+    String::from_utf8(read_to_end(repo.read_target("file.txt").unwrap().unwrap())).unwrap()
+}
+
+/// Test that a cached target is reused while its digest is unchanged, and fetched again once the
+/// targets metadata lists a different digest.
+#[test]
+fn target_cache_reuse() {
+    let repo_dir = TempDir::new().unwrap();
+    let datastore = TempDir::new().unwrap();
+    let transport = CountingTransport::default();
+
+    write_repo(repo_dir.path(), 1, "first");
+    assert_eq!(
+        read(&load(repo_dir.path(), datastore.path(), &transport)),
+        "first"
+    );
+    assert_eq!(transport.targets_fetched(), 1);
+
+    // A cache hit, even for a newly loaded repository, doesn't fetch the target
+    let repo = load(repo_dir.path(), datastore.path(), &transport);
+    assert_eq!(read(&repo), "first");
+    let saved = datastore.path().join("saved.txt");
+    repo.save_target("file.txt", &saved).unwrap();
+    assert_eq!(fs::read_to_string(&saved).unwrap(), "first");
+    assert_eq!(transport.targets_fetched(), 1);
+
+    // The target changes under the same name
+    write_repo(repo_dir.path(), 2, "second");
+    let repo = load(repo_dir.path(), datastore.path(), &transport);
+    assert_eq!(read(&repo), "second");
+    assert_eq!(transport.targets_fetched(), 2);
+    assert_eq!(read(&repo), "second");
+    assert_eq!(transport.targets_fetched(), 2);
+}
+
+/// Test that a cached target that no longer matches its digest is fetched again, and replaced.
+#[test]
+fn target_cache_tampered() {
+    let repo_dir = TempDir::new().unwrap();
+    let datastore = TempDir::new().unwrap();
+    let transport = CountingTransport::default();
+
+    write_repo(repo_dir.path(), 1, "first");
+    let repo = load(repo_dir.path(), datastore.path(), &transport);
+    assert_eq!(read(&repo), "first");
+
+    let cached = fs::read_dir(datastore.path().join("target-cache"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(cached.len(), 1);
+    fs::write(&cached[0], "tampered").unwrap();
+
+    assert_eq!(read(&repo), "first");
+    assert_eq!(transport.targets_fetched(), 2);
+    assert_eq!(fs::read_to_string(&cached[0]).unwrap(), "first");
+    assert_eq!(read(&repo), "first");
+    assert_eq!(transport.targets_fetched(), 2);
+}
+
+/// Test that a target that isn't read to the end isn't cached.
+#[test]
+fn target_cache_partial_read() {
+    let repo_dir = TempDir::new().unwrap();
+    let datastore = TempDir::new().unwrap();
+    let transport = CountingTransport::default();
+
+    write_repo(repo_dir.path(), 1, "first");
+    let repo = load(repo_dir.path(), datastore.path(), &transport);
+    let mut reader = repo.read_target("file.txt").unwrap().unwrap();
+    reader.read_exact(&mut [0; 2]).unwrap();
+    drop(reader);
+    assert_eq!(
+        fs::read_dir(datastore.path().join("target-cache"))
+            .unwrap()
+            .count(),
+        0
+    );
+    assert_eq!(read(&repo), "first");
+    assert_eq!(transport.targets_fetched(), 2);
+}
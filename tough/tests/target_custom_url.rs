@@ -0,0 +1,81 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, test_data, RepoBuilder};
+use tough::error::Error;
+use tough::schema::Target;
+use tough::Repository;
+use url::Url;
+
+mod test_utils;
+
+fn target_path() -> PathBuf {
+    test_data()
+        .join("tuf-reference-impl")
+        .join("targets")
+        .join("file1.txt")
+}
+
+/// Writes the metadata of a repository into `dir` that lists `file1.txt`, downloaded from `url`,
+/// and `other.txt`, whose `custom` URL isn't absolute. Neither is under the targets directory.
+fn write_repo(dir: &Path, url: &Url) {
+    let mut target = Target::from_path(target_path()).unwrap();
+    target.custom.insert("url".to_owned(), url.as_str().into());
+    let mut other = Target::from_path(target_path()).unwrap();
+    other.custom.insert("url".to_owned(), "other.txt".into());
+
+    let builder = RepoBuilder::new();
+    let mut editor = builder.editor();
+    editor
+        .add_target("file1.txt", target)
+        .unwrap()
+        .add_target("other.txt", other)
+        .unwrap();
+    builder.write_editor(editor, dir);
+}
+
+fn load(dir: &Path, custom_key: Option<&str>) -> Repository {
+    let mut loader = repo_loader(dir);
+    if let Some(key) = custom_key {
+        loader = loader.target_url_custom_key(key);
+    }
+    loader.load().unwrap()
+}
+
+/// Test that a target is downloaded from the absolute URL in its `custom` object, and verified.
+#[test]
+fn target_custom_url() {
+    let repo_dir = TempDir::new().unwrap();
+    let object_store = TempDir::new().unwrap();
+    let hosted = object_store.path().join("file1.txt");
+    std::fs::copy(target_path(), &hosted).unwrap();
+    write_repo(repo_dir.path(), &Url::from_file_path(&hosted).unwrap());
+
+    // By default, the target is looked for under the targets base URL
+    assert!(matches!(
+        load(repo_dir.path(), None).read_target("file1.txt"),
+        Err(Error::Transport { .. })
+    ));
+
+    let repo = load(repo_dir.path(), Some("url"));
+    assert_eq!(
+        read_to_end(repo.read_target("file1.txt").unwrap().unwrap()),
+        std::fs::read(target_path()).unwrap()
+    );
+    assert!(matches!(
+        repo.read_target("other.txt"),
+        Err(Error::TargetCustomUrl { key, value, .. }) if key == "url" && value == "\"other.txt\""
+    ));
+
+    // The target is still verified against the targets metadata
+    std::fs::write(&hosted, "tampered").unwrap();
+    let mut contents = Vec::new();
+    repo.read_target("file1.txt")
+        .unwrap()
+        .unwrap()
+        .read_to_end(&mut contents)
+        .unwrap_err();
+}
@@ -0,0 +1,42 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// A file name can only contain `\` on unix; on Windows, it's a path separator
+#![cfg(unix)]
+
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, test_data, RepoBuilder};
+use tough::editor::RepositoryEditor;
+
+mod test_utils;
+
+/// Test that a target added from a file whose name contains `\` is listed, written, and fetched
+/// under that name, since `\` isn't a path separator on unix.
+#[test]
+fn backslash_target_name() {
+    let repo_dir = TempDir::new().unwrap();
+    let input = repo_dir.path().join("dir\\file1.txt");
+    std::fs::copy(
+        test_data()
+            .join("tuf-reference-impl")
+            .join("targets")
+            .join("file1.txt"),
+        &input,
+    )
+    .unwrap();
+    let (name, _) = RepositoryEditor::build_target(&input).unwrap();
+    assert_eq!(name, "dir\\file1.txt");
+
+    RepoBuilder::new()
+        .target_path(&input)
+        .write(repo_dir.path());
+
+    let repo = repo_loader(repo_dir.path()).load().unwrap();
+    assert!(repo.targets().signed.targets.contains_key("dir\\file1.txt"));
+    assert_eq!(
+        read_to_end(repo.read_target("dir\\file1.txt").unwrap().unwrap()),
+        std::fs::read(&input).unwrap()
+    );
+    // The client looks up the name it's asked for as is
+    assert!(repo.read_target("dir/file1.txt").unwrap().is_none());
+}
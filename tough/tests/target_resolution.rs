@@ -0,0 +1,133 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, resign_snapshot_and_timestamp, sign, test_data, RepoBuilder};
+use tough::error::TargetResolutionError;
+use tough::schema::{Signed, Target, Targets};
+use tough::Repository;
+
+mod test_utils;
+
+fn target() -> Target {
+    Target::from_path(
+        test_data()
+            .join("tuf-reference-impl")
+            .join("targets")
+            .join("file1.txt"),
+    )
+    .unwrap()
+}
+
+/// Writes a repository into `dir` whose targets delegate, in order:
+/// * `term/*` to `first`, a terminating delegation that lists `term/a.txt`,
+/// * `term/*` to `second`, which lists `term/b.txt` and `outside.txt`, and
+/// * `gone/*` to `third`, which lists `gone/c.txt`.
+fn create_repo(dir: &Path) {
+    let builder = RepoBuilder::new();
+    let mut editor = builder.editor();
+    for (role, path, targets) in &[
+        ("first", "term/*", vec!["term/a.txt"]),
+        ("second", "term/*", vec!["term/b.txt", "outside.txt"]),
+        ("third", "gone/*", vec!["gone/c.txt"]),
+    ] {
+        let targets = targets.iter().map(|name| (*name, target())).collect();
+        builder.delegate_role(&mut editor, role, "targetskey", &[path], targets);
+    }
+    builder.write_editor(editor, dir);
+    let metadata = &dir.join("metadata");
+
+    // Make `first` terminating, and point snapshot and timestamp at the result
+    let mut targets: Signed<Targets> =
+        serde_json::from_reader(File::open(metadata.join("1.targets.json")).unwrap()).unwrap();
+    let delegations = targets.signed.delegations.as_mut().unwrap();
+    delegations.roles[0].terminating = true;
+    let targets = sign(targets.signed);
+    targets.write(metadata, true).unwrap();
+
+    resign_snapshot_and_timestamp(metadata, |snapshot| {
+        let meta = snapshot.meta.get_mut("targets.json").unwrap();
+        meta.length = Some(*targets.length());
+        meta.hashes.as_mut().unwrap().sha256 = targets.sha256().to_vec().into();
+    });
+}
+
+fn load(dir: &Path) -> Repository {
+    repo_loader(dir)
+        .skip_unreachable_delegations(true)
+        .load()
+        .unwrap()
+}
+
+/// Test that each reason a target can't be resolved is reported, and that targets that can't be
+/// resolved aren't read or cached.
+#[test]
+fn target_resolution_errors() {
+    let dir = TempDir::new().unwrap();
+    create_repo(dir.path());
+    std::fs::remove_file(dir.path().join("metadata").join("1.third.json")).unwrap();
+    let repo = load(dir.path());
+
+    assert_eq!(repo.resolve_target("term/a.txt").unwrap(), &target());
+    assert_eq!(
+        repo.resolve_target("missing.txt").unwrap_err(),
+        TargetResolutionError::NotListed {
+            target: "missing.txt".to_owned()
+        }
+    );
+    assert_eq!(
+        repo.resolve_target("term/b.txt").unwrap_err(),
+        TargetResolutionError::ExcludedByTerminating {
+            target: "term/b.txt".to_owned(),
+            role: "first".to_owned(),
+            listed_by: "second".to_owned(),
+        }
+    );
+    assert_eq!(
+        repo.resolve_target("outside.txt").unwrap_err(),
+        TargetResolutionError::OutsideDelegatedPaths {
+            target: "outside.txt".to_owned(),
+            role: "second".to_owned(),
+        }
+    );
+    assert_eq!(
+        repo.resolve_target("gone/c.txt").unwrap_err(),
+        TargetResolutionError::Unreachable {
+            target: "gone/c.txt".to_owned(),
+            role: "third".to_owned(),
+        }
+    );
+
+    // The targets are all present, so only resolution stops them being cached
+    let source = test_data()
+        .join("tuf-reference-impl")
+        .join("targets")
+        .join("file1.txt");
+    for name in &["term/a.txt", "term/b.txt", "outside.txt", "gone/c.txt"] {
+        let path = dir
+            .path()
+            .join("targets")
+            .join(target().consistent_name(name));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::copy(&source, path).unwrap();
+    }
+    let cache = TempDir::new().unwrap();
+    for name in &["missing.txt", "term/b.txt", "outside.txt", "gone/c.txt"] {
+        assert!(repo.read_target(name).unwrap().is_none());
+        assert!(repo
+            .cache(
+                cache.path().join("metadata"),
+                cache.path().join("targets"),
+                Some(&[name]),
+                false,
+            )
+            .is_err());
+        assert!(!cache
+            .path()
+            .join("targets")
+            .join(target().consistent_name(name))
+            .exists());
+    }
+}
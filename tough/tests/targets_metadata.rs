@@ -0,0 +1,107 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use std::fs::File;
+use std::num::NonZeroU64;
+use test_utils::{dir_url, test_data};
+use tough::schema::decoded::{Decoded, Hex};
+use tough::schema::{PathSet, Role, Targets};
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+/// Test that the verified targets metadata matches the contents of the repository's targets.json.
+#[test]
+fn targets_metadata() {
+    let base = test_data().join("tuf-reference-impl");
+    let repo = RepositoryLoader::new(
+        File::open(base.join("metadata").join("1.root.json")).unwrap(),
+        dir_url(base.join("metadata")),
+        dir_url(base.join("targets")),
+    )
+    .load()
+    .unwrap();
+
+    let targets = repo.targets_metadata();
+    assert_eq!(targets.spec_version, "1.0.0");
+    assert_eq!(targets.version, NonZeroU64::new(1).unwrap());
+    assert_eq!(targets.expires, Utc.ymd(2030, 1, 1).and_hms(0, 0, 0));
+    assert!(targets._extra.is_empty());
+
+    let mut names = targets.targets.keys().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["file1.txt", "file2.txt"]);
+    let file1 = &targets.targets["file1.txt"];
+    assert_eq!(file1.length, 31);
+    assert_eq!(
+        hex::encode(&file1.hashes.sha256),
+        "65b8c67f51c993d898250f40aa57a317d854900b3a04895464313e48785440da"
+    );
+    assert_eq!(file1.custom["file_permissions"], json!("0644"));
+    let file2 = &targets.targets["file2.txt"];
+    assert_eq!(file2.length, 39);
+    assert_eq!(
+        hex::encode(&file2.hashes.sha256),
+        "452ce8308500d83ef44248d8e6062359211992fd837ea9e370e561efb1a4ca99"
+    );
+    assert!(file2.custom.is_empty());
+
+    let delegations = targets.delegations.as_ref().unwrap();
+    let keyid: Decoded<Hex> = "c8022fa1e9b9cb239a6b362bbdffa9649e61ad2cb699d2e4bc4fdf7930a0e64a"
+        .parse()
+        .unwrap();
+    assert_eq!(delegations.keys.len(), 1);
+    assert!(delegations.keys.contains_key(&keyid));
+    assert_eq!(delegations.roles.len(), 1);
+    let role = &delegations.roles[0];
+    assert_eq!(role.name, "role1");
+    assert_eq!(role.keyids, [keyid]);
+    assert_eq!(role.threshold, NonZeroU64::new(1).unwrap());
+    assert_eq!(role.paths, PathSet::Paths(vec!["file3.txt".to_owned()]));
+    assert!(!role.terminating);
+    assert!(role.targets.is_some());
+    assert!(delegations._extra.is_empty());
+    assert!(role._extra.is_empty());
+
+    // It's the same metadata as the signed targets role
+    assert_eq!(targets, &repo.targets().signed);
+}
+
+/// Test that fields added by other implementations survive a round trip through the schema types,
+/// so that the canonical form, and so any signature over it, is unchanged.
+#[test]
+fn targets_unknown_fields_round_trip() {
+    let original = json!({
+        "_type": "targets",
+        "spec_version": "1.0.0",
+        "version": 1,
+        "expires": "2030-01-01T00:00:00Z",
+        "targets": {},
+        "x-targets": "extra",
+        "delegations": {
+            "keys": {},
+            "x-delegations": [1, 2],
+            "roles": [{
+                "name": "role1",
+                "keyids": [],
+                "threshold": 1,
+                "path_hash_prefixes": ["ab"],
+                "terminating": true,
+                "x-role": {"nested": true}
+            }]
+        }
+    });
+    let targets: Targets = serde_json::from_value(original.clone()).unwrap();
+    let delegations = targets.delegations.as_ref().unwrap();
+    assert_eq!(delegations._extra["x-delegations"], json!([1, 2]));
+    let role = &delegations.roles[0];
+    assert_eq!(role.paths, PathSet::PathHashPrefixes(vec!["ab".to_owned()]));
+    assert_eq!(role._extra.len(), 1);
+    assert_eq!(role._extra["x-role"], json!({"nested": true}));
+
+    assert_eq!(serde_json::to_value(&targets).unwrap(), original);
+    let reparsed: Targets = serde_json::from_slice(&targets.canonical_form().unwrap()).unwrap();
+    assert_eq!(reparsed, targets);
+}
@@ -1,8 +1,17 @@
 // Copyright 2020 Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::SystemRandom;
+use std::fs::File;
 use std::io::Read;
+use std::num::NonZeroU64;
 use std::path::{Path, PathBuf};
+use tough::editor::signed::{PathExists, SignedRepository, SignedRole};
+use tough::editor::RepositoryEditor;
+use tough::key_source::{KeySource, LocalKeySource};
+use tough::schema::{KeyHolder, PathSet, Role, Root, Signed, Snapshot, Target, Timestamp};
+use tough::RepositoryLoader;
 use url::Url;
 
 /// Utilities for tests. Not every test module uses every function, so we suppress unused warnings.
@@ -28,3 +37,208 @@ pub fn read_to_end<R: Read>(mut reader: R) -> Vec<u8> {
     reader.read_to_end(&mut v).unwrap();
     v
 }
+
+/// Path to the root.json that corresponds with snakeoil.pem. It uses consistent snapshots.
+#[allow(unused)]
+pub fn root_path() -> PathBuf {
+    test_data().join("simple-rsa").join("root.json")
+}
+
+/// The key in the test data file `name`
+#[allow(unused)]
+pub fn key(name: &str) -> Vec<Box<dyn KeySource>> {
+    vec![Box::new(LocalKeySource {
+        path: test_data().join(name),
+    })]
+}
+
+/// The key that signs every role of the root.json at `root_path`
+#[allow(unused)]
+pub fn snakeoil_keys() -> Vec<Box<dyn KeySource>> {
+    key("snakeoil.pem")
+}
+
+/// Signs `role` with `snakeoil_keys`, as the root.json at `root_path` requires
+#[allow(unused)]
+pub fn sign<T: Role + serde::Serialize + Clone>(role: T) -> SignedRole<T> {
+    let root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    SignedRole::new(
+        role,
+        &KeyHolder::Root(root.signed),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap()
+}
+
+/// Lets `edit` change the version 1 snapshot in the consistent snapshot `metadata` directory,
+/// re-signs it, and points the timestamp at the result
+#[allow(unused)]
+pub fn resign_snapshot_and_timestamp<F: FnOnce(&mut Snapshot)>(metadata: &Path, edit: F) {
+    let mut snapshot: Signed<Snapshot> =
+        serde_json::from_reader(File::open(metadata.join("1.snapshot.json")).unwrap()).unwrap();
+    edit(&mut snapshot.signed);
+    let snapshot = sign(snapshot.signed);
+    snapshot.write(metadata, true).unwrap();
+
+    let mut timestamp: Signed<Timestamp> =
+        serde_json::from_reader(File::open(metadata.join("timestamp.json")).unwrap()).unwrap();
+    let meta = timestamp.signed.meta.get_mut("snapshot.json").unwrap();
+    meta.length = *snapshot.length();
+    meta.hashes.sha256 = snapshot.sha256().to_vec().into();
+    sign(timestamp.signed).write(metadata, true).unwrap();
+}
+
+/// Returns a loader for the repository written by `RepoBuilder::write` to `repo_dir`, trusting the
+/// root.json at `root_path`
+#[allow(unused)]
+pub fn repo_loader(repo_dir: &Path) -> RepositoryLoader<File> {
+    RepositoryLoader::new(
+        File::open(root_path()).unwrap(),
+        dir_url(repo_dir.join("metadata")),
+        dir_url(repo_dir.join("targets")),
+    )
+}
+
+/// Builds a repository under the root.json at `root_path`, signed with `snakeoil_keys`. Each role
+/// has the same version and expiration.
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct RepoBuilder {
+    version: NonZeroU64,
+    expires: DateTime<Utc>,
+    target_paths: Vec<PathBuf>,
+}
+
+#[allow(unused)]
+impl RepoBuilder {
+    /// A repository at version 1, expiring in a week, with no targets
+    pub fn new() -> Self {
+        Self {
+            version: NonZeroU64::new(1).unwrap(),
+            expires: Utc::now().checked_add_signed(Duration::days(7)).unwrap(),
+            target_paths: Vec::new(),
+        }
+    }
+
+    /// Sets the version of each role
+    pub fn version(mut self, version: u64) -> Self {
+        self.version = NonZeroU64::new(version).unwrap();
+        self
+    }
+
+    /// Sets when each role expires
+    pub fn expires(mut self, expires: DateTime<Utc>) -> Self {
+        self.expires = expires;
+        self
+    }
+
+    /// Adds the file at `path` as a target named by its file name
+    pub fn target_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.target_paths.push(path.into());
+        self
+    }
+
+    /// Returns an editor with each role's version and expiration, and the targets, set, which a
+    /// test may change further before signing it with `write_editor`
+    pub fn editor(&self) -> RepositoryEditor {
+        let mut editor = RepositoryEditor::new(root_path()).unwrap();
+        editor
+            .targets_version(self.version)
+            .unwrap()
+            .targets_expires(self.expires)
+            .unwrap()
+            .snapshot_version(self.version)
+            .snapshot_expires(self.expires)
+            .timestamp_version(self.version)
+            .timestamp_expires(self.expires);
+        for path in &self.target_paths {
+            editor.add_target_path(path).unwrap();
+        }
+        editor
+    }
+
+    /// Returns an editor, as `editor` does, that delegates `file1.txt` to `role1`, signed with the
+    /// key in `targetskey`
+    pub fn delegating_editor(&self) -> RepositoryEditor {
+        let mut editor = self.editor();
+        let target = Target::from_path(
+            test_data()
+                .join("tuf-reference-impl")
+                .join("targets")
+                .join("file1.txt"),
+        )
+        .unwrap();
+        self.delegate_role(
+            &mut editor,
+            "role1",
+            "targetskey",
+            &["file1.txt"],
+            vec![("file1.txt", target)],
+        );
+        editor
+    }
+
+    /// Delegates `paths` from the top-level targets of `editor` to `role`, whose key is in the test
+    /// data file `key_name`, and signs `role` listing `targets` with the builder's version and
+    /// expiration. Leaves `editor` editing the top-level targets.
+    pub fn delegate_role(
+        &self,
+        editor: &mut RepositoryEditor,
+        role: &str,
+        key_name: &str,
+        paths: &[&str],
+        targets: Vec<(&str, Target)>,
+    ) {
+        editor
+            .delegate_role(
+                role,
+                &key(key_name),
+                PathSet::Paths(paths.iter().map(|path| (*path).to_owned()).collect()),
+                NonZeroU64::new(1).unwrap(),
+                self.expires,
+                self.version,
+            )
+            .unwrap()
+            .sign_targets_editor(&snakeoil_keys())
+            .unwrap()
+            .change_delegated_targets(role)
+            .unwrap();
+        for (name, target) in targets {
+            editor.add_target(name, target).unwrap();
+        }
+        editor
+            .targets_version(self.version)
+            .unwrap()
+            .targets_expires(self.expires)
+            .unwrap()
+            .sign_targets_editor(&key(key_name))
+            .unwrap()
+            .change_delegated_targets("targets")
+            .unwrap()
+            .targets_version(self.version)
+            .unwrap()
+            .targets_expires(self.expires)
+            .unwrap();
+    }
+
+    /// Signs the repository, writes its metadata to `repo_dir/metadata`, and copies its targets
+    /// to `repo_dir/targets`
+    pub fn write(&self, repo_dir: &Path) -> SignedRepository {
+        self.write_editor(self.editor(), repo_dir)
+    }
+
+    /// Signs `editor`, as returned by `editor`, and writes it as `write` does
+    pub fn write_editor(&self, editor: RepositoryEditor, repo_dir: &Path) -> SignedRepository {
+        let signed = editor.sign(&snakeoil_keys()).unwrap();
+        let targets_dir = repo_dir.join("targets");
+        std::fs::create_dir_all(&targets_dir).unwrap();
+        for path in &self.target_paths {
+            signed
+                .copy_target(path, &targets_dir, PathExists::Skip, None)
+                .unwrap();
+        }
+        signed.write(repo_dir.join("metadata")).unwrap();
+        signed
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use tough::schema::{Hashes, Snapshot, Timestamp};
+
+/// Test that the timestamp describes the snapshot: its version, and the length and hashes it's
+/// given.
+#[test]
+fn timestamp_from_snapshot() {
+    let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+    let snapshot = Snapshot::new("1.0.0".to_owned(), NonZeroU64::new(5).unwrap(), expires);
+    let hashes = Hashes {
+        sha256: vec![0xab; 32].into(),
+        _extra: HashMap::new(),
+    };
+
+    let timestamp = Timestamp::from_snapshot(
+        &snapshot,
+        1234,
+        hashes.clone(),
+        NonZeroU64::new(9).unwrap(),
+        expires,
+    );
+    assert_eq!(timestamp.version.get(), 9);
+    assert_eq!(timestamp.expires, expires);
+    assert_eq!(timestamp.spec_version, "1.0.0");
+    let meta = timestamp.snapshot_meta().unwrap();
+    assert_eq!(meta.version.get(), 5);
+    assert_eq!(meta.length, 1234);
+    assert_eq!(meta.hashes, hashes);
+}
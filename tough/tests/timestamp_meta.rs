@@ -0,0 +1,99 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{repo_loader, root_path, snakeoil_keys, RepoBuilder};
+use tough::editor::signed::SignedRole;
+use tough::error::Error;
+use tough::schema::{KeyHolder, RoleType, Root, Signed, Timestamp};
+use tough::Repository;
+
+mod test_utils;
+
+/// Changes the timestamp's meta with `edit`, and signs the timestamp again.
+fn edit_timestamp_meta<F>(dir: &Path, edit: F) -> Timestamp
+where
+    F: FnOnce(&mut Timestamp),
+{
+    let metadata = dir.join("metadata");
+    let root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    let mut timestamp: Signed<Timestamp> =
+        serde_json::from_reader(File::open(metadata.join("timestamp.json")).unwrap()).unwrap();
+    edit(&mut timestamp.signed);
+    SignedRole::new(
+        timestamp.signed.clone(),
+        &KeyHolder::Root(root.signed),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap()
+    .write(&metadata, true)
+    .unwrap();
+    timestamp.signed
+}
+
+fn load(dir: &Path) -> tough::error::Result<Repository> {
+    repo_loader(dir).load()
+}
+
+fn assert_timestamp_meta_error(err: Error, found: &str) {
+    assert!(
+        matches!(
+            err,
+            Error::VerifyMetadata {
+                role: RoleType::Timestamp,
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+    assert!(
+        err.to_string().contains(&format!(
+            "must describe only snapshot.json, found {}",
+            found
+        )),
+        "{}",
+        err
+    );
+}
+
+/// Test that a timestamp without a description of snapshot.json is rejected.
+#[test]
+fn timestamp_missing_snapshot_meta() {
+    let dir = TempDir::new().unwrap();
+    RepoBuilder::new().write(dir.path());
+    load(dir.path()).unwrap();
+
+    let timestamp = edit_timestamp_meta(dir.path(), |timestamp| {
+        let snapshot_meta = timestamp.meta.remove("snapshot.json").unwrap();
+        timestamp
+            .meta
+            .insert("1.snapshot.json".to_owned(), snapshot_meta);
+    });
+    assert!(timestamp.snapshot_meta().is_err());
+    assert_timestamp_meta_error(load(dir.path()).unwrap_err(), r#"["1.snapshot.json"]"#);
+}
+
+/// Test that a timestamp describing files other than snapshot.json is rejected, even though it
+/// describes snapshot.json correctly.
+#[test]
+fn timestamp_extra_meta() {
+    let dir = TempDir::new().unwrap();
+    RepoBuilder::new().write(dir.path());
+
+    let timestamp = edit_timestamp_meta(dir.path(), |timestamp| {
+        let snapshot_meta = timestamp.meta["snapshot.json"].clone();
+        timestamp
+            .meta
+            .insert("bogus.json".to_owned(), snapshot_meta);
+    });
+    assert!(timestamp.snapshot_meta().is_err());
+    assert_timestamp_meta_error(
+        load(dir.path()).unwrap_err(),
+        r#"["bogus.json", "snapshot.json"]"#,
+    );
+}
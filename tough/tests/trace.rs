@@ -0,0 +1,88 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fs::File;
+use std::sync::Mutex;
+use tempfile::TempDir;
+use test_utils::{repo_loader, RepoBuilder};
+use tough::schema::{Signed, Snapshot};
+
+mod test_utils;
+
+/// Collects the messages logged to the `tough::trace` target.
+struct TraceLogger(Mutex<Vec<String>>);
+
+impl Log for TraceLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "tough::trace" && metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: TraceLogger = TraceLogger(Mutex::new(Vec::new()));
+
+/// Test that the trace of a load that fails because the wrong targets file was fetched points at
+/// the mismatched file, after showing that the files before it were fetched and verified.
+#[test]
+fn trace_hash_mismatch() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    let repo_dir = TempDir::new().unwrap();
+    RepoBuilder::new().write(repo_dir.path());
+    let metadata = repo_dir.path().join("metadata");
+    // Whitespace changes the digest but not the length or parsed contents
+    let targets_path = metadata.join("1.targets.json");
+    let targets = std::fs::read_to_string(&targets_path).unwrap();
+    assert!(targets.contains("\n "));
+    std::fs::write(&targets_path, targets.replacen("\n ", "\n\t", 1)).unwrap();
+
+    repo_loader(repo_dir.path()).load().unwrap_err();
+
+    let snapshot: Signed<Snapshot> =
+        serde_json::from_reader(File::open(metadata.join("1.snapshot.json")).unwrap()).unwrap();
+    let expected = hex::encode(
+        &snapshot.signed.meta["targets.json"]
+            .hashes
+            .as_ref()
+            .unwrap()
+            .sha256,
+    );
+    let trace = LOGGER.0.lock().unwrap();
+    let find = |pattern: &str| {
+        trace
+            .iter()
+            .find(|line| line.contains(pattern))
+            .unwrap_or_else(|| panic!("no trace line contains {:?} in {:#?}", pattern, trace))
+    };
+
+    assert!(find("/timestamp.json: ").contains("expected sha256 unknown"));
+    find("signatures on timestamp.json: verified");
+    assert!(find("/1.snapshot.json: ").contains("(match)"));
+    find("signatures on 1.snapshot.json: verified");
+
+    let targets = find("/1.targets.json: ");
+    let length = std::fs::metadata(&targets_path).unwrap().len();
+    assert!(
+        targets.contains(&format!(": {} bytes with sha256", length)),
+        "{}",
+        targets
+    );
+    assert!(
+        targets.contains(&format!("expected sha256 {} (MISMATCH)", expected)),
+        "{}",
+        targets
+    );
+    // The targets metadata was rejected before its signatures were checked
+    assert!(!trace
+        .iter()
+        .any(|line| line.contains("signatures on 1.targets.json")));
+}
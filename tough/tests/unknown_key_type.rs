@@ -0,0 +1,132 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use chrono::{Duration, Utc};
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{dir_url, root_path, snakeoil_keys};
+use tough::editor::signed::SignedRole;
+use tough::editor::RepositoryEditor;
+use tough::schema::decoded::{Decoded, Hex};
+use tough::schema::key::Key;
+use tough::schema::{KeyHolder, RoleType, Root, Signature, Signed};
+use tough::RepositoryLoader;
+
+mod test_utils;
+
+fn unknown_key() -> Key {
+    serde_json::from_str(
+        r#"{
+            "keytype": "sphincs-plus",
+            "scheme": "sphincs-shake-256s",
+            "keyval": { "public": "0123456789abcdef" }
+        }"#,
+    )
+    .unwrap()
+}
+
+/// Writes a root.json that trusts both snakeoil's RSA key and a key of an unknown type for the
+/// root role, with the given threshold, signed by snakeoil only. Returns the unknown key's ID.
+fn write_root(path: &Path, threshold: u64) -> Decoded<Hex> {
+    let mut root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    let key = unknown_key();
+    let key_id = key.key_id().unwrap();
+    root.signed.keys.insert(key_id.clone(), key);
+    let role_keys = root.signed.roles.get_mut(&RoleType::Root).unwrap();
+    role_keys.keyids.push(key_id.clone());
+    role_keys.threshold = NonZeroU64::new(threshold).unwrap();
+
+    let signed = SignedRole::new(
+        root.signed.clone(),
+        &KeyHolder::Root(root.signed),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap();
+    std::fs::write(path, signed.buffer()).unwrap();
+    key_id
+}
+
+/// Test that a key of an unknown type is read and written back exactly, keeping its key ID.
+#[test]
+fn unknown_key_round_trip() {
+    let key = unknown_key();
+    assert!(matches!(&key, Key::Unknown { keytype, .. } if keytype == "sphincs-plus"));
+    let again: Key = serde_json::from_str(&serde_json::to_string(&key).unwrap()).unwrap();
+    assert_eq!(again, key);
+    assert_eq!(again.key_id().unwrap(), key.key_id().unwrap());
+    assert!(key.to_pem().is_none());
+}
+
+/// Test that a malformed key of a known type is still an error.
+#[test]
+fn malformed_known_key() {
+    serde_json::from_str::<Key>(
+        r#"{
+            "keytype": "rsa",
+            "scheme": "rsassa-pss-sha256",
+            "keyval": { "public": "not a PEM" }
+        }"#,
+    )
+    .unwrap_err();
+}
+
+/// Test that a repository loads when its root lists a key of an unknown type, as long as the
+/// recognized keys meet the threshold.
+#[test]
+fn unknown_key_threshold_met() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("root.json");
+    let metadata = dir.path().join("metadata");
+    write_root(&root, 1);
+
+    let expires = Utc::now().checked_add_signed(Duration::days(7)).unwrap();
+    let mut editor = RepositoryEditor::new(&root).unwrap();
+    editor
+        .targets_version(NonZeroU64::new(1).unwrap())
+        .unwrap()
+        .targets_expires(expires)
+        .unwrap()
+        .snapshot_version(NonZeroU64::new(1).unwrap())
+        .snapshot_expires(expires)
+        .timestamp_version(NonZeroU64::new(1).unwrap())
+        .timestamp_expires(expires);
+    editor
+        .sign(&snakeoil_keys())
+        .unwrap()
+        .write(&metadata)
+        .unwrap();
+
+    let repo = RepositoryLoader::new(
+        File::open(&root).unwrap(),
+        dir_url(&metadata),
+        dir_url(dir.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    assert!(repo
+        .root()
+        .signed
+        .keys
+        .values()
+        .any(|key| matches!(key, Key::Unknown { .. })));
+}
+
+/// Test that a signature by a key of an unknown type doesn't count toward the threshold.
+#[test]
+fn unknown_key_signature_not_counted() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("root.json");
+    let key_id = write_root(&path, 2);
+
+    let mut root: Signed<Root> = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+    root.signatures.push(Signature {
+        keyid: key_id,
+        sig: vec![0; 64].into(),
+        created: None,
+    });
+    root.signed.verify_role(&root).unwrap_err();
+}
@@ -0,0 +1,57 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use test_utils::{repo_loader, test_data, RepoBuilder};
+use tough::schema::Target;
+
+mod test_utils;
+
+fn targets_path() -> PathBuf {
+    test_data().join("tuf-reference-impl").join("targets")
+}
+
+/// Writes a repository that lists `file3.txt` itself, and delegates `file1.txt` to `role1` and
+/// `file2.txt` to `role2`.
+fn write_repo(repo_dir: &Path) {
+    let builder = RepoBuilder::new().target_path(targets_path().join("file3.txt"));
+    let mut editor = builder.editor();
+    for (role, key_name, name) in &[
+        ("role1", "targetskey", "file1.txt"),
+        ("role2", "targetskey-1", "file2.txt"),
+    ] {
+        let target = Target::from_path(targets_path().join(name)).unwrap();
+        builder.delegate_role(&mut editor, role, key_name, &[name], vec![(name, target)]);
+    }
+    builder.write_editor(editor, repo_dir);
+}
+
+/// Test that a repository with an unreachable delegated role can be loaded with only the verified
+/// targets, and that the unreachable role is reported.
+#[test]
+fn unreachable_delegated_role() {
+    let repo_dir = TempDir::new().unwrap();
+    write_repo(repo_dir.path());
+    std::fs::remove_file(repo_dir.path().join("metadata").join("1.role2.json")).unwrap();
+    let loader = || repo_loader(repo_dir.path());
+
+    // By default, the unreachable role is an error
+    loader().load().unwrap_err();
+
+    let repo = loader().skip_unreachable_delegations(true).load().unwrap();
+    let unreachable = repo.unreachable_delegations();
+    assert_eq!(unreachable.len(), 1);
+    assert_eq!(unreachable[0].name, "role2");
+    assert!(matches!(
+        *unreachable[0].error,
+        tough::error::Error::Transport { .. }
+    ));
+
+    let mut names = repo
+        .all_targets()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["file1.txt", "file3.txt"]);
+}
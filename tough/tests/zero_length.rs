@@ -0,0 +1,92 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use ring::rand::SystemRandom;
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+use test_utils::{read_to_end, repo_loader, root_path, snakeoil_keys, RepoBuilder};
+use tough::editor::signed::SignedRole;
+use tough::error::Error;
+use tough::schema::{KeyHolder, RoleType, Root, Signed, Timestamp};
+use tough::Repository;
+
+mod test_utils;
+
+/// Creates a repository in `repo_dir` whose only target, `empty`, has no contents.
+fn create_repo(repo_dir: &Path) {
+    let input = repo_dir.join("input");
+    std::fs::create_dir_all(&input).unwrap();
+    std::fs::write(input.join("empty"), b"").unwrap();
+    RepoBuilder::new()
+        .target_path(input.join("empty"))
+        .write(repo_dir);
+}
+
+fn load(repo_dir: &Path) -> tough::error::Result<Repository> {
+    repo_loader(repo_dir).load()
+}
+
+/// Test that a target with a length of zero is valid and can be read.
+#[test]
+fn zero_length_target() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    let repo = load(repo_dir.path()).unwrap();
+
+    assert_eq!(repo.targets().signed.targets["empty"].length, 0);
+    assert!(read_to_end(repo.read_target("empty").unwrap().unwrap()).is_empty());
+}
+
+/// Test that an empty timestamp file is rejected as malformed.
+#[test]
+fn empty_timestamp() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    std::fs::write(repo_dir.path().join("metadata").join("timestamp.json"), b"").unwrap();
+
+    assert!(matches!(
+        load(repo_dir.path()).unwrap_err(),
+        Error::EmptyMetadata {
+            role: RoleType::Timestamp,
+            ..
+        }
+    ));
+}
+
+/// Test that a timestamp listing a snapshot with a length of zero is rejected as malformed.
+#[test]
+fn zero_length_snapshot() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    let metadata = repo_dir.path().join("metadata");
+    std::fs::write(metadata.join("1.snapshot.json"), b"").unwrap();
+
+    let root: Signed<Root> = serde_json::from_reader(File::open(root_path()).unwrap()).unwrap();
+    let mut timestamp: Signed<Timestamp> =
+        serde_json::from_reader(File::open(metadata.join("timestamp.json")).unwrap()).unwrap();
+    timestamp
+        .signed
+        .meta
+        .get_mut("snapshot.json")
+        .unwrap()
+        .length = 0;
+    SignedRole::new(
+        timestamp.signed,
+        &KeyHolder::Root(root.signed),
+        &snakeoil_keys(),
+        &SystemRandom::new(),
+    )
+    .unwrap()
+    .write(&metadata, true)
+    .unwrap();
+
+    assert!(matches!(
+        load(repo_dir.path()).unwrap_err(),
+        Error::MetaLengthZero {
+            file: "snapshot.json",
+            role: RoleType::Timestamp,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::error::Result;
+use crate::{load_file, write_file};
+use serde_json::Value;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tough::schema::Signed;
+
+/// Rewrites metadata files as pretty-printed JSON with sorted keys, keeping their signatures.
+#[derive(Debug, StructOpt)]
+pub(crate) struct CanonicalizeArgs {
+    /// Paths to the metadata files to rewrite
+    #[structopt(required = true)]
+    paths: Vec<PathBuf>,
+}
+
+impl CanonicalizeArgs {
+    pub(crate) fn run(&self) -> Result<()> {
+        for path in &self.paths {
+            // Signatures cover the canonical JSON form of the signed role, which key order and
+            // whitespace don't affect, so rewriting the file with its keys sorted keeps them
+            // valid. Reading the role as a `Value` keeps fields tough doesn't know about.
+            let role: Signed<Value> = load_file(path)?;
+            write_file(path, &role)?;
+        }
+        Ok(())
+    }
+}
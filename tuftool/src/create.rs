@@ -1,17 +1,18 @@
 // Copyright 2019 Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::build_targets;
-use crate::datetime::parse_datetime;
+use crate::datetime::{expiration, parse_datetime, parse_duration};
 use crate::error::{self, Result};
 use crate::source::parse_key_source;
-use chrono::{DateTime, Utc};
+use crate::{apply_target_custom, build_targets, parse_hash_algorithm};
+use chrono::{DateTime, Duration, Utc};
 use snafu::ResultExt;
 use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use structopt::StructOpt;
 use tough::editor::signed::PathExists;
 use tough::editor::RepositoryEditor;
+use tough::hash::HashAlgorithm;
 use tough::key_source::KeySource;
 
 #[derive(Debug, StructOpt)]
@@ -25,24 +26,48 @@ pub(crate) struct CreateArgs {
     snapshot_version: NonZeroU64,
     /// Expiration of snapshot.json file; can be in full RFC 3339 format, or something like 'in
     /// 7 days'
-    #[structopt(long = "snapshot-expires", parse(try_from_str = parse_datetime))]
-    snapshot_expires: DateTime<Utc>,
+    #[structopt(
+        long = "snapshot-expires",
+        parse(try_from_str = parse_datetime),
+        required_unless = "snapshot-expires-in",
+        conflicts_with = "snapshot-expires-in"
+    )]
+    snapshot_expires: Option<DateTime<Utc>>,
+    /// Expiration of snapshot.json file relative to now, something like '7d' (h/d/w units)
+    #[structopt(long = "snapshot-expires-in", parse(try_from_str = parse_duration))]
+    snapshot_expires_in: Option<Duration>,
 
     /// Version of targets.json file
     #[structopt(long = "targets-version")]
     targets_version: NonZeroU64,
     /// Expiration of targets.json file; can be in full RFC 3339 format, or something like 'in
     /// 7 days'
-    #[structopt(long = "targets-expires", parse(try_from_str = parse_datetime))]
-    targets_expires: DateTime<Utc>,
+    #[structopt(
+        long = "targets-expires",
+        parse(try_from_str = parse_datetime),
+        required_unless = "targets-expires-in",
+        conflicts_with = "targets-expires-in"
+    )]
+    targets_expires: Option<DateTime<Utc>>,
+    /// Expiration of targets.json file relative to now, something like '7d' (h/d/w units)
+    #[structopt(long = "targets-expires-in", parse(try_from_str = parse_duration))]
+    targets_expires_in: Option<Duration>,
 
     /// Version of timestamp.json file
     #[structopt(long = "timestamp-version")]
     timestamp_version: NonZeroU64,
     /// Expiration of timestamp.json file; can be in full RFC 3339 format, or something like 'in
     /// 7 days'
-    #[structopt(long = "timestamp-expires", parse(try_from_str = parse_datetime))]
-    timestamp_expires: DateTime<Utc>,
+    #[structopt(
+        long = "timestamp-expires",
+        parse(try_from_str = parse_datetime),
+        required_unless = "timestamp-expires-in",
+        conflicts_with = "timestamp-expires-in"
+    )]
+    timestamp_expires: Option<DateTime<Utc>>,
+    /// Expiration of timestamp.json file relative to now, something like '7d' (h/d/w units)
+    #[structopt(long = "timestamp-expires-in", parse(try_from_str = parse_duration))]
+    timestamp_expires_in: Option<Duration>,
 
     /// Path to root.json file for the repository
     #[structopt(short = "r", long = "root")]
@@ -52,6 +77,25 @@ pub(crate) struct CreateArgs {
     #[structopt(short = "t", long = "add-targets")]
     targets_indir: PathBuf,
 
+    /// Custom metadata for an added target, given as the target name followed by `key=value`.
+    /// May be repeated. Values that parse as JSON are stored as JSON, otherwise as strings
+    #[structopt(
+        long = "target-custom",
+        number_of_values = 2,
+        value_names = &["target", "key=value"]
+    )]
+    target_custom: Vec<String>,
+
+    /// Hash algorithms to list a digest for in each target's metadata, as well as "sha256", which
+    /// is always listed. May be repeated, or given as a comma-separated list. Options are
+    /// "sha256" and "sha512"
+    #[structopt(
+        long = "hash-algorithms",
+        use_delimiter = true,
+        parse(try_from_str = parse_hash_algorithm)
+    )]
+    hash_algorithms: Vec<Box<dyn HashAlgorithm>>,
+
     /// Behavior when a target exists with the same name and hash in the targets directory,
     /// for example from another repository when they share a targets directory.
     /// Options are "replace", "fail", and "skip"
@@ -73,6 +117,25 @@ pub(crate) struct CreateArgs {
     /// The directory where the repository will be written
     #[structopt(short = "o", long = "outdir")]
     outdir: PathBuf,
+
+    /// Also write versions.json to the metadata directory: an unsigned index of each role's
+    /// version, file name, length, and SHA-256 digest, for mirrors and clients to discover the
+    /// current metadata without guessing version numbers
+    #[structopt(long = "write-versions")]
+    write_versions: bool,
+
+    /// Also write the signed metadata to this path as a single JSON bundle, for clients that load
+    /// the repository from one file
+    #[structopt(long = "bundle")]
+    bundle: Option<PathBuf>,
+
+    /// Write only the bundle given by --bundle, not the individual metadata files
+    #[structopt(
+        long = "bundle-only",
+        requires = "bundle",
+        conflicts_with = "write-versions"
+    )]
+    bundle_only: bool,
 }
 
 impl CreateArgs {
@@ -86,19 +149,32 @@ impl CreateArgs {
                 .context(error::InitializeThreadPool)?;
         }
 
-        let targets = build_targets(&self.targets_indir, self.follow)?;
+        let mut targets = build_targets(&self.targets_indir, self.follow, &self.hash_algorithms)?;
+        apply_target_custom(&mut targets, &self.target_custom)?;
         let mut editor =
             RepositoryEditor::new(&self.root).context(error::EditorCreate { path: &self.root })?;
 
         editor
             .targets_version(self.targets_version)
             .context(error::DelegationStructure)?
-            .targets_expires(self.targets_expires)
+            .targets_expires(expiration(
+                "targets",
+                self.targets_expires,
+                self.targets_expires_in,
+            )?)
             .context(error::DelegationStructure)?
             .snapshot_version(self.snapshot_version)
-            .snapshot_expires(self.snapshot_expires)
+            .snapshot_expires(expiration(
+                "snapshot",
+                self.snapshot_expires,
+                self.snapshot_expires_in,
+            )?)
             .timestamp_version(self.timestamp_version)
-            .timestamp_expires(self.timestamp_expires);
+            .timestamp_expires(expiration(
+                "timestamp",
+                self.timestamp_expires,
+                self.timestamp_expires_in,
+            )?);
 
         for (filename, target) in targets {
             editor
@@ -116,9 +192,23 @@ impl CreateArgs {
                 indir: &self.targets_indir,
                 outdir: targets_outdir,
             })?;
-        signed_repo.write(metadata_dir).context(error::WriteRepo {
-            directory: metadata_dir,
-        })?;
+        if !self.bundle_only {
+            signed_repo.write(metadata_dir).context(error::WriteRepo {
+                directory: metadata_dir,
+            })?;
+        }
+        if let Some(bundle) = &self.bundle {
+            signed_repo
+                .write_bundle(bundle)
+                .context(error::WriteBundle { path: bundle })?;
+        }
+        if self.write_versions {
+            signed_repo
+                .write_versions(metadata_dir)
+                .context(error::WriteRepo {
+                    directory: metadata_dir,
+                })?;
+        }
 
         Ok(())
     }
@@ -4,7 +4,7 @@
 use crate::error::{self, Result};
 
 use chrono::{DateTime, Duration, FixedOffset, Utc};
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 
 /// Parses a user-specified datetime, either in full RFC 3339 format, or a shorthand like "in 7
 /// days"
@@ -57,3 +57,44 @@ pub(crate) fn parse_datetime(input: &str) -> Result<DateTime<Utc>> {
     let then = now + duration;
     Ok(then)
 }
+
+/// Parses a user-specified duration in a compact form like "12h", "7d", or "2w" (hours, days, or
+/// weeks)
+pub(crate) fn parse_duration(input: &str) -> Result<Duration> {
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .filter(|&i| i > 0)
+        .context(error::DateArgInvalid {
+            input,
+            msg: "expected a count and unit, something like '7d'",
+        })?;
+    let (count_str, unit_str) = input.split_at(split);
+    let count: u32 = count_str.parse().context(error::DateArgCount { input })?;
+    match unit_str {
+        "h" => Ok(Duration::hours(i64::from(count))),
+        "d" => Ok(Duration::days(i64::from(count))),
+        "w" => Ok(Duration::weeks(i64::from(count))),
+        _ => error::DateArgInvalid {
+            input,
+            msg: "duration argument's unit must be h/d/w",
+        }
+        .fail(),
+    }
+}
+
+/// Returns the expiration for `role` given on the command line, either as an absolute datetime or
+/// as a duration from now.
+pub(crate) fn expiration(
+    role: &str,
+    absolute: Option<DateTime<Utc>>,
+    relative: Option<Duration>,
+) -> Result<DateTime<Utc>> {
+    match (absolute, relative) {
+        (Some(absolute), None) => Ok(absolute),
+        (None, Some(relative)) => Ok(Utc::now() + relative),
+        _ => error::Missing {
+            what: format!("exactly one of --{role}-expires and --{role}-expires-in"),
+        }
+        .fail(),
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::error::Result;
+use crate::{load_file, write_file};
+use serde_json::Value;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tough::schema::Signed;
+
+/// Removes duplicate signatures from metadata files, leaving the signed content untouched.
+#[derive(Debug, StructOpt)]
+pub(crate) struct DedupSignaturesArgs {
+    /// Paths to the metadata files to clean up
+    #[structopt(required = true)]
+    paths: Vec<PathBuf>,
+}
+
+impl DedupSignaturesArgs {
+    pub(crate) fn run(&self) -> Result<()> {
+        for path in &self.paths {
+            // The signed role is kept as generic JSON so that this works for any role; signatures
+            // are made over its canonical form, so they stay valid when it's written back out.
+            let mut role: Signed<Value> = load_file(path)?;
+            let removed = role.dedup_signatures();
+            if removed == 0 {
+                continue;
+            }
+            println!(
+                "{}: removed {removed} duplicate signature(s)",
+                path.display()
+            );
+            write_file(path, &role)?;
+        }
+        Ok(())
+    }
+}
@@ -2,10 +2,9 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::error::{self, Result};
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::fs::File;
-use std::io::{self};
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 use tough::{ExpirationEnforcement, Repository, RepositoryLoader};
@@ -124,35 +123,38 @@ fn handle_download(
     outdir: &PathBuf,
     target_names: &[String],
 ) -> Result<()> {
-    let download_target = |target: &str| -> Result<()> {
-        let path = PathBuf::from(outdir).join(target);
-        println!("\t-> {}", &target);
-        let mut reader = repository
-            .read_target(target)
-            .context(error::Metadata)?
-            .context(error::TargetNotFound { target })?;
-        let mut f = File::create(&path).context(error::OpenFile { path: &path })?;
-        io::copy(&mut reader, &mut f).context(error::WriteTarget)?;
-        Ok(())
-    };
-
-    // copy requested targets, or all available targets if not specified
-    let targets = if target_names.is_empty() {
+    // copy requested targets, or all available targets, including those of delegated roles, if not
+    // specified
+    let targets: Vec<String> = if target_names.is_empty() {
         repository
-            .targets()
-            .signed
-            .targets
-            .keys()
-            .cloned()
+            .all_targets()
+            .map(|(name, _)| name.clone())
             .collect()
     } else {
         target_names.to_owned()
     };
+    // Report why a requested target can't be downloaded before downloading any of them
+    for target in &targets {
+        repository
+            .resolve_target(target)
+            .context(error::ResolveTarget { target })?;
+    }
 
+    // Each target is saved to a temporary file that's only moved into `outdir` once its length and
+    // hashes have been verified, so a target that fails verification never appears in `outdir`.
+    // Targets are saved one at a time so that they're listed in order.
     println!("Downloading targets to {:?}", outdir);
     std::fs::create_dir_all(outdir).context(error::DirCreate { path: outdir })?;
-    for target in targets {
-        download_target(&target)?;
-    }
-    Ok(())
+    repository
+        .save_targets(
+            &targets,
+            outdir,
+            NonZeroUsize::new(1).unwrap(),
+            |target, result| {
+                if result.is_ok() {
+                    println!("\t-> {}", target);
+                }
+            },
+        )
+        .context(error::SaveTargets { outdir })
 }
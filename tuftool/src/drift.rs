@@ -0,0 +1,156 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::common::UNUSED_URL;
+use crate::error::{self, Result};
+use snafu::{ensure, ResultExt};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use tough::schema::{Role, Signed, Target};
+use tough::{ExpirationEnforcement, Repository, RepositoryLoader};
+use url::Url;
+
+/// Compares a local repository with a remote one and reports any differences between them.
+#[derive(Debug, StructOpt)]
+pub(crate) struct DriftArgs {
+    /// Path to root.json file for the repository
+    #[structopt(short = "r", long = "root")]
+    root: PathBuf,
+
+    /// Metadata base URL of the local copy of the repository
+    #[structopt(long = "local-metadata-url")]
+    local_metadata_url: Url,
+
+    /// Metadata base URL of the remote repository
+    #[structopt(long = "remote-metadata-url")]
+    remote_metadata_url: Url,
+
+    /// Allow comparing repositories whose metadata has expired
+    #[structopt(long)]
+    allow_expired_repo: bool,
+}
+
+impl DriftArgs {
+    pub(crate) fn run(&self) -> Result<()> {
+        let local = self.load(&self.local_metadata_url)?;
+        let remote = self.load(&self.remote_metadata_url)?;
+
+        let mut drift = Vec::new();
+        compare_role("root", local.root(), remote.root(), &mut drift);
+        compare_role(
+            "timestamp",
+            local.timestamp(),
+            remote.timestamp(),
+            &mut drift,
+        );
+        compare_role("snapshot", local.snapshot(), remote.snapshot(), &mut drift);
+        compare_role("targets", local.targets(), remote.targets(), &mut drift);
+
+        let targets = diff_targets(
+            &local.targets().signed.targets_map(),
+            &remote.targets().signed.targets_map(),
+        );
+        for name in &targets.added {
+            drift.push(format!("target '{name}' is only in the remote repository"));
+        }
+        for name in &targets.removed {
+            drift.push(format!("target '{name}' is only in the local repository"));
+        }
+        for name in &targets.changed {
+            drift.push(format!("target '{name}' differs"));
+        }
+
+        if drift.is_empty() {
+            println!("No drift detected");
+        }
+        for line in &drift {
+            println!("{line}");
+        }
+        ensure!(drift.is_empty(), error::Drift { count: drift.len() });
+        Ok(())
+    }
+
+    fn load(&self, metadata_url: &Url) -> Result<Repository> {
+        let root: &Path = &self.root;
+        let expiration_enforcement = if self.allow_expired_repo {
+            ExpirationEnforcement::Unsafe
+        } else {
+            ExpirationEnforcement::Safe
+        };
+        RepositoryLoader::new(
+            File::open(root).context(error::OpenRoot { path: root })?,
+            metadata_url.clone(),
+            // only metadata is compared, so targets are never fetched
+            Url::parse(UNUSED_URL).with_context(|| error::UrlParse {
+                url: UNUSED_URL.to_owned(),
+            })?,
+        )
+        .expiration_enforcement(expiration_enforcement)
+        .load()
+        .with_context(|| error::DriftLoad {
+            url: metadata_url.to_string(),
+        })
+    }
+}
+
+/// Records any difference in the version or expiration of `role` between the two repositories.
+fn compare_role<T: Role>(
+    role: &str,
+    local: &Signed<T>,
+    remote: &Signed<T>,
+    drift: &mut Vec<String>,
+) {
+    let (local, remote) = (&local.signed, &remote.signed);
+    if local.version() != remote.version() {
+        drift.push(format!(
+            "{role} version: local {}, remote {}",
+            local.version(),
+            remote.version()
+        ));
+    }
+    if local.expires() != remote.expires() {
+        drift.push(format!(
+            "{role} expires: local {}, remote {}",
+            local.expires().to_rfc3339(),
+            remote.expires().to_rfc3339()
+        ));
+    }
+}
+
+/// The names of targets that differ between two sets of targets, in sorted order.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TargetsDiff {
+    /// Targets only present in the second set
+    pub(crate) added: BTreeSet<String>,
+    /// Targets only present in the first set
+    pub(crate) removed: BTreeSet<String>,
+    /// Targets present in both sets whose length, hashes, or custom metadata differ
+    pub(crate) changed: BTreeSet<String>,
+}
+
+/// Compares the targets in `from` with those in `to`.
+pub(crate) fn diff_targets(
+    from: &HashMap<String, &Target>,
+    to: &HashMap<String, &Target>,
+) -> TargetsDiff {
+    let mut diff = TargetsDiff::default();
+    for (name, target) in from {
+        match to.get(name) {
+            None => {
+                diff.removed.insert(name.clone());
+            }
+            Some(other) if other != target => {
+                diff.changed.insert(name.clone());
+            }
+            Some(_) => {}
+        }
+    }
+    for name in to.keys() {
+        if !from.contains_key(name) {
+            diff.added.insert(name.clone());
+        }
+    }
+    diff
+}
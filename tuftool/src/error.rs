@@ -34,6 +34,24 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Cannot cross-sign version {} of root.json with version {}; the new root must be exactly one version newer",
+        new_version,
+        old_version
+    ))]
+    CrossSignVersion {
+        old_version: u64,
+        new_version: u64,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to check the cross-signature from '{}': {}", path.display(), source))]
+    CrossSignVerify {
+        path: PathBuf,
+        source: tough::schema::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Cannot determine current directory: {}", source))]
     CurrentDir {
         source: std::io::Error,
@@ -73,6 +91,19 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Found {} difference(s) between the local and remote repositories",
+        count
+    ))]
+    Drift { count: usize, backtrace: Backtrace },
+
+    #[snafu(display("Failed to load repository from '{}': {}", url, source))]
+    DriftLoad {
+        url: String,
+        source: tough::error::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Failed to create a Repository Editor with root.json '{}': {}",
         path.display(),
@@ -150,12 +181,24 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to generate key pair: {}", source))]
+    KeyGenerate {
+        source: ring::error::Unspecified,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to calculate key ID: {}", source))]
     KeyId {
         #[snafu(backtrace)]
         source: tough::schema::Error,
     },
 
+    #[snafu(display("Key ID {} is listed by a role but not in root.json", key_id))]
+    KeyIdMissing {
+        key_id: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Unable to parse keypair: {}", source))]
     KeyPairParse {
         source: tough::error::Error,
@@ -193,12 +236,6 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
-    #[snafu(display("Metadata error: {}", source))]
-    Metadata {
-        source: tough::error::Error,
-        backtrace: Backtrace,
-    },
-
     #[snafu(display("Missing: {}", what))]
     Missing { what: String, backtrace: Backtrace },
 
@@ -243,12 +280,48 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Can't download target '{}': {}", target, source))]
+    ResolveTarget {
+        target: String,
+        source: tough::error::TargetResolutionError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Found {} problem(s) in '{}'", count, path.display()))]
+    RootLint {
+        path: PathBuf,
+        count: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to download and verify targets to '{}': {}",
+        outdir.display(),
+        source
+    ))]
+    SaveTargets {
+        outdir: PathBuf,
+        source: tough::error::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to sign repository: {}", source))]
     SignRepo {
         source: tough::error::Error,
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Signature in '{}' is not a valid signature of root.json by key {}",
+        path.display(),
+        key_id
+    ))]
+    SignatureInvalid {
+        path: PathBuf,
+        key_id: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Root was signed with {} signatures; it must be signed with at least {}",
         signature_count,
@@ -259,6 +332,13 @@ pub(crate) enum Error {
         signature_count: usize,
     },
 
+    #[snafu(display("Failed to check the signature in '{}': {}", path.display(), source))]
+    SignatureVerify {
+        path: PathBuf,
+        source: tough::schema::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to sign '{}': {}", path.display(), source))]
     SignRoot {
         path: PathBuf,
@@ -266,6 +346,28 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Spec version '{}' is not supported by tough, which supports '{}'",
+        version,
+        tough::editor::SPEC_VERSION
+    ))]
+    SpecVersion {
+        version: &'static str,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Custom target field '{}' is invalid, expected the form 'key=value'",
+        input
+    ))]
+    TargetCustomParse { input: String, backtrace: Backtrace },
+
+    #[snafu(display("Custom field given for target '{}', which is not being added", target))]
+    TargetCustomUnknown {
+        target: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Unable to create Target from path '{}': {}", path.display(), source))]
     TargetFromPath {
         path: PathBuf,
@@ -280,6 +382,13 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Targets in '{}' are incomplete: {}", directory.display(), source))]
+    TargetsIncomplete {
+        directory: PathBuf,
+        source: tough::error::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Target not found: {}", target))]
     TargetNotFound {
         target: String,
@@ -292,12 +401,27 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Key {} is not authorized to sign for the root role", key_id))]
+    UnauthorizedSigningKey {
+        key_id: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Unrecognized hash algorithm \"{}\"", name))]
+    UnrecognizedHashAlgorithm { name: String, backtrace: Backtrace },
+
     #[snafu(display("Unrecognized URL scheme \"{}\"", scheme))]
     UnrecognizedScheme {
         scheme: String,
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Unrecognized AWS KMS signing algorithm \"{}\"", algorithm))]
+    UnrecognizedSigningAlgorithm {
+        algorithm: String,
+        backtrace: Backtrace,
+    },
+
     /// Root creates an unloadable repo
     #[snafu(display(
         "Unstable root: '{}' role contains {} keys, threshold is {}",
@@ -331,6 +455,13 @@ pub(crate) enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed writing metadata bundle to '{}': {}", path.display(), source))]
+    WriteBundle {
+        path: PathBuf,
+        source: tough::error::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed write: {}", source))]
     WriteKeySource {
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
@@ -350,10 +481,4 @@ pub(crate) enum Error {
         source: tough::error::Error,
         backtrace: Backtrace,
     },
-
-    #[snafu(display("Failed writing target data to disk: {}", source))]
-    WriteTarget {
-        source: std::io::Error,
-        backtrace: Backtrace,
-    },
 }
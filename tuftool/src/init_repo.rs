@@ -0,0 +1,137 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::datetime::parse_datetime;
+use crate::error::{self, Result};
+use crate::root::{add_key, generate_rsa_key, round_time};
+use crate::source::parse_key_source;
+use crate::write_file;
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use snafu::ResultExt;
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tough::editor::signed::SignedRole;
+use tough::editor::RepositoryEditor;
+use tough::key_source::KeySource;
+use tough::schema::{KeyHolder, RoleKeys, RoleType, Root};
+use tough::sign::{parse_keypair, Sign};
+
+/// Creates a new key for each top-level role, a root.json that trusts them, and an empty
+/// repository signed with them.
+#[derive(Debug, StructOpt)]
+pub(crate) struct InitRepoArgs {
+    /// Path to write the new root.json to
+    #[structopt(short = "r", long = "root")]
+    root: PathBuf,
+
+    /// Where to write the new root key
+    #[structopt(long = "root-key", parse(try_from_str = parse_key_source))]
+    root_key: Box<dyn KeySource>,
+    /// Where to write the new snapshot key
+    #[structopt(long = "snapshot-key", parse(try_from_str = parse_key_source))]
+    snapshot_key: Box<dyn KeySource>,
+    /// Where to write the new targets key
+    #[structopt(long = "targets-key", parse(try_from_str = parse_key_source))]
+    targets_key: Box<dyn KeySource>,
+    /// Where to write the new timestamp key
+    #[structopt(long = "timestamp-key", parse(try_from_str = parse_key_source))]
+    timestamp_key: Box<dyn KeySource>,
+
+    /// Bit length of the new keys
+    #[structopt(short = "b", long = "bits", default_value = "2048")]
+    bits: u16,
+    /// Public exponent of the new keys
+    #[structopt(short = "e", long = "exp", default_value = "65537")]
+    exponent: u32,
+
+    /// Expiration of root.json; can be in full RFC 3339 format, or something like 'in 7 days'
+    #[structopt(long = "root-expires", parse(try_from_str = parse_datetime))]
+    root_expires: DateTime<Utc>,
+    /// Expiration of snapshot.json; can be in full RFC 3339 format, or something like 'in 7 days'
+    #[structopt(long = "snapshot-expires", parse(try_from_str = parse_datetime))]
+    snapshot_expires: DateTime<Utc>,
+    /// Expiration of targets.json; can be in full RFC 3339 format, or something like 'in 7 days'
+    #[structopt(long = "targets-expires", parse(try_from_str = parse_datetime))]
+    targets_expires: DateTime<Utc>,
+    /// Expiration of timestamp.json; can be in full RFC 3339 format, or something like 'in 7
+    /// days'
+    #[structopt(long = "timestamp-expires", parse(try_from_str = parse_datetime))]
+    timestamp_expires: DateTime<Utc>,
+
+    /// The directory where the repository will be written
+    #[structopt(short = "o", long = "outdir")]
+    outdir: PathBuf,
+}
+
+impl InitRepoArgs {
+    pub(crate) fn run(self) -> Result<()> {
+        let one = NonZeroU64::new(1).unwrap();
+        let role_keys = || RoleKeys {
+            keyids: Vec::new(),
+            threshold: one,
+            _extra: HashMap::new(),
+        };
+        let mut root = Root {
+            spec_version: crate::SPEC_VERSION.to_owned(),
+            consistent_snapshot: true,
+            version: one,
+            expires: round_time(self.root_expires),
+            keys: HashMap::new(),
+            roles: RoleType::TOP_LEVEL
+                .iter()
+                .map(|role| (*role, role_keys()))
+                .collect(),
+            _extra: HashMap::new(),
+        };
+
+        let roles = [
+            (RoleType::Root, &self.root_key),
+            (RoleType::Snapshot, &self.snapshot_key),
+            (RoleType::Targets, &self.targets_key),
+            (RoleType::Timestamp, &self.timestamp_key),
+        ];
+        for (role, key_source) in &roles {
+            let stdout = generate_rsa_key(self.bits, self.exponent)?;
+            let key_pair = parse_keypair(stdout.as_bytes()).context(error::KeyPairParse)?;
+            let key_id = hex::encode(add_key(&mut root, &[*role], key_pair.tuf_key())?);
+            key_source
+                .write(&stdout, &key_id)
+                .context(error::WriteKeySource)?;
+            println!("{role}: {key_id}");
+        }
+
+        let signed_root = SignedRole::new(
+            root.clone(),
+            &KeyHolder::Root(root),
+            &[self.root_key],
+            &SystemRandom::new(),
+        )
+        .context(error::SignRoot { path: &self.root })?;
+        write_file(&self.root, signed_root.signed())?;
+
+        let mut editor =
+            RepositoryEditor::new(&self.root).context(error::EditorCreate { path: &self.root })?;
+        editor
+            .targets_version(one)
+            .context(error::DelegationStructure)?
+            .targets_expires(self.targets_expires)
+            .context(error::DelegationStructure)?
+            .snapshot_version(one)
+            .snapshot_expires(self.snapshot_expires)
+            .timestamp_version(one)
+            .timestamp_expires(self.timestamp_expires);
+        let keys = vec![self.snapshot_key, self.targets_key, self.timestamp_key];
+        let signed_repo = editor.sign(&keys).context(error::SignRepo)?;
+
+        let metadata_dir = &self.outdir.join("metadata");
+        let targets_dir = &self.outdir.join("targets");
+        std::fs::create_dir_all(targets_dir).context(error::DirCreate { path: targets_dir })?;
+        signed_repo.write(metadata_dir).context(error::WriteRepo {
+            directory: metadata_dir,
+        })?;
+        Ok(())
+    }
+}
@@ -13,30 +13,38 @@
 
 mod add_key_role;
 mod add_role;
+mod canonicalize;
 mod common;
 mod create;
 mod create_role;
 mod datetime;
+mod dedup_signatures;
 mod download;
+mod drift;
 mod error;
+mod init_repo;
 mod remove_key_role;
 mod remove_role;
 mod root;
+mod set_paths_role;
 mod source;
 mod update;
 mod update_targets;
 
 use crate::error::Result;
 use rayon::prelude::*;
+use serde_json::Value;
 use simplelog::{ConfigBuilder, LevelFilter, TermLogger, TerminalMode};
-use snafu::{ErrorCompat, OptionExt, ResultExt};
+use snafu::{ensure, ErrorCompat, OptionExt, ResultExt};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 use structopt::StructOpt;
 use tempfile::NamedTempFile;
+use tough::hash::{HashAlgorithm, Sha256, Sha512};
 use tough::schema::Target;
+use tough::target_name_from_path;
 use walkdir::WalkDir;
 
 static SPEC_VERSION: &str = "1.0.0";
@@ -67,16 +75,31 @@ impl Program {
             TerminalMode::Mixed,
         )
         .context(error::Logger)?;
+        // Never write metadata that tough itself would refuse to load
+        ensure!(
+            tough::editor::spec_version_supported(SPEC_VERSION),
+            error::SpecVersion {
+                version: SPEC_VERSION
+            }
+        );
         self.cmd.run()
     }
 }
 
 #[derive(Debug, StructOpt)]
 enum Command {
+    /// Rewrite metadata files in a tidy, deterministic form without invalidating signatures
+    Canonicalize(canonicalize::CanonicalizeArgs),
     /// Create a TUF repository
     Create(create::CreateArgs),
+    /// Remove duplicate signatures from metadata files
+    DedupSignatures(dedup_signatures::DedupSignaturesArgs),
     /// Download a TUF repository's resources
     Download(download::DownloadArgs),
+    /// Compare a local TUF repository with a remote one and report any drift
+    Drift(drift::DriftArgs),
+    /// Generate keys for every role, a root.json, and an empty TUF repository in one step
+    InitRepo(Box<init_repo::InitRepoArgs>),
     /// Update a TUF repository's metadata and optionally add targets
     Update(Box<update::UpdateArgs>),
     /// Manipulate a root.json metadata file
@@ -88,9 +111,13 @@ enum Command {
 impl Command {
     fn run(self) -> Result<()> {
         match self {
+            Command::Canonicalize(args) => args.run(),
             Command::Create(args) => args.run(),
+            Command::DedupSignatures(args) => args.run(),
             Command::Root(root_subcommand) => root_subcommand.run(),
             Command::Download(args) => args.run(),
+            Command::Drift(args) => args.run(),
+            Command::InitRepo(args) => args.run(),
             Command::Update(args) => args.run(),
             Command::Delegation(cmd) => cmd.run(),
         }
@@ -119,9 +146,13 @@ where
     Ok(())
 }
 
-// Walk the directory specified, building a map of filename to Target structs.
-// Hashing of the targets is done in parallel
-fn build_targets<P>(indir: P, follow_links: bool) -> Result<HashMap<String, Target>>
+// Walk the directory specified, building a map of filename to Target structs, each listing a
+// digest for `hash_algorithms` as well as SHA-256. Hashing of the targets is done in parallel
+fn build_targets<P>(
+    indir: P,
+    follow_links: bool,
+    hash_algorithms: &[Box<dyn HashAlgorithm>],
+) -> Result<HashMap<String, Target>>
 where
     P: AsRef<Path>,
 {
@@ -133,7 +164,7 @@ where
         .filter_map(|entry| match entry {
             Ok(entry) => {
                 if entry.file_type().is_file() {
-                    Some(process_target(entry.path()))
+                    Some(process_target(entry.path(), hash_algorithms))
                 } else {
                     None
                 }
@@ -143,17 +174,53 @@ where
         .collect()
 }
 
-fn process_target(path: &Path) -> Result<(String, Target)> {
+// Apply custom metadata given on the command line as pairs of target name and `key=value`. Values
+// that parse as JSON are stored as JSON; anything else is stored as a string.
+fn apply_target_custom(
+    targets: &mut HashMap<String, Target>,
+    target_custom: &[String],
+) -> Result<()> {
+    for pair in target_custom.chunks_exact(2) {
+        let (target_name, field) = (&pair[0], &pair[1]);
+        let (key, value) = field
+            .find('=')
+            .map(|i| (&field[..i], &field[i + 1..]))
+            .filter(|(key, _)| !key.is_empty())
+            .context(error::TargetCustomParse { input: field })?;
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_owned()));
+        targets
+            .get_mut(target_name)
+            .context(error::TargetCustomUnknown {
+                target: target_name,
+            })?
+            .custom
+            .insert(key.to_owned(), value);
+    }
+    Ok(())
+}
+
+// Parse the name of a hash algorithm to list target digests for, as given on the command line
+fn parse_hash_algorithm(name: &str) -> Result<Box<dyn HashAlgorithm>> {
+    match name {
+        "sha256" => Ok(Box::new(Sha256)),
+        "sha512" => Ok(Box::new(Sha512)),
+        _ => error::UnrecognizedHashAlgorithm { name }.fail(),
+    }
+}
+
+fn process_target(
+    path: &Path,
+    hash_algorithms: &[Box<dyn HashAlgorithm>],
+) -> Result<(String, Target)> {
     // Build a Target from the path given. If it is not a file, this will fail
-    let target = Target::from_path(path).context(error::TargetFromPath { path })?;
-
-    // Get the file name as a string
-    let target_name = path
-        .file_name()
-        .context(error::NoFileName { path })?
-        .to_str()
-        .context(error::PathUtf8 { path })?
-        .to_owned();
+    let target = Target::from_path_with_hash_algorithms(path, hash_algorithms)
+        .context(error::TargetFromPath { path })?;
+
+    // Get the file name as a target name
+    let target_name = target_name_from_path(Path::new(
+        path.file_name().context(error::NoFileName { path })?,
+    ))
+    .context(error::PathUtf8 { path })?;
     Ok((target_name, target))
 }
 
@@ -204,6 +271,8 @@ enum DelegationCommand {
     RemoveKey(Box<remove_key_role::RemoveKeyArgs>),
     /// Remove a role
     Remove(Box<remove_role::RemoveRoleArgs>),
+    /// Set the paths delegated to a role
+    SetPaths(Box<set_paths_role::SetPathsArgs>),
 }
 
 impl DelegationCommand {
@@ -215,6 +284,7 @@ impl DelegationCommand {
             DelegationCommand::AddKey(args) => args.run(role),
             DelegationCommand::RemoveKey(args) => args.run(role),
             DelegationCommand::Remove(args) => args.run(role),
+            DelegationCommand::SetPaths(args) => args.run(role),
         }
     }
 }
@@ -6,19 +6,20 @@ use crate::error::{self, Result};
 use crate::source::parse_key_source;
 use crate::{load_file, write_file};
 use chrono::{DateTime, Timelike, Utc};
-use maplit::hashmap;
+use ring::digest::{digest, SHA256};
 use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
 use snafu::{ensure, OptionExt, ResultExt};
 use std::collections::HashMap;
 use std::io::Write;
 use std::num::NonZeroU64;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 use tempfile::NamedTempFile;
 use tough::editor::signed::SignedRole;
 use tough::key_source::KeySource;
 use tough::schema::decoded::{Decoded, Hex};
-use tough::schema::{key::Key, KeyHolder, RoleKeys, RoleType, Root, Signed};
+use tough::schema::{key::Key, KeyHolder, Role, RoleKeys, RoleType, Root, Signature, Signed};
 use tough::sign::{parse_keypair, Sign};
 
 #[derive(Debug, StructOpt)]
@@ -79,6 +80,25 @@ pub(crate) enum Command {
         /// file)
         role: Option<RoleType>,
     },
+    /// Remove keys that are not listed by any role, printing their key IDs
+    RemoveOrphanedKeys {
+        /// Path to root.json
+        path: PathBuf,
+    },
+    /// Check that root.json is well-formed and signed by a threshold of its own keys, printing
+    /// each problem found
+    Lint {
+        /// Path to root.json
+        path: PathBuf,
+    },
+    /// Write the public key of each key ID listed by a role to `<key ID>.pem` in a directory,
+    /// printing each role and key ID
+    ExportKeys {
+        /// Path to root.json
+        path: PathBuf,
+        /// Directory to write the public keys to
+        outdir: PathBuf,
+    },
     /// Generate a new RSA key pair, saving it to a file, and add it to a role
     GenRsaKey {
         /// Path to root.json
@@ -96,6 +116,17 @@ pub(crate) enum Command {
         #[structopt(short = "r", long = "role")]
         roles: Vec<RoleType>,
     },
+    /// Generate a new ECDSA P-256 key pair, saving it to a file, and add it to a role
+    GenEcdsaKey {
+        /// Path to root.json
+        path: PathBuf,
+        /// Where to write the new key
+        #[structopt(parse(try_from_str = parse_key_source))]
+        key_source: Box<dyn KeySource>,
+        /// The role to add the key to
+        #[structopt(short = "r", long = "role")]
+        roles: Vec<RoleType>,
+    },
     /// Sign the given root.json
     Sign {
         /// Path to root.json
@@ -106,9 +137,65 @@ pub(crate) enum Command {
         ///Optional - Path of older root.json that contains the key-id
         #[structopt(short = "c", long = "cross-sign")]
         cross_sign: Option<PathBuf>,
+        /// Fail, rather than warn, if a key is not authorized to sign for the root role
+        #[structopt(long = "strict")]
+        strict: bool,
+        /// Record the time each new signature was made alongside it (informational only)
+        #[structopt(long = "record-signing-time")]
+        record_signing_time: bool,
+        /// Don't sign; print the SHA-256 digest of the canonical bytes that would be signed, and
+        /// the bytes themselves, so they can be audited first
+        #[structopt(long = "dry-run")]
+        dry_run: bool,
+        /// With --dry-run, write the canonical bytes to this file rather than printing them
+        #[structopt(long = "preview-out", requires = "dry-run")]
+        preview_out: Option<PathBuf>,
+    },
+    /// Write the canonical bytes of root.json that a signature must be made over, such as by an
+    /// offline HSM, and print their SHA-256 digest
+    GenSigningPayload {
+        /// Path to root.json
+        path: PathBuf,
+        /// Where to write the canonical bytes
+        out: PathBuf,
+    },
+    /// Attach a signature made elsewhere over the bytes from `gen-signing-payload`, after checking
+    /// that it's valid
+    ImportSignature {
+        /// Path to root.json
+        path: PathBuf,
+        /// The key ID that made the signature
+        key_id: Decoded<Hex>,
+        /// Path to a file containing the raw signature bytes
+        #[structopt(short = "s", long = "signature")]
+        signature: PathBuf,
+        /// Optional - Path of older root.json that lists the key ID, when cross-signing
+        #[structopt(short = "c", long = "cross-sign")]
+        cross_sign: Option<PathBuf>,
+    },
+    /// Cross-sign a new root.json with keys from the previous root.json, so clients can verify
+    /// the rotation; same as `sign <new-root> --cross-sign <old-root>`
+    CrossSign {
+        /// Path to the previous root.json
+        old_root: PathBuf,
+        /// Path to the new root.json
+        new_root: PathBuf,
+        /// Key source(s) of the previous root to sign the new root with
+        #[structopt(short = "k", long = "key", required = true, parse(try_from_str = parse_key_source))]
+        key_sources: Vec<Box<dyn KeySource>>,
+        /// Fail, rather than warn, if a key is not authorized to sign for the previous root
+        #[structopt(long = "strict")]
+        strict: bool,
+        /// Record the time each new signature was made alongside it (informational only)
+        #[structopt(long = "record-signing-time")]
+        record_signing_time: bool,
     },
 }
 
+/// The threshold given to roles in a new root.json; absurdly high so that someone realizes they
+/// need to change it.
+const PLACEHOLDER_THRESHOLD: u64 = 1507;
+
 macro_rules! role_keys {
     ($threshold:expr) => {
         RoleKeys {
@@ -119,8 +206,7 @@ macro_rules! role_keys {
     };
 
     () => {
-        // absurdly high threshold value so that someone realizes they need to change this
-        role_keys!(NonZeroU64::new(1507).unwrap())
+        role_keys!(NonZeroU64::new(PLACEHOLDER_THRESHOLD).unwrap())
     };
 }
 
@@ -142,18 +228,61 @@ impl Command {
                 key_source,
             } => Command::add_key(&path, &roles, &key_source),
             Command::RemoveKey { path, key_id, role } => Command::remove_key(&path, &key_id, role),
+            Command::RemoveOrphanedKeys { path } => Command::remove_orphaned_keys(&path),
+            Command::Lint { path } => Command::lint(&path),
+            Command::ExportKeys { path, outdir } => Command::export_keys(&path, &outdir),
             Command::GenRsaKey {
                 path,
                 roles,
                 key_source,
                 bits,
                 exponent,
-            } => Command::gen_rsa_key(&path, &roles, &key_source, bits, exponent),
+            } => Command::gen_key(
+                &path,
+                &roles,
+                &key_source,
+                generate_rsa_key(bits, exponent)?,
+            ),
+            Command::GenEcdsaKey {
+                path,
+                roles,
+                key_source,
+            } => Command::gen_key(&path, &roles, &key_source, generate_ecdsa_key()?),
+            Command::Sign {
+                path,
+                dry_run: true,
+                preview_out,
+                ..
+            } => Command::preview_sign(&path, preview_out.as_deref()),
             Command::Sign {
                 path,
                 key_sources,
                 cross_sign,
-            } => Command::sign(&path, &key_sources, cross_sign),
+                strict,
+                record_signing_time,
+                ..
+            } => Command::sign(&path, &key_sources, cross_sign, strict, record_signing_time),
+            // The payload is the same canonical form that a dry run previews
+            Command::GenSigningPayload { path, out } => Command::preview_sign(&path, Some(&out)),
+            Command::ImportSignature {
+                path,
+                key_id,
+                signature,
+                cross_sign,
+            } => Command::import_signature(&path, &key_id, &signature, cross_sign.as_deref()),
+            Command::CrossSign {
+                old_root,
+                new_root,
+                key_sources,
+                strict,
+                record_signing_time,
+            } => Command::sign(
+                &new_root,
+                &key_sources,
+                Some(old_root),
+                strict,
+                record_signing_time,
+            ),
         }
     }
 
@@ -167,12 +296,10 @@ impl Command {
                     version: NonZeroU64::new(1).unwrap(),
                     expires: round_time(Utc::now()),
                     keys: HashMap::new(),
-                    roles: hashmap! {
-                        RoleType::Root => role_keys!(),
-                        RoleType::Snapshot => role_keys!(),
-                        RoleType::Targets => role_keys!(),
-                        RoleType::Timestamp => role_keys!(),
-                    },
+                    roles: RoleType::TOP_LEVEL
+                        .iter()
+                        .map(|role| (*role, role_keys!()))
+                        .collect(),
                     _extra: HashMap::new(),
                 },
                 signatures: Vec::new(),
@@ -256,45 +383,166 @@ impl Command {
         write_file(path, &root)
     }
 
+    fn remove_orphaned_keys(path: &PathBuf) -> Result<()> {
+        let mut root: Signed<Root> = load_file(path)?;
+        let mut orphans: Vec<Decoded<Hex>> =
+            root.signed.orphaned_keyids().into_iter().cloned().collect();
+        if orphans.is_empty() {
+            return Ok(());
+        }
+        orphans.sort();
+        for key_id in &orphans {
+            root.signed.keys.remove(key_id);
+            println!("{}", hex::encode(key_id));
+        }
+        clear_sigs(&mut root);
+        write_file(path, &root)
+    }
+
+    fn lint(path: &PathBuf) -> Result<()> {
+        // Parsing checks that each key is listed under the key ID of its key material
+        let root: Signed<Root> = load_file(path)?;
+        let problems = lint_root(&root);
+        if problems.is_empty() {
+            println!("{} passed all checks", path.display());
+            return Ok(());
+        }
+        for problem in &problems {
+            println!("{problem}");
+        }
+        error::RootLint {
+            path,
+            count: problems.len(),
+        }
+        .fail()
+    }
+
+    fn export_keys(path: &Path, outdir: &Path) -> Result<()> {
+        let root: Signed<Root> = load_file(path)?;
+        std::fs::create_dir_all(outdir).context(error::DirCreate { path: outdir })?;
+        let mut roles = root.signed.roles.iter().collect::<Vec<_>>();
+        roles.sort_by_key(|(role, _)| role.to_string());
+        for (role, role_keys) in roles {
+            for key_id in &role_keys.keyids {
+                let key = root.signed.keys.get(key_id).context(error::KeyIdMissing {
+                    key_id: hex::encode(key_id),
+                })?;
+                let mut pem = if let Some(pem) = key.to_pem() {
+                    pem
+                } else {
+                    log::warn!(
+                        "Skipping key {} of role {}, which is of an unknown type",
+                        hex::encode(key_id),
+                        role
+                    );
+                    continue;
+                };
+                let key_path = outdir.join(format!("{}.pem", hex::encode(key_id)));
+                if !pem.ends_with('\n') {
+                    pem.push('\n');
+                }
+                std::fs::write(&key_path, pem).context(error::FileWrite { path: &key_path })?;
+                println!("{role}: {}", hex::encode(key_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the newly generated key `pem` to `roles`, then writes it to `key_source`.
     #[allow(clippy::borrowed_box)]
-    fn gen_rsa_key(
+    fn gen_key(
         path: &PathBuf,
         roles: &[RoleType],
         key_source: &Box<dyn KeySource>,
-        bits: u16,
-        exponent: u32,
+        pem: String,
     ) -> Result<()> {
         let mut root: Signed<Root> = load_file(path)?;
+        let key_pair = parse_keypair(pem.as_bytes()).context(error::KeyPairParse)?;
+        let key_id = hex::encode(add_key(&mut root.signed, roles, key_pair.tuf_key())?);
+        key_source
+            .write(&pem, &key_id)
+            .context(error::WriteKeySource)?;
+        clear_sigs(&mut root);
+        println!("{}", key_id);
+        write_file(path, &root)
+    }
 
-        // ring doesn't support RSA key generation yet
-        // https://github.com/briansmith/ring/issues/219
-        let mut command = std::process::Command::new("openssl");
-        command.args(&["genpkey", "-algorithm", "RSA", "-pkeyopt"]);
-        command.arg(format!("rsa_keygen_bits:{}", bits));
-        command.arg("-pkeyopt");
-        command.arg(format!("rsa_keygen_pubexp:{}", exponent));
+    /// Prints the SHA-256 digest of the canonical form of root.json, which is what its signatures
+    /// are made over, followed by the canonical form itself unless it's written to `preview_out`.
+    fn preview_sign(path: &Path, preview_out: Option<&Path>) -> Result<()> {
+        let root: Signed<Root> = load_file(path)?;
+        let canonical = root
+            .signed
+            .canonical_form()
+            .context(error::JsonSerialization)?;
+        println!("sha256: {}", hex::encode(digest(&SHA256, &canonical)));
+        match preview_out {
+            Some(preview_out) => std::fs::write(preview_out, &canonical)
+                .context(error::FileWrite { path: preview_out })?,
+            // Canonical JSON is always valid UTF-8
+            None => println!("{}", String::from_utf8_lossy(&canonical)),
+        }
+        Ok(())
+    }
 
-        let command_str = format!("{:?}", command);
-        let output = command.output().context(error::CommandExec {
-            command_str: &command_str,
+    fn import_signature(
+        path: &Path,
+        key_id: &Decoded<Hex>,
+        signature_path: &Path,
+        cross_sign: Option<&Path>,
+    ) -> Result<()> {
+        let mut root: Signed<Root> = load_file(path)?;
+        let verifying_root = match cross_sign {
+            None => root.signed.clone(),
+            Some(cross_sign_root) => {
+                let old_root: Signed<Root> = load_file(cross_sign_root)?;
+                ensure!(
+                    root.signed.version.get() == old_root.signed.version.get() + 1,
+                    error::CrossSignVersion {
+                        old_version: old_root.signed.version.get(),
+                        new_version: root.signed.version.get(),
+                    }
+                );
+                old_root.signed
+            }
+        };
+        let sig = std::fs::read(signature_path).context(error::FileOpen {
+            path: signature_path,
         })?;
+        let signature = Signature {
+            keyid: key_id.clone(),
+            sig: sig.into(),
+            created: None,
+        };
+
+        // Check the signature on its own, so that a bad one is caught here rather than by clients.
+        let candidate = Signed {
+            signed: root.signed.clone(),
+            signatures: vec![signature.clone()],
+        };
+        let status = verifying_root
+            .signature_statuses(&candidate)
+            .context(error::SignatureVerify {
+                path: signature_path,
+            })?
+            .remove(0);
         ensure!(
-            output.status.success(),
-            error::CommandStatus {
-                command_str: &command_str,
-                status: output.status
+            status.authorized,
+            error::UnauthorizedSigningKey {
+                key_id: hex::encode(key_id)
+            }
+        );
+        ensure!(
+            status.valid,
+            error::SignatureInvalid {
+                path: signature_path,
+                key_id: hex::encode(key_id),
             }
         );
-        let stdout =
-            String::from_utf8(output.stdout).context(error::CommandUtf8 { command_str })?;
 
-        let key_pair = parse_keypair(stdout.as_bytes()).context(error::KeyPairParse)?;
-        let key_id = hex::encode(add_key(&mut root.signed, roles, key_pair.tuf_key())?);
-        key_source
-            .write(&stdout, &key_id)
-            .context(error::WriteKeySource)?;
-        clear_sigs(&mut root);
-        println!("{}", key_id);
+        // A new signature by the same key replaces the old one.
+        root.signatures.retain(|existing| existing.keyid != *key_id);
+        root.signatures.push(signature);
         write_file(path, &root)
     }
 
@@ -302,21 +550,46 @@ impl Command {
         path: &PathBuf,
         key_source: &[Box<dyn KeySource>],
         cross_sign: Option<PathBuf>,
+        strict: bool,
+        record_signing_time: bool,
     ) -> Result<()> {
         let root: Signed<Root> = load_file(path)?;
         // get the root based on cross-sign
-        let loaded_root = match cross_sign {
+        let loaded_root = match &cross_sign {
             None => root.clone(),
-            Some(cross_sign_root) => load_file(&cross_sign_root)?,
+            Some(cross_sign_root) => load_file(cross_sign_root)?,
         };
-        //sign the root
-        let mut signed_root = SignedRole::new(
+        // Clients only verify a new root against the root immediately before it, so a signature
+        // from any other version wouldn't help the rotation chain verify.
+        if cross_sign.is_some() {
+            ensure!(
+                root.signed.version.get() == loaded_root.signed.version.get() + 1,
+                error::CrossSignVersion {
+                    old_version: loaded_root.signed.version.get(),
+                    new_version: root.signed.version.get(),
+                }
+            );
+        }
+
+        // Keys that aren't authorized for the root role are skipped with a warning, or are an
+        // error with --strict
+        let new_signed_role = if strict {
+            SignedRole::new_strict
+        } else {
+            SignedRole::new
+        };
+        let mut signed_root = new_signed_role(
             root.signed.clone(),
-            &KeyHolder::Root(loaded_root.signed),
+            &KeyHolder::Root(loaded_root.signed.clone()),
             key_source,
             &SystemRandom::new(),
         )
         .context(error::SignRoot { path })?;
+        if record_signing_time {
+            signed_root = signed_root
+                .record_signing_time(round_time(Utc::now()))
+                .context(error::SignRoot { path })?;
+        }
 
         // append the existing signatures if present
         if !root.signatures.is_empty() {
@@ -325,6 +598,25 @@ impl Command {
                 .context(error::SignRoot { path })?;
         }
 
+        // Let the operator know whether the old root's keys have signed enough for the rotation
+        // to verify. Each holder of an old key may cross-sign separately, so this isn't an error.
+        if let Some(cross_sign_root) = &cross_sign {
+            let status = loaded_root
+                .signed
+                .verify_status(signed_root.signed())
+                .context(error::CrossSignVerify {
+                    path: cross_sign_root,
+                })?;
+            if !status.satisfied {
+                log::warn!(
+                    "Root is signed by {} of the {} root keys required by '{}'; clients won't accept it until more are added",
+                    status.valid,
+                    status.required,
+                    cross_sign_root.display()
+                );
+            }
+        }
+
         // Quick check that root is signed by enough key IDs
         for (roletype, rolekeys) in &signed_root.signed().signed.roles {
             if rolekeys.threshold.get() > rolekeys.keyids.len() as u64 {
@@ -370,7 +662,41 @@ impl Command {
     }
 }
 
-fn round_time(time: DateTime<Utc>) -> DateTime<Utc> {
+/// Generates a new RSA key pair, returning it in PEM format.
+pub(crate) fn generate_rsa_key(bits: u16, exponent: u32) -> Result<String> {
+    // ring doesn't support RSA key generation yet
+    // https://github.com/briansmith/ring/issues/219
+    let mut command = std::process::Command::new("openssl");
+    command.args(&["genpkey", "-algorithm", "RSA", "-pkeyopt"]);
+    command.arg(format!("rsa_keygen_bits:{}", bits));
+    command.arg("-pkeyopt");
+    command.arg(format!("rsa_keygen_pubexp:{}", exponent));
+
+    let command_str = format!("{:?}", command);
+    let output = command.output().context(error::CommandExec {
+        command_str: &command_str,
+    })?;
+    ensure!(
+        output.status.success(),
+        error::CommandStatus {
+            command_str: &command_str,
+            status: output.status
+        }
+    );
+    String::from_utf8(output.stdout).context(error::CommandUtf8 { command_str })
+}
+
+/// Generates a new ECDSA P-256 key pair, returning it in PKCS#8 PEM format.
+fn generate_ecdsa_key() -> Result<String> {
+    let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &SystemRandom::new())
+        .context(error::KeyGenerate)?;
+    Ok(pem::encode(&pem::Pem {
+        tag: "PRIVATE KEY".to_owned(),
+        contents: pkcs8.as_ref().to_vec(),
+    }))
+}
+
+pub(crate) fn round_time(time: DateTime<Utc>) -> DateTime<Utc> {
     // `Timelike::with_nanosecond` returns None only when passed a value >= 2_000_000_000
     time.with_nanosecond(0).unwrap()
 }
@@ -380,8 +706,52 @@ fn clear_sigs<T>(role: &mut Signed<T>) {
     role.signatures.clear();
 }
 
+/// Returns a description of each problem that would prevent `root` from being used as-is.
+fn lint_root(root: &Signed<Root>) -> Vec<String> {
+    let mut problems = Vec::new();
+    for role in RoleType::TOP_LEVEL {
+        let role_keys = if let Some(role_keys) = root.signed.roles.get(role) {
+            role_keys
+        } else {
+            problems.push(format!("The '{role}' role is not listed"));
+            continue;
+        };
+        let threshold = role_keys.threshold.get();
+        if threshold == PLACEHOLDER_THRESHOLD {
+            problems.push(format!(
+                "The '{role}' role still has the placeholder threshold {threshold}"
+            ));
+        } else if threshold > role_keys.keyids.len() as u64 {
+            problems.push(format!(
+                "The '{role}' role has a threshold of {threshold} but only {} keys",
+                role_keys.keyids.len()
+            ));
+        }
+        for key_id in &role_keys.keyids {
+            if !root.signed.keys.contains_key(key_id) {
+                problems.push(format!(
+                    "The '{role}' role lists key ID {}, which is not in the keys map",
+                    hex::encode(key_id)
+                ));
+            }
+        }
+    }
+
+    if root.signed.expires <= Utc::now() {
+        problems.push(format!(
+            "Root expired at {}",
+            root.signed.expires.to_rfc3339()
+        ));
+    }
+
+    if let Err(err) = root.signed.verify_role(root) {
+        problems.push(format!("Root is not signed by its own root keys: {err}"));
+    }
+    problems
+}
+
 /// Adds a key to the root role if not already present, and adds its key ID to the specified role.
-fn add_key(root: &mut Root, role: &[RoleType], key: Key) -> Result<Decoded<Hex>> {
+pub(crate) fn add_key(root: &mut Root, role: &[RoleType], key: Key) -> Result<Decoded<Hex>> {
     let key_id = if let Some((key_id, _)) = root
         .keys
         .iter()
@@ -397,6 +767,18 @@ fn add_key(root: &mut Root, role: &[RoleType], key: Key) -> Result<Decoded<Hex>>
                 key_id: hex::encode(&key_id)
             }
         );
+        // The same key under a different scheme gets a new ID, which is rarely what's intended
+        for (existing_id, _) in root
+            .keys
+            .iter()
+            .filter(|(_, candidate_key)| key.same_public_material(candidate_key))
+        {
+            log::warn!(
+                "Key {} has the same public key as existing key {} but a different scheme",
+                hex::encode(&key_id),
+                hex::encode(existing_id)
+            );
+        }
         root.keys.insert(key_id.clone(), key);
         key_id
     };
@@ -0,0 +1,102 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::common::load_metadata_repo;
+use crate::datetime::parse_datetime;
+use crate::error::{self, Result};
+use crate::source::parse_key_source;
+use chrono::{DateTime, Utc};
+use snafu::ResultExt;
+use std::num::NonZeroU64;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tough::editor::targets::TargetsEditor;
+use tough::key_source::KeySource;
+use tough::schema::PathSet;
+use url::Url;
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct SetPathsArgs {
+    /// Key files to sign with
+    #[structopt(short = "k", long = "key", required = true, parse(try_from_str = parse_key_source))]
+    keys: Vec<Box<dyn KeySource>>,
+
+    /// Expiration of new role file; can be in full RFC 3339 format, or something like 'in
+    /// 7 days'
+    #[structopt(short = "e", long = "expires", parse(try_from_str = parse_datetime))]
+    expires: DateTime<Utc>,
+
+    /// Version of role file
+    #[structopt(short = "v", long = "version")]
+    version: NonZeroU64,
+
+    /// Path to root.json file for the repository
+    #[structopt(short = "r", long = "root")]
+    root: PathBuf,
+
+    /// TUF repository metadata base URL
+    #[structopt(short = "m", long = "metadata-url")]
+    metadata_base_url: Url,
+
+    /// The directory where the repository will be written
+    #[structopt(short = "o", long = "outdir")]
+    outdir: PathBuf,
+
+    /// The role whose delegated paths are set
+    #[structopt(short = "d", long = "delegated-role")]
+    delegatee: String,
+
+    /// The delegated paths, replacing the current ones
+    #[structopt(
+        short = "p",
+        long = "paths",
+        conflicts_with = "path-hash-prefixes",
+        required_unless = "path-hash-prefixes"
+    )]
+    paths: Option<Vec<String>>,
+
+    /// The delegated paths hash prefixes, replacing the current paths
+    #[structopt(short = "hp", long = "path-hash-prefixes")]
+    path_hash_prefixes: Option<Vec<String>>,
+}
+
+impl SetPathsArgs {
+    pub(crate) fn run(&self, role: &str) -> Result<()> {
+        let repository = load_metadata_repo(&self.root, self.metadata_base_url.clone())?;
+        self.set_paths(
+            role,
+            TargetsEditor::from_repo(repository, role)
+                .context(error::EditorFromRepo { path: &self.root })?,
+        )
+    }
+
+    #[allow(clippy::option_if_let_else)]
+    /// Sets the paths of a delegated role using targets Editor
+    fn set_paths(&self, role: &str, mut editor: TargetsEditor) -> Result<()> {
+        let paths = if let Some(paths) = &self.paths {
+            PathSet::Paths(paths.clone())
+        } else if let Some(path_hash_prefixes) = &self.path_hash_prefixes {
+            PathSet::PathHashPrefixes(path_hash_prefixes.clone())
+        } else {
+            // structopt requires one of the two
+            PathSet::Paths(Vec::new())
+        };
+        let updated_role = editor
+            .set_paths(&self.delegatee, paths)
+            .context(error::DelegateeNotFound {
+                role: self.delegatee.clone(),
+            })?
+            .version(self.version)
+            .expires(self.expires)
+            .sign(&self.keys)
+            .context(error::SignRepo)?;
+        let metadata_destination_out = &self.outdir.join("metadata");
+        updated_role
+            .write(metadata_destination_out, false)
+            .context(error::WriteRoles {
+                roles: [role.to_string()].to_vec(),
+            })?;
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@
 //! This module parses a key source command line parameter as a URL, relative to `file://$PWD`,
 //! then matches the URL scheme against ones we understand.
 //!
-//! Currently supported key sources are local files and AWS SSM.
+//! Currently supported key sources are local files, AWS SSM, and AWS KMS.
 //!
 //! Examples of currently supported formats:
 //!
@@ -18,12 +18,15 @@
 //! "file:///./a/key/file/here" (notice the 3 slashes after the colon)
 //!
 //! Keys stored in AWS SSM use a special format:
-//! "aws-ssm://<aws profile>/key/path/in/SSM?kms-key-id=12345"
+//! "aws-ssm://<aws profile>/key/path/in/SSM?kms-key-id=12345&region=us-west-2"
 //!
 //! "kms-key-id" is an optional parameter you can provide. It is only used for writing
 //! a key back to SSM. If it is not provided, the default key associated with your AWS
 //! account is used.
 //!
+//! "region" is also optional. If it is not provided, the region of the profile is used, or
+//! else the region from your environment.
+//!
 //! For example, using a profile "foo" and a key located at "a/key"
 //! "aws-ssm://foo/a/key"
 //!
@@ -32,6 +35,12 @@
 //!
 //! You may also skip the profile bit and just use your local environment's default profile:
 //! "aws-ssm:///a/key" (notice the 3 slashes after the colon)
+//!
+//! Keys in AWS KMS are referred to by their key ID, alias, or ARN, and never leave KMS:
+//! "aws-kms://<aws profile>/<key id>?signing-algorithm=ECDSA_SHA_256"
+//!
+//! "signing-algorithm" is optional, and defaults to "RSASSA_PSS_SHA_256" for RSA keys. Use
+//! "ECDSA_SHA_256" for "ECC_NIST_P256" keys.
 
 use crate::error::{self, Result};
 use snafu::ResultExt;
@@ -69,6 +78,13 @@ pub(crate) fn parse_key_source(input: &str) -> Result<Box<dyn KeySource>> {
                     Some(s.to_owned())
                 }
             }),
+            region: url.query_pairs().find_map(|(k, v)| {
+                if k == "region" {
+                    Some(v.into_owned())
+                } else {
+                    None
+                }
+            }),
             parameter_name: url.path().to_owned(),
             // If a key ID isn't provided, the system uses the default key
             // associated with your AWS account.
@@ -95,7 +111,18 @@ pub(crate) fn parse_key_source(input: &str) -> Result<Box<dyn KeySource>> {
                 url.path()[1..].to_string()
             },
             client: None,
-            signing_algorithm: KmsSigningAlgorithm::RsassaPssSha256,
+            signing_algorithm: match url
+                .query_pairs()
+                .find(|(k, _)| k == "signing-algorithm")
+                .map(|(_, v)| v)
+                .as_deref()
+            {
+                None | Some("RSASSA_PSS_SHA_256") => KmsSigningAlgorithm::RsassaPssSha256,
+                Some("ECDSA_SHA_256") => KmsSigningAlgorithm::EcdsaSha256,
+                Some(algorithm) => {
+                    return error::UnrecognizedSigningAlgorithm { algorithm }.fail();
+                }
+            },
         })),
         _ => error::UnrecognizedScheme {
             scheme: url.scheme(),
@@ -1,19 +1,20 @@
 // Copyright 2019 Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::build_targets;
 use crate::common::UNUSED_URL;
 use crate::datetime::parse_datetime;
 use crate::error::{self, Result};
 use crate::source::parse_key_source;
+use crate::{apply_target_custom, build_targets, parse_hash_algorithm};
 use chrono::{DateTime, Utc};
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use std::fs::File;
 use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 use tough::editor::signed::PathExists;
 use tough::editor::RepositoryEditor;
+use tough::hash::HashAlgorithm;
 use tough::key_source::KeySource;
 use tough::{ExpirationEnforcement, RepositoryLoader};
 use url::Url;
@@ -24,25 +25,25 @@ pub(crate) struct UpdateArgs {
     #[structopt(short = "k", long = "key", required = true, parse(try_from_str = parse_key_source))]
     keys: Vec<Box<dyn KeySource>>,
 
-    /// Version of snapshot.json file
+    /// Version of snapshot.json file; defaults to one more than the current version
     #[structopt(long = "snapshot-version")]
-    snapshot_version: NonZeroU64,
+    snapshot_version: Option<NonZeroU64>,
     /// Expiration of snapshot.json file; can be in full RFC 3339 format, or something like 'in
     /// 7 days'
     #[structopt(long = "snapshot-expires", parse(try_from_str = parse_datetime))]
     snapshot_expires: DateTime<Utc>,
 
-    /// Version of targets.json file
+    /// Version of targets.json file; defaults to one more than the current version
     #[structopt(long = "targets-version")]
-    targets_version: NonZeroU64,
+    targets_version: Option<NonZeroU64>,
     /// Expiration of targets.json file; can be in full RFC 3339 format, or something like 'in
     /// 7 days'
     #[structopt(long = "targets-expires", parse(try_from_str = parse_datetime))]
     targets_expires: DateTime<Utc>,
 
-    /// Version of timestamp.json file
+    /// Version of timestamp.json file; defaults to one more than the current version
     #[structopt(long = "timestamp-version")]
-    timestamp_version: NonZeroU64,
+    timestamp_version: Option<NonZeroU64>,
     /// Expiration of timestamp.json file; can be in full RFC 3339 format, or something like 'in
     /// 7 days'
     #[structopt(long = "timestamp-expires", parse(try_from_str = parse_datetime))]
@@ -60,12 +61,41 @@ pub(crate) struct UpdateArgs {
     #[structopt(short = "t", long = "add-targets")]
     targets_indir: Option<PathBuf>,
 
+    /// Name of a target to remove from the repository's targets.json. May be repeated
+    #[structopt(long = "remove-target")]
+    remove_targets: Vec<String>,
+
+    /// Custom metadata for an added target, given as the target name followed by `key=value`.
+    /// May be repeated. Values that parse as JSON are stored as JSON, otherwise as strings
+    #[structopt(
+        long = "target-custom",
+        number_of_values = 2,
+        value_names = &["target", "key=value"],
+        requires = "targets-indir"
+    )]
+    target_custom: Vec<String>,
+
+    /// Hash algorithms to list a digest for in each added target's metadata, as well as "sha256",
+    /// which is always listed. May be repeated, or given as a comma-separated list. Options are
+    /// "sha256" and "sha512"
+    #[structopt(
+        long = "hash-algorithms",
+        use_delimiter = true,
+        parse(try_from_str = parse_hash_algorithm)
+    )]
+    hash_algorithms: Vec<Box<dyn HashAlgorithm>>,
+
     /// Behavior when a target exists with the same name and hash in the desired repository
     /// directory, for example from another repository when you're sharing target directories.
     /// Options are "replace", "fail", and "skip"
     #[structopt(long = "target-path-exists", default_value = "skip")]
     target_path_exists: PathExists,
 
+    /// Refuse to write the updated metadata unless every target it lists is present, with a
+    /// matching hash, in the output directory's targets directory
+    #[structopt(long = "check-targets")]
+    check_targets: bool,
+
     /// Follow symbolic links in the given directory when adding targets
     #[structopt(short = "f", long = "follow")]
     follow: bool,
@@ -82,6 +112,12 @@ pub(crate) struct UpdateArgs {
     #[structopt(short = "o", long = "outdir")]
     outdir: PathBuf,
 
+    /// Also write versions.json to the metadata directory: an unsigned index of each role's
+    /// version, file name, length, and SHA-256 digest, for mirrors and clients to discover the
+    /// current metadata without guessing version numbers
+    #[structopt(long = "write-versions")]
+    write_versions: bool,
+
     /// Incoming metadata from delegatee
     #[structopt(short = "i", long = "incoming-metadata")]
     indir: Option<Url>,
@@ -95,6 +131,27 @@ pub(crate) struct UpdateArgs {
     allow_expired_repo: bool,
 }
 
+/// The versions of the top-level roles written by `tuftool update`.
+struct Versions {
+    targets: NonZeroU64,
+    snapshot: NonZeroU64,
+    timestamp: NonZeroU64,
+}
+
+/// Returns `version` if it was given, or else the version after `current`.
+fn version_or_next(version: Option<NonZeroU64>, current: NonZeroU64) -> Result<NonZeroU64> {
+    match version {
+        Some(version) => Ok(version),
+        None => NonZeroU64::new(
+            current
+                .get()
+                .checked_add(1)
+                .context(error::VersionOverflow)?,
+        )
+        .context(error::VersionZero),
+    }
+}
+
 fn expired_repo_warning<P: AsRef<Path>>(path: P) {
     #[rustfmt::skip]
     eprintln!("\
@@ -121,23 +178,46 @@ impl UpdateArgs {
         .expiration_enforcement(expiration_enforcement)
         .load()
         .context(error::RepoLoad)?;
+        for name in &self.remove_targets {
+            ensure!(
+                repository.targets().signed.targets.contains_key(name),
+                error::TargetNotFound { target: name }
+            );
+        }
+        let versions = Versions {
+            targets: version_or_next(self.targets_version, repository.targets().signed.version)?,
+            snapshot: version_or_next(self.snapshot_version, repository.snapshot().signed.version)?,
+            timestamp: version_or_next(
+                self.timestamp_version,
+                repository.timestamp().signed.version,
+            )?,
+        };
         self.update_metadata(
             RepositoryEditor::from_repo(&self.root, repository)
                 .context(error::EditorFromRepo { path: &self.root })?,
+            &versions,
         )
     }
 
-    fn update_metadata(&self, mut editor: RepositoryEditor) -> Result<()> {
+    fn update_metadata(&self, mut editor: RepositoryEditor, versions: &Versions) -> Result<()> {
         editor
-            .targets_version(self.targets_version)
+            .targets_version(versions.targets)
             .context(error::DelegationStructure)?
             .targets_expires(self.targets_expires)
             .context(error::DelegationStructure)?
-            .snapshot_version(self.snapshot_version)
+            .snapshot_version(versions.snapshot)
             .snapshot_expires(self.snapshot_expires)
-            .timestamp_version(self.timestamp_version)
+            .timestamp_version(versions.timestamp)
             .timestamp_expires(self.timestamp_expires);
 
+        // Remove targets before adding any, so that a target that's both removed and added is
+        // replaced
+        for name in &self.remove_targets {
+            editor
+                .remove_target(name)
+                .context(error::DelegationStructure)?;
+        }
+
         // If the "add-targets" argument was passed, build a list of targets
         // and add them to the repository. If a user specifies job count we
         // override the default, which is the number of cores.
@@ -149,7 +229,9 @@ impl UpdateArgs {
                     .context(error::InitializeThreadPool)?;
             }
 
-            let new_targets = build_targets(&targets_indir, self.follow)?;
+            let mut new_targets =
+                build_targets(&targets_indir, self.follow, &self.hash_algorithms)?;
+            apply_target_custom(&mut new_targets, &self.target_custom)?;
 
             for (filename, target) in new_targets {
                 editor
@@ -194,11 +276,27 @@ impl UpdateArgs {
                 })?;
         };
 
+        if self.check_targets {
+            let targets_outdir = &self.outdir.join("targets");
+            signed_repo
+                .check_targets(targets_outdir)
+                .context(error::TargetsIncomplete {
+                    directory: targets_outdir,
+                })?;
+        }
+
         // Write the metadata to the outdir
         let metadata_dir = &self.outdir.join("metadata");
         signed_repo.write(metadata_dir).context(error::WriteRepo {
             directory: metadata_dir,
         })?;
+        if self.write_versions {
+            signed_repo
+                .write_versions(metadata_dir)
+                .context(error::WriteRepo {
+                    directory: metadata_dir,
+                })?;
+        }
 
         Ok(())
     }
@@ -1,11 +1,11 @@
 // Copyright 2019 Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::build_targets;
 use crate::common::load_metadata_repo;
 use crate::datetime::parse_datetime;
 use crate::error::{self, Result};
 use crate::source::parse_key_source;
+use crate::{build_targets, parse_hash_algorithm};
 use chrono::{DateTime, Utc};
 use snafu::ResultExt;
 use std::num::NonZeroU64;
@@ -14,6 +14,7 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use tough::editor::signed::PathExists;
 use tough::editor::targets::TargetsEditor;
+use tough::hash::HashAlgorithm;
 use tough::key_source::KeySource;
 use url::Url;
 
@@ -52,6 +53,16 @@ pub(crate) struct UpdateTargetsArgs {
     #[structopt(short = "f", long = "follow")]
     follow: bool,
 
+    /// Hash algorithms to list a digest for in each added target's metadata, as well as "sha256",
+    /// which is always listed. May be repeated, or given as a comma-separated list. Options are
+    /// "sha256" and "sha512"
+    #[structopt(
+        long = "hash-algorithms",
+        use_delimiter = true,
+        parse(try_from_str = parse_hash_algorithm)
+    )]
+    hash_algorithms: Vec<Box<dyn HashAlgorithm>>,
+
     /// Number of target hashing threads to run when adding targets
     /// (default: number of cores)
     // No default is specified in structopt here. This is because rayon
@@ -90,7 +101,7 @@ impl UpdateTargetsArgs {
                     .context(error::InitializeThreadPool)?;
             }
 
-            let new_targets = build_targets(&targets_indir, self.follow)?;
+            let new_targets = build_targets(&targets_indir, self.follow, &self.hash_algorithms)?;
 
             for (filename, target) in new_targets {
                 editor.add_target(&filename, target);
@@ -0,0 +1,46 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod test_utils;
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs::File;
+use tempfile::TempDir;
+use tough::schema::{Root, Signed};
+
+#[test]
+// Ensure a messy metadata file is rewritten in a tidy form and its signatures still verify
+fn canonicalize() {
+    let out_dir = TempDir::new().unwrap();
+    let root_json = out_dir.path().join("root.json");
+    let original: Signed<Root> = serde_json::from_reader(
+        File::open(test_utils::test_data().join("simple-rsa").join("root.json")).unwrap(),
+    )
+    .unwrap();
+    // Struct fields serialize in declaration order rather than sorted, all on one line
+    let messy = format!("  {}  ", serde_json::to_string(&original).unwrap());
+    std::fs::write(&root_json, &messy).unwrap();
+
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&["canonicalize", root_json.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let tidy = std::fs::read_to_string(&root_json).unwrap();
+    let value: Signed<Value> = serde_json::from_str(&messy).unwrap();
+    assert_eq!(tidy, serde_json::to_string_pretty(&value).unwrap() + "\n");
+    assert!(tidy.starts_with("{\n  \"signed\": {\n    \"_type\": \"root\",\n"));
+
+    let root: Signed<Root> = serde_json::from_str(&tidy).unwrap();
+    assert_eq!(root, original);
+    root.signed.verify_role(&root).unwrap();
+
+    // Canonicalizing again doesn't change anything
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&["canonicalize", root_json.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(&root_json).unwrap(), tidy);
+}
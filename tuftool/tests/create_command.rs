@@ -3,11 +3,16 @@
 
 mod test_utils;
 
+use assert_cmd::assert::Assert;
 use assert_cmd::Command;
 use chrono::{Duration, Utc};
+use ring::digest::{digest, SHA512};
 use std::fs::File;
+use std::path::Path;
 use tempfile::TempDir;
 use test_utils::dir_url;
+use tough::bundle::MetadataBundle;
+use tough::editor::signed::VersionsIndex;
 use tough::RepositoryLoader;
 
 #[test]
@@ -106,6 +111,191 @@ fn create_command() {
         snapshot_version
     );
     assert_eq!(repo.snapshot().signatures.len(), 1);
+
+    // The versions index is only written on request
+    assert!(!repo_dir
+        .path()
+        .join("metadata")
+        .join("versions.json")
+        .exists());
+}
+
+/// Returns a `tuftool create` command for the reference implementation's targets, signed with
+/// snakeoil.pem and writing the repository to `repo_dir`. Expirations and versions aren't given.
+fn create_base(repo_dir: &Path) -> Command {
+    let targets_input_dir = test_utils::test_data()
+        .join("tuf-reference-impl")
+        .join("targets");
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let root_key = test_utils::test_data().join("snakeoil.pem");
+    let mut command = Command::cargo_bin("tuftool").unwrap();
+    command.args(&[
+        "create",
+        "-t",
+        targets_input_dir.to_str().unwrap(),
+        "-o",
+        repo_dir.to_str().unwrap(),
+        "-k",
+        root_key.to_str().unwrap(),
+        "--root",
+        root_json.to_str().unwrap(),
+    ]);
+    command
+}
+
+/// Runs `tuftool create` with the reference implementation's targets and `extra_args`, writing the
+/// repository to `repo_dir`. Targets, snapshot and timestamp are at versions 3, 5 and 7.
+fn create_with_args(repo_dir: &Path, extra_args: &[&str]) -> Assert {
+    create_base(repo_dir)
+        .args(&[
+            "--targets-expires",
+            "in 7 days",
+            "--targets-version",
+            "3",
+            "--snapshot-expires",
+            "in 7 days",
+            "--snapshot-version",
+            "5",
+            "--timestamp-expires",
+            "in 7 days",
+            "--timestamp-version",
+            "7",
+        ])
+        .args(extra_args)
+        .assert()
+}
+
+#[test]
+// Ensure the versions index written by `--write-versions` matches the metadata written with it
+fn create_with_write_versions() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let repo_dir = TempDir::new().unwrap();
+    let metadata_dir = repo_dir.path().join("metadata");
+    create_with_args(repo_dir.path(), &["--write-versions"]).success();
+
+    let repo = RepositoryLoader::new(
+        File::open(root_json).unwrap(),
+        dir_url(&metadata_dir),
+        dir_url(repo_dir.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    let index: VersionsIndex =
+        serde_json::from_reader(File::open(metadata_dir.join("versions.json")).unwrap()).unwrap();
+
+    let versions = index
+        .roles
+        .iter()
+        .map(|(role, indexed)| (role.as_str(), indexed.version.get()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        versions,
+        [
+            ("root", repo.root().signed.version.get()),
+            ("snapshot", 5),
+            ("targets", 3),
+            ("timestamp", 7)
+        ]
+    );
+    // The root uses consistent snapshots, so every file but timestamp.json has a version prefix
+    assert_eq!(index.roles["targets"].filename, "3.targets.json");
+    assert_eq!(index.roles["snapshot"].filename, "5.snapshot.json");
+    assert_eq!(index.roles["timestamp"].filename, "timestamp.json");
+    for indexed in index.roles.values() {
+        let file = std::fs::read(metadata_dir.join(&indexed.filename)).unwrap();
+        assert_eq!(file.len() as u64, indexed.length);
+        assert_eq!(
+            ring::digest::digest(&ring::digest::SHA256, &file).as_ref(),
+            indexed.sha256.as_ref() as &[u8]
+        );
+    }
+    let snapshot_meta = &repo.timestamp().signed.meta["snapshot.json"];
+    assert_eq!(snapshot_meta.hashes.sha256, index.roles["snapshot"].sha256);
+}
+
+#[test]
+// Ensure the bundle written by `--bundle` loads, and holds the metadata files byte for byte
+fn create_with_bundle() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let repo_dir = TempDir::new().unwrap();
+    let metadata_dir = repo_dir.path().join("metadata");
+    let bundle_path = repo_dir.path().join("bundle.json");
+    create_with_args(
+        repo_dir.path(),
+        &["--bundle", bundle_path.to_str().unwrap()],
+    )
+    .success();
+
+    let bundle = MetadataBundle::from_reader(File::open(&bundle_path).unwrap()).unwrap();
+    let repo = RepositoryLoader::new(
+        File::open(&root_json).unwrap(),
+        dir_url(repo_dir.path().join("unused")),
+        dir_url(repo_dir.path().join("targets")),
+    )
+    .transport(bundle.clone())
+    .load()
+    .unwrap();
+    assert_eq!(repo.snapshot().signed.version.get(), 5);
+    assert_eq!(repo.targets().signed.version.get(), 3);
+    assert_eq!(repo.timestamp().signed.version.get(), 7);
+
+    let root_version = repo.root().signed.version;
+    for (member, file) in &[
+        ("root", format!("{}.root.json", root_version)),
+        ("timestamp", "timestamp.json".to_owned()),
+        ("snapshot", "5.snapshot.json".to_owned()),
+        ("targets", "3.targets.json".to_owned()),
+    ] {
+        assert_eq!(
+            bundle.member(member).unwrap(),
+            std::fs::read(metadata_dir.join(file)).unwrap().as_slice()
+        );
+    }
+}
+
+#[test]
+// Ensure `--hash-algorithms` lists a digest for each algorithm, which the client verifies targets with
+fn create_with_hash_algorithms() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let targets_input_dir = test_utils::test_data()
+        .join("tuf-reference-impl")
+        .join("targets");
+    let repo_dir = TempDir::new().unwrap();
+    create_with_args(repo_dir.path(), &["--hash-algorithms", "sha256,sha512"]).success();
+
+    let repo = RepositoryLoader::new(
+        File::open(root_json).unwrap(),
+        dir_url(repo_dir.path().join("metadata")),
+        dir_url(repo_dir.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    assert_eq!(repo.targets().signed.targets.len(), 3);
+    for (name, target) in &repo.targets().signed.targets {
+        let contents = std::fs::read(targets_input_dir.join(name)).unwrap();
+        assert_eq!(
+            target.hashes.get("sha512").unwrap(),
+            digest(&SHA512, &contents).as_ref()
+        );
+        assert_eq!(
+            test_utils::read_to_end(repo.read_target(name).unwrap().unwrap()),
+            contents
+        );
+    }
+}
+
+#[test]
+// Ensure `--bundle-only` writes the bundle instead of the metadata files
+fn create_with_bundle_only() {
+    let repo_dir = TempDir::new().unwrap();
+    let bundle_path = repo_dir.path().join("bundle.json");
+    create_with_args(
+        repo_dir.path(),
+        &["--bundle", bundle_path.to_str().unwrap(), "--bundle-only"],
+    )
+    .success();
+    MetadataBundle::from_reader(File::open(&bundle_path).unwrap()).unwrap();
+    assert!(!repo_dir.path().join("metadata").exists());
 }
 
 #[test]
@@ -176,3 +366,148 @@ fn create_with_no_key() {
         .assert()
         .failure();
 }
+
+#[test]
+// Ensure custom target fields given on the command line end up in targets.json
+fn create_with_target_custom() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let repo_dir = TempDir::new().unwrap();
+    create_with_args(
+        repo_dir.path(),
+        &[
+            "--target-custom",
+            "file1.txt",
+            "owner=alice",
+            "--target-custom",
+            "file1.txt",
+            "priority=42",
+            "--target-custom",
+            "file2.txt",
+            "owner=bob",
+        ],
+    )
+    .success();
+
+    let repo = RepositoryLoader::new(
+        File::open(root_json).unwrap(),
+        dir_url(repo_dir.path().join("metadata")),
+        dir_url(repo_dir.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+
+    let targets = &repo.targets().signed.targets;
+    assert_eq!(targets["file1.txt"].custom["owner"], "alice");
+    assert_eq!(targets["file1.txt"].custom["priority"], 42);
+    assert_eq!(targets["file2.txt"].custom["owner"], "bob");
+    assert!(targets["file3.txt"].custom.is_empty());
+}
+
+#[test]
+// Ensure custom target fields for a target that isn't being added are rejected
+fn create_with_target_custom_unknown_target() {
+    let repo_dir = TempDir::new().unwrap();
+    create_with_args(
+        repo_dir.path(),
+        &["--target-custom", "no-such-file.txt", "owner=alice"],
+    )
+    .failure();
+}
+
+#[test]
+// Ensure expirations given relative to now are computed from the time of the command, and can be
+// mixed with absolute expirations
+fn create_with_relative_expiration() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let repo_dir = TempDir::new().unwrap();
+    let targets_expiration = Utc::now().checked_add_signed(Duration::days(13)).unwrap();
+
+    let before = Utc::now();
+    create_base(repo_dir.path())
+        .args(&[
+            "--targets-expires",
+            targets_expiration.to_rfc3339().as_str(),
+            "--targets-version",
+            "1",
+            "--snapshot-expires-in",
+            "2w",
+            "--snapshot-version",
+            "1",
+            "--timestamp-expires-in",
+            "36h",
+            "--timestamp-version",
+            "1",
+        ])
+        .assert()
+        .success();
+    let after = Utc::now();
+
+    let repo = RepositoryLoader::new(
+        File::open(root_json).unwrap(),
+        dir_url(repo_dir.path().join("metadata")),
+        dir_url(repo_dir.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    assert_eq!(repo.targets().signed.expires, targets_expiration);
+    let snapshot_expires = repo.snapshot().signed.expires;
+    assert!(snapshot_expires >= before + Duration::weeks(2));
+    assert!(snapshot_expires <= after + Duration::weeks(2));
+    let timestamp_expires = repo.timestamp().signed.expires;
+    assert!(timestamp_expires >= before + Duration::hours(36));
+    assert!(timestamp_expires <= after + Duration::hours(36));
+}
+
+#[test]
+// Ensure an expiration can't be given both absolutely and relatively, and that one is required
+fn create_with_conflicting_expiration() {
+    let args = [
+        "create",
+        "-t",
+        "input/dir/does/not/matter",
+        "-o",
+        "output/dir/does/not/matter",
+        "-k",
+        "key/does/not/matter",
+        "--root",
+        "root/does/not/matter",
+        "--targets-expires",
+        "in 7 days",
+        "--targets-version",
+        "1",
+        "--snapshot-version",
+        "1",
+        "--timestamp-expires",
+        "in 7 days",
+        "--timestamp-version",
+        "1",
+    ];
+
+    // Neither form of the snapshot expiration
+    let output = Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&args)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("--snapshot-expires"));
+
+    // Both forms of the snapshot expiration
+    let output = Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&args)
+        .args(&[
+            "--snapshot-expires",
+            "in 7 days",
+            "--snapshot-expires-in",
+            "7d",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("cannot be used with"));
+}
@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod test_utils;
+use assert_cmd::Command;
+use std::fs::File;
+use tempfile::TempDir;
+use tough::schema::{Root, Signed};
+
+#[test]
+// Ensure duplicate signatures are collapsed to one and the remaining signature still verifies
+fn dedup_signatures() {
+    let out_dir = TempDir::new().unwrap();
+    let root_json = out_dir.path().join("root.json");
+    let original: Signed<Root> = serde_json::from_reader(
+        File::open(test_utils::test_data().join("simple-rsa").join("root.json")).unwrap(),
+    )
+    .unwrap();
+    let mut root = original.clone();
+    let duplicate = root.signatures[0].clone();
+    root.signatures.push(duplicate.clone());
+    root.signatures.push(duplicate);
+    serde_json::to_writer_pretty(File::create(&root_json).unwrap(), &root).unwrap();
+
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&["dedup-signatures", root_json.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let root: Signed<Root> = serde_json::from_reader(File::open(&root_json).unwrap()).unwrap();
+    assert_eq!(root, original);
+    root.signed.verify_role(&root).unwrap();
+}
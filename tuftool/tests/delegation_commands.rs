@@ -9,6 +9,7 @@ use std::fs::File;
 use std::path::Path;
 use tempfile::TempDir;
 use test_utils::dir_url;
+use tough::schema::PathSet;
 use tough::RepositoryLoader;
 
 fn create_repo<P: AsRef<Path>>(repo_dir: P) {
@@ -1430,3 +1431,163 @@ fn remove_role_recursive_command() {
     assert!(repo.delegated_role("A").is_none());
     assert!(repo.delegated_role("B").is_none());
 }
+
+#[test]
+// Ensure we can set the paths delegated to a role, and that a role that isn't delegated is an error
+fn set_paths_command() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let root_key = test_utils::test_data().join("snakeoil.pem");
+    let targets_key = test_utils::test_data().join("targetskey");
+    let repo_dir = TempDir::new().unwrap();
+
+    // Create a repo using tuftool and the reference tuf implementation data
+    create_repo(repo_dir.path());
+
+    let expiration = Utc::now().checked_add_signed(Duration::days(4)).unwrap();
+    let metadata_base_url = &dir_url(repo_dir.path().join("metadata"));
+    let meta_out = TempDir::new().unwrap();
+
+    // create role A
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "delegation",
+            "--signing-role",
+            "A",
+            "create-role",
+            "-o",
+            meta_out.path().to_str().unwrap(),
+            "-k",
+            targets_key.to_str().unwrap(),
+            "-e",
+            expiration.to_rfc3339().as_str(),
+            "-v",
+            "1",
+        ])
+        .assert()
+        .success();
+
+    // add role A to targets metadata, delegating every path, and sign entire repo
+    let new_repo_dir = TempDir::new().unwrap();
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "delegation",
+            "--signing-role",
+            "targets",
+            "add-role",
+            "-o",
+            new_repo_dir.path().to_str().unwrap(),
+            "-i",
+            dir_url(&meta_out.path().join("metadata")).as_str(),
+            "-k",
+            root_key.to_str().unwrap(),
+            "--root",
+            root_json.to_str().unwrap(),
+            "--metadata-url",
+            metadata_base_url.as_str(),
+            "-e",
+            expiration.to_rfc3339().as_str(),
+            "--delegated-role",
+            "A",
+            "-t",
+            "1",
+            "-v",
+            "2",
+            "-p",
+            "*",
+            "--sign-all",
+            "--snapshot-expires",
+            expiration.to_rfc3339().as_str(),
+            "--snapshot-version",
+            "2",
+            "--timestamp-expires",
+            expiration.to_rfc3339().as_str(),
+            "--timestamp-version",
+            "2",
+        ])
+        .assert()
+        .success();
+    let updated_metadata_base_url = &dir_url(new_repo_dir.path().join("metadata"));
+
+    // narrow the paths delegated to A
+    let set_paths_out = TempDir::new().unwrap();
+    let set_paths = |role: &str| {
+        Command::cargo_bin("tuftool")
+            .unwrap()
+            .args(&[
+                "delegation",
+                "--signing-role",
+                "targets",
+                "set-paths",
+                "-o",
+                set_paths_out.path().to_str().unwrap(),
+                "-e",
+                expiration.to_rfc3339().as_str(),
+                "-v",
+                "3",
+                "-k",
+                root_key.to_str().unwrap(),
+                "--root",
+                root_json.to_str().unwrap(),
+                "--metadata-url",
+                updated_metadata_base_url.as_str(),
+                "--delegated-role",
+                role,
+                "-p",
+                "file1.txt",
+                "-p",
+                "file2.txt",
+            ])
+            .assert()
+    };
+    set_paths("Z").failure();
+    set_paths("A").success();
+
+    // sign the change to targets as repo owner
+    let new_repo_dir = TempDir::new().unwrap();
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "update",
+            "--role",
+            "targets",
+            "-o",
+            new_repo_dir.path().to_str().unwrap(),
+            "-i",
+            dir_url(&set_paths_out.path().join("metadata")).as_str(),
+            "-k",
+            root_key.to_str().unwrap(),
+            "--root",
+            root_json.to_str().unwrap(),
+            "--metadata-url",
+            updated_metadata_base_url.as_str(),
+            "--targets-expires",
+            expiration.to_rfc3339().as_str(),
+            "--targets-version",
+            "3",
+            "--snapshot-expires",
+            expiration.to_rfc3339().as_str(),
+            "--snapshot-version",
+            "3",
+            "--timestamp-expires",
+            expiration.to_rfc3339().as_str(),
+            "--timestamp-version",
+            "3",
+        ])
+        .assert()
+        .success();
+
+    // Load the updated repo
+    let repo = RepositoryLoader::new(
+        File::open(&root_json).unwrap(),
+        dir_url(new_repo_dir.path().join("metadata")),
+        dir_url(new_repo_dir.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    assert_eq!(
+        repo.delegated_role("A").unwrap().paths,
+        PathSet::Paths(vec!["file1.txt".to_owned(), "file2.txt".to_owned()])
+    );
+}
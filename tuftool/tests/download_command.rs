@@ -59,7 +59,7 @@ fn download_command(metadata_base_url: Url, targets_base_url: Url) {
     // Assert the files are exactly correct
     assert_file_match(&outdir, "file1.txt");
     assert_file_match(&outdir, "file2.txt");
-    // TODO - assert_file_match(&outdir, "file3.txt"); when delegate support lands.
+    assert_file_match(&outdir, "file3.txt");
 
     // Add "bloop" to the end of file1.txt so that we can prove that the file is truncated when we
     // download the repo a second time into the same outdir.
@@ -88,7 +88,7 @@ fn download_command(metadata_base_url: Url, targets_base_url: Url) {
     // Assert the files are exactly correct
     assert_file_match(&outdir, "file1.txt");
     assert_file_match(&outdir, "file2.txt");
-    // TODO - assert_file_match(&outdir, "file3.txt"); when delegate support lands.
+    assert_file_match(&outdir, "file3.txt");
 }
 
 #[test]
@@ -167,3 +167,112 @@ fn download_command_expired_repo_allow() {
     assert_file_match(&outdir, "file1.txt");
     assert_file_match(&outdir, "file2.txt");
 }
+
+#[test]
+// Ensure that a target that fails verification makes the download command fail, and isn't written to
+// the output directory
+fn download_command_tampered_target() {
+    let repo_dir = TempDir::new().unwrap();
+    let reference = test_utils::test_data().join("tuf-reference-impl");
+    for dir in &["metadata", "targets"] {
+        std::fs::create_dir(repo_dir.path().join(dir)).unwrap();
+        for entry in std::fs::read_dir(reference.join(dir)).unwrap() {
+            let entry = entry.unwrap();
+            std::fs::copy(
+                entry.path(),
+                repo_dir.path().join(dir).join(entry.file_name()),
+            )
+            .unwrap();
+        }
+    }
+    std::fs::write(
+        repo_dir.path().join("targets").join("file1.txt"),
+        "tampered",
+    )
+    .unwrap();
+
+    let outdir = TempDir::new().unwrap();
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "download",
+            "-r",
+            reference
+                .join("metadata")
+                .join("root.json")
+                .to_str()
+                .unwrap(),
+            "--metadata-url",
+            test_utils::dir_url(repo_dir.path().join("metadata")).as_str(),
+            "--targets-url",
+            test_utils::dir_url(repo_dir.path().join("targets")).as_str(),
+            outdir.path().to_str().unwrap(),
+            "-n",
+            "file1.txt",
+        ])
+        .assert()
+        .failure();
+    assert!(!outdir.path().join("file1.txt").exists());
+}
+
+#[test]
+// Ensure that a target name that would escape the output directory is refused
+fn download_command_unsafe_target_name() {
+    let repo_dir = test_utils::test_data().join("tuf-reference-impl");
+    let outdir = TempDir::new().unwrap();
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "download",
+            "-r",
+            repo_dir
+                .join("metadata")
+                .join("root.json")
+                .to_str()
+                .unwrap(),
+            "--metadata-url",
+            test_utils::dir_url(repo_dir.join("metadata")).as_str(),
+            "--targets-url",
+            test_utils::dir_url(repo_dir.join("targets")).as_str(),
+            outdir.path().join("out").to_str().unwrap(),
+            "-n",
+            "../file1.txt",
+        ])
+        .assert()
+        .failure();
+    assert!(!outdir.path().join("file1.txt").exists());
+}
+
+#[test]
+// Ensure that the reason a requested target can't be downloaded is reported
+fn download_command_unlisted_target() {
+    let repo_dir = test_utils::test_data().join("tuf-reference-impl");
+    let outdir = TempDir::new().unwrap();
+    let output = Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "download",
+            "-r",
+            repo_dir
+                .join("metadata")
+                .join("root.json")
+                .to_str()
+                .unwrap(),
+            "--metadata-url",
+            test_utils::dir_url(repo_dir.join("metadata")).as_str(),
+            "--targets-url",
+            test_utils::dir_url(repo_dir.join("targets")).as_str(),
+            outdir.path().to_str().unwrap(),
+            "-n",
+            "file1.txt",
+            "-n",
+            "missing.txt",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Target 'missing.txt' is not listed in any targets metadata"));
+    assert!(!outdir.path().join("file1.txt").exists());
+}
@@ -0,0 +1,153 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod test_utils;
+
+use assert_cmd::Command;
+use chrono::{Duration, Utc};
+use mockito::mock;
+use std::path::Path;
+use std::str::FromStr;
+use tempfile::TempDir;
+use test_utils::dir_url;
+use url::Url;
+
+/// Creates a repository in `repo_dir` containing the named reference targets, with every role at
+/// `version`.
+fn create_repo(repo_dir: &Path, targets: &[&str], version: u64) {
+    let targets_input_dir = repo_dir.join("input");
+    std::fs::create_dir_all(&targets_input_dir).unwrap();
+    for target in targets {
+        std::fs::copy(
+            test_utils::test_data()
+                .join("tuf-reference-impl")
+                .join("targets")
+                .join(target),
+            targets_input_dir.join(target),
+        )
+        .unwrap();
+    }
+    let expiration = Utc::now() + Duration::days(7);
+    let expiration = expiration.to_rfc3339();
+    let version = version.to_string();
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "create",
+            "-t",
+            targets_input_dir.to_str().unwrap(),
+            "-o",
+            repo_dir.join("repo").to_str().unwrap(),
+            "-k",
+            test_utils::test_data()
+                .join("snakeoil.pem")
+                .to_str()
+                .unwrap(),
+            "--root",
+            test_utils::test_data()
+                .join("simple-rsa")
+                .join("root.json")
+                .to_str()
+                .unwrap(),
+            "--targets-expires",
+            &expiration,
+            "--targets-version",
+            &version,
+            "--snapshot-expires",
+            &expiration,
+            "--snapshot-version",
+            &version,
+            "--timestamp-expires",
+            &expiration,
+            "--timestamp-version",
+            &version,
+        ])
+        .assert()
+        .success();
+}
+
+/// Serves every metadata file of the repository in `repo_dir` under `/{prefix}/` on the mock
+/// HTTP server.
+fn serve_metadata(repo_dir: &Path, prefix: &str) -> (Url, Vec<mockito::Mock>) {
+    let metadata_dir = repo_dir.join("repo").join("metadata");
+    let mocks = std::fs::read_dir(&metadata_dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_str().unwrap().to_owned();
+            mock("GET", format!("/{}/{}", prefix, name).as_str())
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(std::fs::read(&path).unwrap())
+                .create()
+        })
+        .collect();
+    let url = Url::from_str(&format!("{}/{}/", mockito::server_url(), prefix)).unwrap();
+    (url, mocks)
+}
+
+fn drift_command(local: &Url, remote: &Url) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "drift",
+            "-r",
+            test_utils::test_data()
+                .join("simple-rsa")
+                .join("root.json")
+                .to_str()
+                .unwrap(),
+            "--local-metadata-url",
+            local.as_str(),
+            "--remote-metadata-url",
+            remote.as_str(),
+        ])
+        .assert()
+}
+
+#[test]
+// Ensure that a local repository one version behind the remote is reported as drifted
+fn drift_local_behind_remote() {
+    let local_dir = TempDir::new().unwrap();
+    create_repo(local_dir.path(), &["file1.txt"], 1);
+    let remote_dir = TempDir::new().unwrap();
+    create_repo(remote_dir.path(), &["file1.txt", "file2.txt"], 2);
+    let (remote_url, _mocks) = serve_metadata(remote_dir.path(), "drift-behind");
+
+    let local_url = dir_url(local_dir.path().join("repo").join("metadata"));
+    let output = drift_command(&local_url, &remote_url)
+        .failure()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for role in &["timestamp", "snapshot", "targets"] {
+        assert!(
+            stdout.contains(&format!("{} version: local 1, remote 2", role)),
+            "{}",
+            stdout
+        );
+    }
+    assert!(!stdout.contains("root version"), "{}", stdout);
+    assert!(
+        stdout.contains("target 'file2.txt' is only in the remote repository"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("file1.txt"), "{}", stdout);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("difference(s) between the local and remote repositories"));
+}
+
+#[test]
+// Ensure that a local repository matching the remote is reported as in sync
+fn drift_in_sync() {
+    let local_dir = TempDir::new().unwrap();
+    create_repo(local_dir.path(), &["file1.txt"], 1);
+    let (remote_url, _mocks) = serve_metadata(local_dir.path(), "drift-in-sync");
+
+    let local_url = dir_url(local_dir.path().join("repo").join("metadata"));
+    drift_command(&local_url, &remote_url)
+        .success()
+        .stdout("No drift detected\n");
+}
@@ -0,0 +1,77 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod test_utils;
+use assert_cmd::Command;
+use std::fs::File;
+use tempfile::TempDir;
+use test_utils::dir_url;
+use tough::schema::{RoleType, Root, Signed};
+use tough::RepositoryLoader;
+
+#[test]
+// Ensure init-repo creates keys, a root, and an empty repository that loads and verifies
+fn init_repo() {
+    let dir = TempDir::new().unwrap();
+    let root_json = dir.path().join("root.json");
+    let key_path = |role: &str| dir.path().join(format!("{}.pem", role));
+    let repo_dir = dir.path().join("repo");
+
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "init-repo",
+            "--root",
+            root_json.to_str().unwrap(),
+            "--root-key",
+            key_path("root").to_str().unwrap(),
+            "--snapshot-key",
+            key_path("snapshot").to_str().unwrap(),
+            "--targets-key",
+            key_path("targets").to_str().unwrap(),
+            "--timestamp-key",
+            key_path("timestamp").to_str().unwrap(),
+            "--root-expires",
+            "in 52 weeks",
+            "--snapshot-expires",
+            "in 7 days",
+            "--targets-expires",
+            "in 7 days",
+            "--timestamp-expires",
+            "in 1 day",
+            "--outdir",
+            repo_dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    // Each role has its own key and a threshold of 1, and root is signed by its key
+    let root: Signed<Root> = serde_json::from_reader(File::open(&root_json).unwrap()).unwrap();
+    root.signed.verify_role(&root).unwrap();
+    let mut keyids = Vec::new();
+    for role in &[
+        RoleType::Root,
+        RoleType::Snapshot,
+        RoleType::Targets,
+        RoleType::Timestamp,
+    ] {
+        let role_keys = &root.signed.roles[role];
+        assert_eq!(role_keys.threshold.get(), 1);
+        assert_eq!(role_keys.keyids.len(), 1);
+        keyids.push(role_keys.keyids[0].clone());
+        assert!(key_path(&role.to_string()).is_file());
+    }
+    keyids.sort();
+    keyids.dedup();
+    assert_eq!(keyids.len(), 4);
+
+    let repo = RepositoryLoader::new(
+        File::open(&root_json).unwrap(),
+        dir_url(repo_dir.join("metadata")),
+        dir_url(repo_dir.join("targets")),
+    )
+    .load()
+    .unwrap();
+    assert!(repo.targets().signed.targets.is_empty());
+    assert_eq!(repo.snapshot().signed.version.get(), 1);
+}
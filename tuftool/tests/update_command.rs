@@ -6,6 +6,7 @@ mod test_utils;
 use assert_cmd::assert::Assert;
 use assert_cmd::Command;
 use chrono::{DateTime, Duration, Utc};
+use ring::digest::{digest, SHA512};
 use std::fs::File;
 use std::path::Path;
 use tempfile::TempDir;
@@ -213,6 +214,144 @@ fn update_command_with_new_targets() {
     assert_eq!(repo.timestamp().signed.expires, new_timestamp_expiration);
 }
 
+#[test]
+// Ensure `--hash-algorithms` lists a digest for each algorithm in the targets that are added
+fn update_command_with_hash_algorithms() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let root_key = test_utils::test_data().join("snakeoil.pem");
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::days(4))
+        .unwrap()
+        .to_rfc3339();
+    let new_targets_input_dir = test_utils::test_data().join("targets");
+    let update_out = TempDir::new().unwrap();
+
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "update",
+            "-t",
+            new_targets_input_dir.to_str().unwrap(),
+            "--hash-algorithms",
+            "sha512",
+            "-o",
+            update_out.path().to_str().unwrap(),
+            "-k",
+            root_key.to_str().unwrap(),
+            "--root",
+            root_json.to_str().unwrap(),
+            "--metadata-url",
+            dir_url(repo_dir.path().join("metadata")).as_str(),
+            "--targets-expires",
+            expiration.as_str(),
+            "--snapshot-expires",
+            expiration.as_str(),
+            "--timestamp-expires",
+            expiration.as_str(),
+        ])
+        .assert()
+        .success();
+
+    let repo = RepositoryLoader::new(
+        File::open(root_json).unwrap(),
+        dir_url(update_out.path().join("metadata")),
+        dir_url(update_out.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    let targets = &repo.targets().signed.targets;
+    for name in &["file4.txt", "file5.txt", "file6.txt"] {
+        let contents = std::fs::read(new_targets_input_dir.join(name)).unwrap();
+        assert_eq!(
+            targets[*name].hashes.get("sha512").unwrap(),
+            digest(&SHA512, &contents).as_ref()
+        );
+        assert_eq!(
+            test_utils::read_to_end(repo.read_target(name).unwrap().unwrap()),
+            contents
+        );
+    }
+    // Targets that were already listed are unchanged
+    assert!(targets["file1.txt"].hashes.get("sha512").is_none());
+}
+
+/// Runs `tuftool update` on the repo in `repo_dir`, removing `remove_targets` and leaving the
+/// versions to default, and writes the updated repository to `update_out`.
+fn update_removing_targets(repo_dir: &Path, update_out: &Path, remove_targets: &[&str]) -> Assert {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let root_key = test_utils::test_data().join("snakeoil.pem");
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::days(4))
+        .unwrap()
+        .to_rfc3339();
+    let metadata_base_url = &dir_url(repo_dir.join("metadata"));
+    let mut cmd = Command::cargo_bin("tuftool").unwrap();
+    cmd.args(&[
+        "update",
+        "-o",
+        update_out.to_str().unwrap(),
+        "-k",
+        root_key.to_str().unwrap(),
+        "--root",
+        root_json.to_str().unwrap(),
+        "--metadata-url",
+        metadata_base_url.as_str(),
+        "--targets-expires",
+        expiration.as_str(),
+        "--snapshot-expires",
+        expiration.as_str(),
+        "--timestamp-expires",
+        expiration.as_str(),
+    ]);
+    for target in remove_targets {
+        cmd.args(&["--remove-target", target]);
+    }
+    cmd.assert()
+}
+
+#[test]
+// Ensure that targets can be removed by `tuftool update`, and that each role's version is bumped
+// when it isn't given
+fn update_command_remove_targets() {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    let update_out = TempDir::new().unwrap();
+
+    update_removing_targets(
+        repo_dir.path(),
+        update_out.path(),
+        &["file1.txt", "file3.txt"],
+    )
+    .success();
+
+    let repo = RepositoryLoader::new(
+        File::open(root_json).unwrap(),
+        dir_url(update_out.path().join("metadata")),
+        dir_url(update_out.path().join("targets")),
+    )
+    .load()
+    .unwrap();
+    let targets: Vec<_> = repo.targets().signed.targets.keys().collect();
+    assert_eq!(targets, ["file2.txt"]);
+    assert_eq!(repo.targets().signed.version.get(), 18);
+    assert_eq!(repo.snapshot().signed.version.get(), 26);
+    assert_eq!(repo.timestamp().signed.version.get(), 32);
+}
+
+#[test]
+// Ensure that removing a target that isn't in the repository fails
+fn update_command_remove_missing_target() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    let update_out = TempDir::new().unwrap();
+
+    update_removing_targets(repo_dir.path(), update_out.path(), &["file9.txt"]).failure();
+    assert!(!update_out.path().join("metadata").exists());
+}
+
 #[test]
 // Ensure that the update command fails if none of the keys we give it match up with root.json.
 fn update_with_incorrect_key() {
@@ -383,3 +522,84 @@ fn update_command_expired_repo_allow() {
     assert_eq!(repo.targets().signed.expires, update_expected.5);
     assert_eq!(repo.targets().signed.version.get(), update_expected.6);
 }
+
+/// Runs `tuftool update --check-targets`, writing the updated repository to `update_out`.
+fn update_checking_targets(repo_dir: &Path, update_out: &Path) -> Assert {
+    let root_json = test_utils::test_data().join("simple-rsa").join("root.json");
+    let root_key = test_utils::test_data().join("snakeoil.pem");
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::days(4))
+        .unwrap()
+        .to_rfc3339();
+    Command::cargo_bin("tuftool")
+        .unwrap()
+        .args(&[
+            "update",
+            "-o",
+            update_out.to_str().unwrap(),
+            "-k",
+            root_key.to_str().unwrap(),
+            "--root",
+            root_json.to_str().unwrap(),
+            "--metadata-url",
+            dir_url(repo_dir.join("metadata")).as_str(),
+            "--targets-expires",
+            &expiration,
+            "--targets-version",
+            "170",
+            "--snapshot-expires",
+            &expiration,
+            "--snapshot-version",
+            "250",
+            "--timestamp-expires",
+            &expiration,
+            "--timestamp-version",
+            "310",
+            "--check-targets",
+        ])
+        .assert()
+}
+
+#[test]
+// Ensure `--check-targets` refuses to write metadata for a repository with missing or altered
+// targets
+fn update_command_check_targets() {
+    let repo_dir = TempDir::new().unwrap();
+    create_repo(repo_dir.path());
+    let update_out = TempDir::new().unwrap();
+    let targets_out = update_out.path().join("targets");
+
+    // None of the targets are in the output directory
+    let output = update_checking_targets(repo_dir.path(), update_out.path())
+        .failure()
+        .get_output()
+        .clone();
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("is missing"));
+    assert!(!update_out.path().join("metadata").exists());
+
+    // All of the targets are present
+    std::fs::create_dir_all(&targets_out).unwrap();
+    for entry in std::fs::read_dir(repo_dir.path().join("targets")).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, targets_out.join(path.file_name().unwrap())).unwrap();
+    }
+    update_checking_targets(repo_dir.path(), update_out.path()).success();
+
+    // A target's contents no longer match its hash
+    let altered = std::fs::read_dir(&targets_out)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    std::fs::write(&altered, b"altered").unwrap();
+    let output = update_checking_targets(repo_dir.path(), update_out.path())
+        .failure()
+        .get_output()
+        .clone();
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Hash mismatch"));
+}